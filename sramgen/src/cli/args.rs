@@ -1,6 +1,13 @@
 use clap::Parser;
 use std::path::PathBuf;
 
+use crate::layout::lef::LefTool;
+use crate::liberty::Characterizer;
+use crate::verification::drc::DrcTool;
+use crate::verification::lvs::LvsTool;
+use crate::verification::patterns::Pattern;
+use crate::verification::SimulatorKind;
+
 #[derive(Parser, Debug)]
 #[command(
     author,
@@ -21,42 +28,112 @@ pub struct Args {
     pub output_dir: Option<PathBuf>,
 
     /// Generate LEF (used in place and route).
-    #[cfg(feature = "abstract_lef")]
     #[arg(long)]
     pub lef: bool,
 
+    /// Tool to use for generating LEF.
+    #[arg(long, value_enum, default_value_t)]
+    pub lef_tool: LefTool,
+
+    /// Generate DEF (used in place and route).
+    #[arg(long)]
+    pub def: bool,
+
     /// Generate LIB (setup, hold, and delay timing information).
-    #[cfg(feature = "liberate_mx")]
     #[arg(long)]
     pub lib: bool,
 
-    /// Run DRC using Calibre.
-    #[cfg(feature = "calibre")]
+    /// Tool to use for generating LIB.
+    #[arg(long, value_enum, default_value_t)]
+    pub characterizer: Characterizer,
+
+    /// Run DRC.
     #[arg(long)]
     pub drc: bool,
 
-    /// Run LVS using Calibre.
-    #[cfg(feature = "calibre")]
+    /// Tool to use for DRC.
+    #[arg(long, value_enum, default_value_t)]
+    pub drc_tool: DrcTool,
+
+    /// Run LVS.
     #[arg(long)]
     pub lvs: bool,
 
+    /// Tool to use for LVS.
+    #[arg(long, value_enum, default_value_t)]
+    pub lvs_tool: LvsTool,
+
     /// Run PEX using Calibre.
     #[cfg(all(feature = "calibre", feature = "pex"))]
     #[arg(long)]
     pub pex: bool,
 
-    /// Run Spectre to verify SRAM functionality.
-    #[cfg(feature = "spectre")]
+    /// Run a simulation to verify SRAM functionality.
     #[arg(long)]
     pub sim: bool,
 
+    /// Simulator to use for functional verification.
+    #[arg(long, value_enum, default_value_t)]
+    pub simulator: SimulatorKind,
+
+    /// Test pattern to apply during functional verification.
+    #[arg(long, value_enum, default_value_t)]
+    pub pattern: Pattern,
+
+    /// Apply randomly generated operations with the given seed instead of a test pattern.
+    #[arg(long, conflicts_with = "pattern")]
+    pub seed: Option<u64>,
+
+    /// Apply the test vectors in the given JSON or TOML file instead of a test pattern.
+    #[arg(long, conflicts_with_all = ["pattern", "seed"])]
+    pub test_vectors: Option<PathBuf>,
+
+    /// Number of random operations to generate.
+    #[arg(long, default_value_t = 100, requires = "seed")]
+    pub num_ops: usize,
+
+    /// Relative weights of random reads, writes, masked writes,
+    /// and optionally idle cycles.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        num_args = 3..=4,
+        default_value = "1,1,1",
+        requires = "seed"
+    )]
+    pub op_weights: Vec<f64>,
+
+    /// Maximum number of operations to simulate.
+    ///
+    /// Test patterns other than `basic` grow with the number of words.
+    #[arg(long, default_value_t = 10_000)]
+    pub max_ops: usize,
+
+    /// Probe internal SRAM nets during simulation to diagnose failed reads.
+    #[arg(long)]
+    pub probe_internal: bool,
+
+    /// Run a Monte Carlo analysis of the bitline split at sense amp enable using ngspice.
+    #[arg(long)]
+    pub read_margin: bool,
+
+    /// Number of Monte Carlo runs per corner.
+    #[arg(long, default_value_t = 100, requires = "read_margin")]
+    pub mc_runs: usize,
+
+    /// Random seed of the first Monte Carlo run.
+    #[arg(long, default_value_t = 1, requires = "read_margin")]
+    pub mc_seed: u64,
+
+    /// Delay from the wordline rising to the sense amp enable rising, in seconds.
+    #[arg(long, default_value_t = 1e-9, requires = "read_margin")]
+    pub sae_delay: f64,
+
+    /// Find the static noise margins of each bitcell using ngspice.
+    #[arg(long)]
+    pub snm: bool,
+
     /// Run all available steps.
-    #[cfg(any(
-        feature = "abstract_lef",
-        feature = "liberate_mx",
-        feature = "calibre",
-        feature = "spectre"
-    ))]
     #[arg(short, long)]
     pub all: bool,
 }
@@ -10,6 +10,10 @@ use crate::config::sram::parse_sram_config;
 use crate::paths::out_sram;
 use crate::plan::extract::ExtractionResult;
 use crate::plan::{execute_plan, generate_plan, ExecutePlanParams, TaskKey};
+use crate::verification::random::{OpWeights, RandomParams};
+use crate::verification::read_margin::ReadMarginParams;
+use crate::verification::vectors::parse_test_vectors;
+use crate::verification::{SimOptions, Stimulus};
 use crate::Result;
 
 pub mod args;
@@ -30,6 +34,7 @@ pub const BANNER: &str = r"
 
 pub fn run() -> Result<()> {
     let args = Args::parse();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
 
     let config_path = canonicalize(&args.config)?;
 
@@ -58,24 +63,16 @@ pub fn run() -> Result<()> {
     println!("\tControl mode: {:?}\n", config.control);
 
     let enabled_tasks = vec![
-        #[cfg(feature = "abstract_lef")]
         (args.lef, TaskKey::GenerateLef),
-        #[cfg(feature = "calibre")]
+        (args.def, TaskKey::GenerateDef),
         (args.drc, TaskKey::RunDrc),
-        #[cfg(feature = "calibre")]
         (args.lvs, TaskKey::RunLvs),
         #[cfg(all(feature = "calibre", feature = "pex"))]
         (args.pex, TaskKey::RunPex),
-        #[cfg(feature = "liberate_mx")]
+        (args.sim, TaskKey::RunSim),
+        (args.read_margin, TaskKey::RunReadMargin),
+        (args.snm, TaskKey::RunSnm),
         (args.lib, TaskKey::GenerateLib),
-        #[cfg(feature = "spectre")]
-        (args.sim, TaskKey::RunSpectre),
-        #[cfg(any(
-            feature = "abstract_lef",
-            feature = "liberate_mx",
-            feature = "calibre",
-            feature = "spectre"
-        ))]
         (args.all, TaskKey::All),
     ]
     .into_iter()
@@ -88,11 +85,44 @@ pub fn run() -> Result<()> {
     let plan = ctx.check(generate_plan(ExtractionResult {}, &config))?;
     ctx.finish(TaskKey::GeneratePlan);
 
+    let stimulus = match (args.test_vectors, args.seed) {
+        (Some(path), _) => Stimulus::File(parse_test_vectors(path)?),
+        (None, Some(seed)) => Stimulus::Random(
+            RandomParams::builder()
+                .seed(seed)
+                .num_ops(args.num_ops)
+                .weights(OpWeights {
+                    read: args.op_weights[0],
+                    write: args.op_weights[1],
+                    write_masked: args.op_weights[2],
+                    idle: args.op_weights.get(3).copied().unwrap_or_default(),
+                })
+                .build()?,
+        ),
+        (None, None) => Stimulus::Pattern(args.pattern),
+    };
+
     let res = execute_plan(ExecutePlanParams {
         work_dir: &work_dir,
         plan: &plan,
         tasks: &tasks,
         ctx: Some(&mut ctx),
+        sim: SimOptions {
+            simulator: args.simulator,
+            stimulus,
+            probe_internal: args.probe_internal,
+            max_ops: Some(args.max_ops),
+        },
+        lef_tool: args.lef_tool,
+        characterizer: args.characterizer,
+        drc_tool: args.drc_tool,
+        lvs_tool: args.lvs_tool,
+        read_margin: ReadMarginParams {
+            runs: args.mc_runs,
+            seed: args.mc_seed,
+            sae_delay: args.sae_delay,
+            ..Default::default()
+        },
     });
 
     ctx.check(res)?;
@@ -61,21 +61,24 @@ impl StepContext {
                 progress_bar: ProgressBar::new_spinner(),
                 disabled: false,
             },
-            #[cfg(feature = "abstract_lef")]
             Step {
                 desc: "Generate LEF".to_string(),
                 key: TaskKey::GenerateLef,
                 progress_bar: ProgressBar::new_spinner(),
                 disabled: !tasks.contains(&TaskKey::GenerateLef) && !tasks.contains(&TaskKey::All),
             },
-            #[cfg(feature = "calibre")]
+            Step {
+                desc: "Generate DEF".to_string(),
+                key: TaskKey::GenerateDef,
+                progress_bar: ProgressBar::new_spinner(),
+                disabled: !tasks.contains(&TaskKey::GenerateDef) && !tasks.contains(&TaskKey::All),
+            },
             Step {
                 desc: "Run DRC".to_string(),
                 key: TaskKey::RunDrc,
                 progress_bar: ProgressBar::new_spinner(),
                 disabled: !tasks.contains(&TaskKey::RunDrc) && !tasks.contains(&TaskKey::All),
             },
-            #[cfg(feature = "calibre")]
             Step {
                 desc: "Run LVS".to_string(),
                 key: TaskKey::RunLvs,
@@ -89,19 +92,30 @@ impl StepContext {
                 progress_bar: ProgressBar::new_spinner(),
                 disabled: !tasks.contains(&TaskKey::RunPex) && !tasks.contains(&TaskKey::All),
             },
-            #[cfg(feature = "liberate_mx")]
             Step {
-                desc: "Generate LIB".to_string(),
-                key: TaskKey::GenerateLib,
+                desc: "Run simulation".to_string(),
+                key: TaskKey::RunSim,
                 progress_bar: ProgressBar::new_spinner(),
-                disabled: !tasks.contains(&TaskKey::GenerateLib) && !tasks.contains(&TaskKey::All),
+                disabled: !tasks.contains(&TaskKey::RunSim) && !tasks.contains(&TaskKey::All),
+            },
+            Step {
+                desc: "Run read margin analysis".to_string(),
+                key: TaskKey::RunReadMargin,
+                progress_bar: ProgressBar::new_spinner(),
+                disabled: !tasks.contains(&TaskKey::RunReadMargin)
+                    && !tasks.contains(&TaskKey::All),
             },
-            #[cfg(feature = "spectre")]
             Step {
-                desc: "Run Spectre".to_string(),
-                key: TaskKey::RunSpectre,
+                desc: "Run SNM analysis".to_string(),
+                key: TaskKey::RunSnm,
                 progress_bar: ProgressBar::new_spinner(),
-                disabled: !tasks.contains(&TaskKey::RunSpectre) && !tasks.contains(&TaskKey::All),
+                disabled: !tasks.contains(&TaskKey::RunSnm) && !tasks.contains(&TaskKey::All),
+            },
+            Step {
+                desc: "Generate LIB".to_string(),
+                key: TaskKey::GenerateLib,
+                progress_bar: ProgressBar::new_spinner(),
+                disabled: !tasks.contains(&TaskKey::GenerateLib) && !tasks.contains(&TaskKey::All),
             },
         ];
         let mp = MultiProgress::new();
@@ -189,6 +203,11 @@ impl StepContext {
         }
     }
 
+    /// Prints a report produced by a task.
+    pub fn report(&self, report: impl Display) {
+        println!("\n{report}");
+    }
+
     pub fn done(&mut self) {
         println!("\n\nCompleted all tasks");
     }
@@ -6,9 +6,11 @@ pub mod dout_buffer;
 pub mod gate;
 pub mod inv_chain;
 pub mod mux;
+pub mod pins;
 pub mod precharge;
 pub mod sense_amp;
 pub mod sram;
+pub mod tech;
 pub mod tmc;
 pub mod wl_driver;
 pub mod wmask_control;
@@ -0,0 +1,157 @@
+use layout21::raw::Dir;
+use pdkprims::config::Int;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// An edge of a layout.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+impl Side {
+    #[inline]
+    pub fn dir(&self) -> Dir {
+        match *self {
+            Side::Left | Side::Right => Dir::Horiz,
+            Side::Bottom | Side::Top => Dir::Vert,
+        }
+    }
+
+    /// Indicates if this side is a positive-going direction
+    pub fn pos(&self) -> bool {
+        match *self {
+            Side::Left | Side::Bottom => false,
+            Side::Right | Side::Top => true,
+        }
+    }
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Left => write!(f, "left"),
+            Self::Right => write!(f, "right"),
+            Self::Bottom => write!(f, "bottom"),
+            Self::Top => write!(f, "top"),
+        }
+    }
+}
+
+/// A group of SRAM signal ports whose pins are placed together.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortGroup {
+    Addr,
+    Din,
+    Dout,
+    Wmask,
+    /// The clock and write enable.
+    Ctrl,
+}
+
+impl PortGroup {
+    pub const ALL: [PortGroup; 5] = [
+        PortGroup::Addr,
+        PortGroup::Din,
+        PortGroup::Dout,
+        PortGroup::Wmask,
+        PortGroup::Ctrl,
+    ];
+}
+
+impl Display for PortGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Addr => write!(f, "addr"),
+            Self::Din => write!(f, "din"),
+            Self::Dout => write!(f, "dout"),
+            Self::Wmask => write!(f, "wmask"),
+            Self::Ctrl => write!(f, "ctrl"),
+        }
+    }
+}
+
+/// The order of the pins of a bus along an edge.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitOrder {
+    /// Bit 0 is nearest the left or bottom end of the edge.
+    #[default]
+    LsbFirst,
+    /// The most significant bit is nearest the left or bottom end of the edge.
+    MsbFirst,
+}
+
+/// The placement of the pins of a port group.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PinGroupConfig {
+    /// The edge of the SRAM on which to place the pins.
+    pub side: Side,
+    /// The distance between the centers of adjacent pins, in nanometers.
+    /// Must be a multiple of the track pitch if the pins are aligned to tracks.
+    ///
+    /// Defaults to the minimum pin pitch on `side`, rounded up to a multiple
+    /// of the track pitch.
+    pub pitch: Option<Int>,
+    /// The distance from the left or bottom end of the edge to the center
+    /// of the first pin, in nanometers.
+    ///
+    /// Defaults to placing the pins after those of the preceding groups on `side`,
+    /// in the order `addr`, `din`, `dout`, `wmask`, `ctrl`.
+    pub offset: Option<Int>,
+    #[serde(default)]
+    pub order: BitOrder,
+}
+
+/// The routing tracks of a metal layer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrackConfig {
+    /// The distance between the centers of adjacent tracks, in nanometers.
+    pub pitch: Int,
+    /// The distance from the lower left corner of the SRAM to the center
+    /// of a track, in nanometers.
+    pub offset: Int,
+}
+
+/// The routing tracks to which the signal pins are aligned.
+///
+/// Pins on a layer without tracks are placed wherever their routes end.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PinTracks {
+    /// Horizontal tracks, which align the pins on the left and right edges.
+    pub m2: Option<TrackConfig>,
+    /// Vertical tracks, which align the pins on the top and bottom edges.
+    pub m3: Option<TrackConfig>,
+}
+
+/// The placement of the signal pins of an SRAM.
+///
+/// The pins of groups that are not listed are placed on the bottom edge,
+/// directly below the circuitry they connect to.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PinConfig {
+    pub addr: Option<PinGroupConfig>,
+    pub din: Option<PinGroupConfig>,
+    pub dout: Option<PinGroupConfig>,
+    pub wmask: Option<PinGroupConfig>,
+    pub ctrl: Option<PinGroupConfig>,
+    #[serde(default)]
+    pub tracks: PinTracks,
+}
+
+impl PinConfig {
+    pub fn group(&self, group: PortGroup) -> Option<&PinGroupConfig> {
+        match group {
+            PortGroup::Addr => self.addr.as_ref(),
+            PortGroup::Din => self.din.as_ref(),
+            PortGroup::Dout => self.dout.as_ref(),
+            PortGroup::Wmask => self.wmask.as_ref(),
+            PortGroup::Ctrl => self.ctrl.as_ref(),
+        }
+    }
+}
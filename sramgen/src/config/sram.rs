@@ -4,13 +4,40 @@ use std::fmt::Display;
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+use crate::config::pins::PinConfig;
+use crate::config::tech::TechOverrides;
+use crate::liberty::characterize::CharParams;
+use crate::schematic::NetlistFormat;
+use crate::verification::pvt::PvtCorner;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SramConfig {
     pub num_words: i32,
     pub data_width: i32,
     pub mux_ratio: i32,
     pub write_size: i32,
     pub control: ControlMode,
+    /// Netlist formats to generate. Defaults to all available formats.
+    #[serde(default = "default_netlist_formats")]
+    pub netlist_formats: Vec<NetlistFormat>,
+    /// Tech configuration selection and overrides.
+    #[serde(default)]
+    pub tech: TechOverrides,
+    /// Corners at which to simulate the SRAM.
+    ///
+    /// Defaults to the default process corner at nominal voltage and temperature.
+    #[serde(default)]
+    pub corners: Vec<PvtCorner>,
+    /// Placement of the signal pins on the edges of the SRAM.
+    #[serde(default)]
+    pub pins: PinConfig,
+    /// Options of the native Liberty characterization flow.
+    #[serde(default)]
+    pub lib: CharParams,
+}
+
+pub fn default_netlist_formats() -> Vec<NetlistFormat> {
+    NetlistFormat::ALL.to_vec()
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash, Serialize, Deserialize)]
@@ -49,10 +76,21 @@ pub struct SramParams {
     pub addr_width: usize,
 
     pub control: ControlMode,
+    pub pins: PinConfig,
+
+    // Netlists
+    pub netlist_formats: Vec<NetlistFormat>,
 }
 
 pub fn parse_sram_config(path: impl AsRef<Path>) -> Result<SramConfig> {
+    let path = path.as_ref();
     let contents = fs::read_to_string(path)?;
-    let data = toml::from_str(&contents)?;
+    let mut data: SramConfig = toml::from_str(&contents)?;
+
+    // Tech configuration paths are relative to the SRAM configuration file.
+    if let Some(dir) = path.parent() {
+        data.tech.resolve_paths(dir);
+    }
+
     Ok(data)
 }
@@ -0,0 +1,387 @@
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::verification::SimulatorKind;
+use crate::Result;
+
+/// Placeholder for the process corner in model paths and sections.
+pub const CORNER_PLACEHOLDER: &str = "{corner}";
+
+/// Tools, models and rule decks used to simulate and verify generated SRAMs.
+///
+/// Paths may reference environment variables as `${NAME}`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechConfig {
+    /// Process corners for which models are available.
+    pub corners: Vec<String>,
+    /// Process corner used when none is specified.
+    pub default_corner: String,
+    #[serde(default)]
+    pub spectre: SimModels,
+    #[serde(default)]
+    pub ngspice: SimModels,
+    #[serde(default)]
+    pub xyce: SimModels,
+    #[serde(default)]
+    pub calibre: RuleDecks,
+    /// Startup files (`.magicrc`) that load the technology into Magic
+    /// for DRC and for extracting layouts for LVS.
+    #[serde(default)]
+    pub magic: RuleDecks,
+    #[serde(default)]
+    pub klayout: RuleDecks,
+    #[serde(default)]
+    pub netgen: RuleDecks,
+    /// Rule tables for the built-in design rule checker.
+    #[serde(default)]
+    pub native: RuleDecks,
+    /// Capacitance of each SRAM input pin in farads, reported in Liberty files.
+    pub input_cap: Option<f64>,
+}
+
+/// Model files loaded by a simulator.
+///
+/// Paths and sections may contain [`CORNER_PLACEHOLDER`],
+/// which is replaced by the simulated process corner.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SimModels {
+    /// Files to include with `.include`.
+    #[serde(default)]
+    pub includes: Vec<String>,
+    /// Model libraries to load with `.lib`.
+    #[serde(default)]
+    pub libs: Vec<ModelLib>,
+    /// Model libraries loaded in place of `libs` by Monte Carlo
+    /// simulations with device mismatch.
+    #[serde(default)]
+    pub mismatch_libs: Vec<ModelLib>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ModelLib {
+    pub path: String,
+    pub section: String,
+}
+
+/// Physical verification rule decks for a single tool.
+///
+/// Relative waiver file paths are relative to the configuration file that declares them,
+/// as are relative rule deck paths in the `native` section.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct RuleDecks {
+    pub drc: Option<String>,
+    pub lvs: Option<String>,
+    pub pex: Option<String>,
+    /// A file listing DRC violations of the `drc` deck to waive.
+    pub drc_waivers: Option<String>,
+}
+
+/// The `[tech]` section of an SRAM configuration file.
+///
+/// Selects a tech configuration file, either by path or by preset name,
+/// and optionally overrides some of its entries.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechOverrides {
+    /// Name of a built-in tech configuration, such as `open_pdks`.
+    pub preset: Option<String>,
+    /// Path to a tech configuration file. Takes precedence over `preset`.
+    pub path: Option<PathBuf>,
+    pub corners: Option<Vec<String>>,
+    pub default_corner: Option<String>,
+    pub spectre: Option<SimModels>,
+    pub ngspice: Option<SimModels>,
+    pub xyce: Option<SimModels>,
+    pub calibre: Option<RuleDecks>,
+    pub magic: Option<RuleDecks>,
+    pub klayout: Option<RuleDecks>,
+    pub netgen: Option<RuleDecks>,
+    pub native: Option<RuleDecks>,
+    pub input_cap: Option<f64>,
+}
+
+impl TechOverrides {
+    /// Resolves relative paths in the overrides against `dir`,
+    /// the directory containing the SRAM configuration file.
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        if let Some(path) = self.path.as_mut() {
+            if path.is_relative() {
+                *path = dir.join(&path);
+            }
+        }
+        for decks in [
+            &mut self.calibre,
+            &mut self.magic,
+            &mut self.klayout,
+            &mut self.netgen,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            decks.resolve_paths(dir);
+        }
+        if let Some(native) = self.native.as_mut() {
+            native.resolve_all_paths(dir);
+        }
+    }
+}
+
+/// The preset used if the SRAM configuration does not select one.
+pub fn default_tech_preset() -> &'static str {
+    if cfg!(feature = "spectre") {
+        "bwrc"
+    } else {
+        "open_pdks"
+    }
+}
+
+pub fn tech_preset_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../tech/sky130/tools")
+        .join(format!("{name}.toml"))
+}
+
+pub fn parse_tech_config(path: impl AsRef<Path>) -> Result<TechConfig> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Error reading tech configuration {path:?}"))?;
+    let mut data: TechConfig = toml::from_str(&contents)
+        .with_context(|| format!("Error parsing tech configuration {path:?}"))?;
+    if let Some(dir) = path.parent() {
+        for decks in data.tool_rule_decks_mut() {
+            decks.resolve_paths(dir);
+        }
+        data.native.resolve_all_paths(dir);
+    }
+    Ok(data)
+}
+
+impl TechConfig {
+    /// Loads the tech configuration selected by `overrides`,
+    /// then applies the overrides.
+    pub fn load(overrides: &TechOverrides) -> Result<Self> {
+        let path = match (&overrides.path, &overrides.preset) {
+            (Some(path), _) => path.clone(),
+            (None, Some(preset)) => tech_preset_path(preset),
+            (None, None) => tech_preset_path(default_tech_preset()),
+        };
+        let mut tech = parse_tech_config(path)?;
+        tech.apply(overrides);
+        tech.validate()?;
+        Ok(tech)
+    }
+
+    pub fn apply(&mut self, overrides: &TechOverrides) {
+        let TechOverrides {
+            corners,
+            default_corner,
+            spectre,
+            ngspice,
+            xyce,
+            calibre,
+            magic,
+            klayout,
+            netgen,
+            native,
+            input_cap,
+            ..
+        } = overrides.clone();
+
+        if let Some(corners) = corners {
+            self.corners = corners;
+        }
+        if let Some(default_corner) = default_corner {
+            self.default_corner = default_corner;
+        }
+        if let Some(spectre) = spectre {
+            self.spectre = spectre;
+        }
+        if let Some(ngspice) = ngspice {
+            self.ngspice = ngspice;
+        }
+        if let Some(xyce) = xyce {
+            self.xyce = xyce;
+        }
+        if let Some(calibre) = calibre {
+            self.calibre = calibre;
+        }
+        if let Some(magic) = magic {
+            self.magic = magic;
+        }
+        if let Some(klayout) = klayout {
+            self.klayout = klayout;
+        }
+        if let Some(netgen) = netgen {
+            self.netgen = netgen;
+        }
+        if let Some(native) = native {
+            self.native = native;
+        }
+        if input_cap.is_some() {
+            self.input_cap = input_cap;
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.corners.is_empty() {
+            bail!("Tech configuration must declare at least one process corner");
+        }
+        self.check_corner(&self.default_corner)
+    }
+
+    pub fn check_corner(&self, corner: &str) -> Result<()> {
+        if !self.corners.iter().any(|c| c == corner) {
+            bail!(
+                "Unknown process corner `{}`; expected one of {:?}",
+                corner,
+                self.corners
+            );
+        }
+        Ok(())
+    }
+
+    /// The rule decks of external verification tools.
+    fn tool_rule_decks_mut(&mut self) -> [&mut RuleDecks; 4] {
+        [
+            &mut self.calibre,
+            &mut self.magic,
+            &mut self.klayout,
+            &mut self.netgen,
+        ]
+    }
+
+    pub fn models(&self, simulator: SimulatorKind) -> &SimModels {
+        match simulator {
+            SimulatorKind::Spectre => &self.spectre,
+            SimulatorKind::Ngspice => &self.ngspice,
+            SimulatorKind::Xyce => &self.xyce,
+        }
+    }
+}
+
+impl SimModels {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.includes.is_empty() && self.libs.is_empty()
+    }
+
+    /// The files to include when simulating at `corner`.
+    pub fn includes(&self, corner: &str) -> Result<Vec<String>> {
+        self.includes
+            .iter()
+            .map(|path| expand_path(path, corner))
+            .collect()
+    }
+
+    /// The libraries to load when simulating at `corner`, as `(path, section)` pairs.
+    pub fn libs(&self, corner: &str) -> Result<Vec<(String, String)>> {
+        expand_libs(&self.libs, corner)
+    }
+
+    /// The libraries to load when simulating at `corner` with device mismatch,
+    /// as `(path, section)` pairs.
+    pub fn mismatch_libs(&self, corner: &str) -> Result<Vec<(String, String)>> {
+        expand_libs(&self.mismatch_libs, corner)
+    }
+}
+
+fn expand_libs(libs: &[ModelLib], corner: &str) -> Result<Vec<(String, String)>> {
+    libs.iter()
+        .map(|lib| {
+            Ok((
+                expand_path(&lib.path, corner)?,
+                lib.section.replace(CORNER_PLACEHOLDER, corner),
+            ))
+        })
+        .collect()
+}
+
+impl RuleDecks {
+    pub fn drc(&self) -> Result<PathBuf> {
+        rule_deck(self.drc.as_deref(), "DRC")
+    }
+
+    pub fn lvs(&self) -> Result<PathBuf> {
+        rule_deck(self.lvs.as_deref(), "LVS")
+    }
+
+    pub fn pex(&self) -> Result<PathBuf> {
+        rule_deck(self.pex.as_deref(), "PEX")
+    }
+
+    /// The DRC waiver file, if one is specified.
+    pub fn drc_waivers(&self) -> Result<Option<PathBuf>> {
+        self.drc_waivers
+            .as_deref()
+            .map(|path| Ok(PathBuf::from(expand_env(path)?)))
+            .transpose()
+    }
+
+    /// Makes the relative path to the waiver file relative to `dir` instead.
+    ///
+    /// Rule deck paths are passed to external tools as is.
+    /// Paths beginning with an environment variable are left as is.
+    fn resolve_paths(&mut self, dir: &Path) {
+        if let Some(path) = self.drc_waivers.as_mut() {
+            resolve_path(path, dir);
+        }
+    }
+
+    /// Makes the relative paths to the rule decks and the waiver file
+    /// relative to `dir` instead.
+    ///
+    /// Used for the rule tables of the built-in checkers,
+    /// which are usually kept alongside the tech configuration.
+    fn resolve_all_paths(&mut self, dir: &Path) {
+        for path in [
+            &mut self.drc,
+            &mut self.lvs,
+            &mut self.pex,
+            &mut self.drc_waivers,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve_path(path, dir);
+        }
+    }
+}
+
+/// Makes `path` relative to `dir` if it is relative and does not begin with an
+/// environment variable.
+fn resolve_path(path: &mut String, dir: &Path) {
+    if !path.starts_with("${") && Path::new(path.as_str()).is_relative() {
+        *path = dir.join(path.as_str()).to_string_lossy().into_owned();
+    }
+}
+
+fn rule_deck(path: Option<&str>, kind: &str) -> Result<PathBuf> {
+    let path =
+        path.ok_or_else(|| anyhow!("No {kind} rule deck specified in the tech configuration"))?;
+    Ok(PathBuf::from(expand_env(path)?))
+}
+
+fn expand_path(path: &str, corner: &str) -> Result<String> {
+    expand_env(&path.replace(CORNER_PLACEHOLDER, corner))
+}
+
+/// Replaces every `${NAME}` in `s` with the value of the environment variable `NAME`.
+pub fn expand_env(s: &str) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let len = rest[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated environment variable in `{}`", s))?;
+        let var = &rest[start + 2..start + len];
+        let value = std::env::var(var).with_context(|| {
+            format!("Environment variable `{var}` referenced by `{s}` is not set")
+        })?;
+        out.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
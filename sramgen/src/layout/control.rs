@@ -128,6 +128,15 @@ pub fn draw_control_logic_simple(lib: &mut PdkLib) -> Result<Ptr<Cell>> {
         .named("vss1");
     cell.add_pin_from_port(port, m1);
 
+    let port = MergeArgs::builder()
+        .layer(m1)
+        .insts(GateList::Cells(&cell.layout().insts))
+        .port_name("vpwr")
+        .build()?
+        .port()
+        .named("vdd1");
+    cell.add_pin_from_port(port, m1);
+
     cell.abs_mut().add_port(delay_chain.port("m2_block"));
 
     cell.layout_mut().add_inst(inv);
@@ -0,0 +1,92 @@
+//! DEF views of generated layouts, for place and route.
+//!
+//! The DEF view of a cell has the same die area, pins, and obstructions as its
+//! [LEF abstract](crate::layout::lef), with obstructions written as routing blockages.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::layout::drc::geometry::DBU_PER_MICRON;
+use crate::layout::lef::LefMacro;
+use crate::Result;
+
+pub fn save_def(abs: &LefMacro, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, to_def(abs)?)?;
+    Ok(())
+}
+
+/// Formats the abstract `abs` as a DEF 5.8 design.
+///
+/// As in the LEF abstract, coordinates are shifted so that the lower left corner
+/// of the bounding box is the origin. Each shape of a pin is a separate `PORT`.
+pub fn to_def(abs: &LefMacro) -> Result<String> {
+    let mut out = String::new();
+    let (dx, dy) = (abs.bbox.x0, abs.bbox.y0);
+
+    writeln!(out, "VERSION 5.8 ;")?;
+    writeln!(out, "DIVIDERCHAR \"/\" ;")?;
+    writeln!(out, "BUSBITCHARS \"[]\" ;")?;
+    writeln!(out, "DESIGN {} ;", abs.name)?;
+    writeln!(out, "UNITS DISTANCE MICRONS {} ;", DBU_PER_MICRON)?;
+    writeln!(
+        out,
+        "DIEAREA ( 0 0 ) ( {} {} ) ;",
+        abs.bbox.width(),
+        abs.bbox.height()
+    )?;
+    writeln!(out)?;
+
+    writeln!(out, "PINS {} ;", abs.pins.len())?;
+    for pin in abs.pins.iter() {
+        writeln!(
+            out,
+            "  - {} + NET {} + DIRECTION {} + USE {}",
+            pin.name, pin.name, pin.direction, pin.use_
+        )?;
+        for (layer, rects) in pin.shapes.iter() {
+            for r in rects.iter() {
+                writeln!(out, "    + PORT")?;
+                writeln!(
+                    out,
+                    "      + LAYER {layer} ( 0 0 ) ( {} {} )",
+                    r.width(),
+                    r.height()
+                )?;
+                writeln!(out, "      + FIXED ( {} {} ) N", r.x0 - dx, r.y0 - dy)?;
+            }
+        }
+        writeln!(out, "  ;")?;
+    }
+    writeln!(out, "END PINS")?;
+    writeln!(out)?;
+
+    let blockages = abs
+        .obstructions
+        .iter()
+        .filter(|(_, rects)| !rects.is_empty())
+        .collect::<Vec<_>>();
+    writeln!(out, "BLOCKAGES {} ;", blockages.len())?;
+    for (layer, rects) in blockages {
+        writeln!(out, "  - LAYER {layer}")?;
+        for r in rects.iter() {
+            writeln!(
+                out,
+                "    RECT ( {} {} ) ( {} {} )",
+                r.x0 - dx,
+                r.y0 - dy,
+                r.x1 - dx,
+                r.y1 - dy
+            )?;
+        }
+        writeln!(out, "  ;")?;
+    }
+    writeln!(out, "END BLOCKAGES")?;
+    writeln!(out)?;
+
+    writeln!(out, "END DESIGN")?;
+    Ok(out)
+}
@@ -0,0 +1,79 @@
+//! Flattening of hierarchical layouts into rectangles per layer.
+
+use layout21::raw::{Cell, LayerKey, LayerPurpose, Shape};
+use layout21::utils::Ptr;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::layout::drc::geometry::{path_regions, polygon_regions, Placement, Region};
+use crate::Result;
+
+/// The drawn rectangles of a cell and its instances, by layer.
+pub type FlatShapes = HashMap<LayerKey, Vec<Region>>;
+
+/// Flattens cells, reusing the shapes of cells that are instantiated many times.
+pub struct Flattener<'a> {
+    /// The layers to keep; shapes on other layers are dropped.
+    layers: &'a HashSet<LayerKey>,
+    cache: HashMap<String, Rc<FlatShapes>>,
+}
+
+impl<'a> Flattener<'a> {
+    pub fn new(layers: &'a HashSet<LayerKey>) -> Self {
+        Self {
+            layers,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The drawn shapes of `cell` and everything it instantiates, in the coordinates of `cell`.
+    ///
+    /// Polygons and paths are decomposed into rectangles.
+    /// Shapes with edges that are neither horizontal nor vertical are dropped.
+    pub fn flatten(&mut self, cell: &Ptr<Cell>) -> Result<Rc<FlatShapes>> {
+        let cell = cell.read().unwrap();
+        if let Some(shapes) = self.cache.get(&cell.name) {
+            return Ok(Rc::clone(shapes));
+        }
+
+        let mut shapes = FlatShapes::new();
+        if let Some(ref layout) = cell.layout {
+            for elem in layout.elems.iter() {
+                if !matches!(elem.purpose, LayerPurpose::Drawing)
+                    || !self.layers.contains(&elem.layer)
+                {
+                    continue;
+                }
+                let regions = shape_regions(&elem.inner).unwrap_or_default();
+                shapes.entry(elem.layer).or_default().extend(regions);
+            }
+
+            for inst in layout.insts.iter() {
+                let placement = Placement::from_instance(inst)?;
+                let child = self.flatten(&inst.cell)?;
+                for (layer, regions) in child.iter() {
+                    shapes
+                        .entry(*layer)
+                        .or_default()
+                        .extend(regions.iter().map(|r| placement.apply(r)));
+                }
+            }
+        }
+
+        let shapes = Rc::new(shapes);
+        self.cache.insert(cell.name.clone(), Rc::clone(&shapes));
+        Ok(shapes)
+    }
+}
+
+/// Decomposes a shape into rectangles, or returns `None` if it is not Manhattan.
+///
+/// Points have no area, and are dropped.
+pub(crate) fn shape_regions(shape: &Shape) -> Option<Vec<Region>> {
+    match shape {
+        Shape::Rect(r) => Some(vec![Region::new(r.left(), r.bottom(), r.right(), r.top())]),
+        Shape::Polygon(p) => polygon_regions(&p.points),
+        Shape::Path(p) => path_regions(&p.points, p.width as _),
+        _ => Some(Vec::new()),
+    }
+}
@@ -0,0 +1,454 @@
+//! Manhattan geometry used by the design rule checker, the layout extractor, and the LEF writer.
+
+use anyhow::bail;
+use layout21::raw::{Instance, Int, Point};
+use std::collections::HashMap;
+
+use crate::verification::drc::Bbox;
+use crate::Result;
+
+/// Layout database units per micron.
+pub const DBU_PER_MICRON: f64 = 1_000.0;
+
+/// An axis-aligned rectangle, in layout database units.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Region {
+    pub x0: Int,
+    pub y0: Int,
+    pub x1: Int,
+    pub y1: Int,
+}
+
+impl Region {
+    /// The rectangle with corners `(x0, y0)` and `(x1, y1)`, in any order.
+    pub fn new(x0: Int, y0: Int, x1: Int, y1: Int) -> Self {
+        Self {
+            x0: x0.min(x1),
+            y0: y0.min(y1),
+            x1: x0.max(x1),
+            y1: y0.max(y1),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> Int {
+        self.x1 - self.x0
+    }
+
+    #[inline]
+    pub fn height(&self) -> Int {
+        self.y1 - self.y0
+    }
+
+    #[inline]
+    pub fn area(&self) -> Int {
+        self.width() * self.height()
+    }
+
+    /// The overlap of two rectangles, if it has a positive area.
+    pub fn intersection(&self, other: &Region) -> Option<Region> {
+        let r = Region {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1),
+            y1: self.y1.min(other.y1),
+        };
+        if r.x0 < r.x1 && r.y0 < r.y1 {
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    /// The parts of the rectangle not covered by `other`, as disjoint rectangles.
+    pub fn difference(&self, other: &Region) -> Vec<Region> {
+        let cut = match self.intersection(other) {
+            Some(cut) => cut,
+            None => return vec![*self],
+        };
+        let mut out = Vec::new();
+        if self.y0 < cut.y0 {
+            out.push(Region::new(self.x0, self.y0, self.x1, cut.y0));
+        }
+        if cut.y1 < self.y1 {
+            out.push(Region::new(self.x0, cut.y1, self.x1, self.y1));
+        }
+        if self.x0 < cut.x0 {
+            out.push(Region::new(self.x0, cut.y0, cut.x0, cut.y1));
+        }
+        if cut.x1 < self.x1 {
+            out.push(Region::new(cut.x1, cut.y0, self.x1, cut.y1));
+        }
+        out
+    }
+
+    /// Whether the rectangles overlap or share part of an edge.
+    ///
+    /// Rectangles that only meet at a corner do not touch.
+    pub fn touches(&self, other: &Region) -> bool {
+        let dx = self.x1.min(other.x1) - self.x0.max(other.x0);
+        let dy = self.y1.min(other.y1) - self.y0.max(other.y0);
+        dx >= 0 && dy >= 0 && (dx > 0 || dy > 0)
+    }
+
+    /// Grows the rectangle by `dx` on its left and right and by `dy` on its top and bottom.
+    pub fn expand(&self, dx: Int, dy: Int) -> Region {
+        Region {
+            x0: self.x0 - dx,
+            y0: self.y0 - dy,
+            x1: self.x1 + dx,
+            y1: self.y1 + dy,
+        }
+    }
+
+    /// Swaps the x and y axes.
+    #[inline]
+    fn transpose(&self) -> Region {
+        Region {
+            x0: self.y0,
+            y0: self.x0,
+            x1: self.y1,
+            y1: self.x1,
+        }
+    }
+
+    /// The smallest rectangle containing both rectangles.
+    fn union(&self, other: &Region) -> Region {
+        Region {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
+    /// The rectangle in microns.
+    pub fn to_bbox(&self) -> Bbox {
+        Bbox {
+            x0: self.x0 as f64 / DBU_PER_MICRON,
+            y0: self.y0 as f64 / DBU_PER_MICRON,
+            x1: self.x1 as f64 / DBU_PER_MICRON,
+            y1: self.y1 as f64 / DBU_PER_MICRON,
+        }
+    }
+}
+
+/// A horizontal strip of a union of rectangles,
+/// within which the union is a fixed set of disjoint intervals.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Band {
+    pub y0: Int,
+    pub y1: Int,
+    /// The disjoint intervals covered by the union, sorted from left to right.
+    pub intervals: Vec<(Int, Int)>,
+}
+
+/// Decomposes the union of `rects` into horizontal bands, from bottom to top.
+///
+/// Adjacent bands covering the same intervals are merged,
+/// so the intervals of each band are as tall as possible.
+pub fn bands(rects: &[Region]) -> Vec<Band> {
+    let mut ys = rects.iter().flat_map(|r| [r.y0, r.y1]).collect::<Vec<_>>();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let mut sorted = rects
+        .iter()
+        .filter(|r| r.x0 < r.x1 && r.y0 < r.y1)
+        .collect::<Vec<_>>();
+    sorted.sort_unstable_by_key(|r| r.y0);
+
+    let mut out: Vec<Band> = Vec::new();
+    let mut active: Vec<&Region> = Vec::new();
+    let mut next = 0;
+    for w in ys.windows(2) {
+        let (y0, y1) = (w[0], w[1]);
+        active.retain(|r| r.y1 > y0);
+        while next < sorted.len() && sorted[next].y0 <= y0 {
+            if sorted[next].y1 > y0 {
+                active.push(sorted[next]);
+            }
+            next += 1;
+        }
+
+        let mut spans = active.iter().map(|r| (r.x0, r.x1)).collect::<Vec<_>>();
+        spans.sort_unstable();
+        let mut intervals: Vec<(Int, Int)> = Vec::new();
+        for (x0, x1) in spans {
+            match intervals.last_mut() {
+                Some(last) if x0 <= last.1 => last.1 = last.1.max(x1),
+                _ => intervals.push((x0, x1)),
+            }
+        }
+        if intervals.is_empty() {
+            continue;
+        }
+
+        match out.last_mut() {
+            Some(last) if last.y1 == y0 && last.intervals == intervals => last.y1 = y1,
+            _ => out.push(Band { y0, y1, intervals }),
+        }
+    }
+    out
+}
+
+/// Decomposes the union of `rects` into vertical bands, from left to right.
+///
+/// The returned bands are transposed: their `y0` and `y1` are x coordinates,
+/// and their intervals are spans of y coordinates.
+pub fn vertical_bands(rects: &[Region]) -> Vec<Band> {
+    bands(&rects.iter().map(Region::transpose).collect::<Vec<_>>())
+}
+
+/// The rectangles covered by the intervals of `bands`.
+///
+/// The rectangles do not overlap.
+pub fn band_regions(bands: &[Band]) -> Vec<Region> {
+    bands
+        .iter()
+        .flat_map(|b| {
+            b.intervals
+                .iter()
+                .map(move |&(x0, x1)| Region::new(x0, b.y0, x1, b.y1))
+        })
+        .collect()
+}
+
+/// Transposes regions found in [`vertical_bands`] back to layout coordinates.
+pub fn untranspose(regions: Vec<Region>) -> Vec<Region> {
+    regions.iter().map(Region::transpose).collect()
+}
+
+/// A connected piece of a union of rectangles.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Component {
+    pub bbox: Region,
+    pub area: Int,
+}
+
+/// Finds the connected components of the union of the intervals of `bands`.
+///
+/// Pieces that only touch at a corner are separate components.
+pub fn components(bands: &[Band]) -> Vec<Component> {
+    let regions = band_regions(bands);
+    let mut parent = (0..regions.len()).collect::<Vec<_>>();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    // Regions of touching bands are connected if their intervals overlap.
+    let mut start = 0;
+    for w in bands.windows(2) {
+        let n = w[0].intervals.len();
+        if w[0].y1 == w[1].y0 {
+            for (a, &(a0, a1)) in w[0].intervals.iter().enumerate() {
+                for (b, &(b0, b1)) in w[1].intervals.iter().enumerate() {
+                    if a0 < b1 && b0 < a1 {
+                        let ra = find(&mut parent, start + a);
+                        let rb = find(&mut parent, start + n + b);
+                        parent[ra] = rb;
+                    }
+                }
+            }
+        }
+        start += n;
+    }
+
+    let mut out: HashMap<usize, Component> = HashMap::new();
+    for (i, r) in regions.iter().enumerate() {
+        let root = find(&mut parent, i);
+        out.entry(root)
+            .and_modify(|c| {
+                c.bbox = c.bbox.union(r);
+                c.area += r.area();
+            })
+            .or_insert(Component {
+                bbox: *r,
+                area: r.area(),
+            });
+    }
+    let mut out = out.into_values().collect::<Vec<_>>();
+    out.sort_unstable_by_key(|c| (c.bbox.y0, c.bbox.x0));
+    out
+}
+
+/// Rectangles bucketed by location, for fast overlap queries.
+pub struct RegionIndex {
+    regions: Vec<Region>,
+    buckets: HashMap<(Int, Int), Vec<usize>>,
+}
+
+/// The side of a bucket in a [`RegionIndex`].
+const BUCKET_SIZE: Int = 2_000;
+
+impl RegionIndex {
+    /// Indexes `regions`.
+    ///
+    /// Area queries assume that the regions do not overlap each other.
+    pub fn new(regions: Vec<Region>) -> Self {
+        let mut buckets: HashMap<(Int, Int), Vec<usize>> = HashMap::new();
+        for (i, r) in regions.iter().enumerate() {
+            for key in bucket_keys(r) {
+                buckets.entry(key).or_default().push(i);
+            }
+        }
+        Self { regions, buckets }
+    }
+
+    /// The area of `query` covered by the indexed regions.
+    pub fn covered_area(&self, query: &Region) -> Int {
+        self.overlapping(query)
+            .into_iter()
+            .filter_map(|i| self.regions[i].intersection(query))
+            .map(|r| r.area())
+            .sum()
+    }
+
+    /// Whether `query` is entirely covered by the indexed regions.
+    #[inline]
+    pub fn covers(&self, query: &Region) -> bool {
+        self.covered_area(query) == query.area()
+    }
+
+    /// Whether no indexed region overlaps `query`.
+    #[inline]
+    pub fn is_clear(&self, query: &Region) -> bool {
+        self.covered_area(query) == 0
+    }
+
+    /// The indices of the indexed regions that touch `query`.
+    pub fn touching(&self, query: &Region) -> Vec<usize> {
+        let mut out = self.overlapping(query);
+        out.retain(|&i| self.regions[i].touches(query));
+        out
+    }
+
+    /// The indices of the indexed regions sharing a bucket with `query`.
+    fn overlapping(&self, query: &Region) -> Vec<usize> {
+        let mut out = bucket_keys(query)
+            .filter_map(|key| self.buckets.get(&key))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+}
+
+fn bucket_keys(r: &Region) -> impl Iterator<Item = (Int, Int)> {
+    let (bx0, bx1) = (r.x0.div_euclid(BUCKET_SIZE), r.x1.div_euclid(BUCKET_SIZE));
+    let (by0, by1) = (r.y0.div_euclid(BUCKET_SIZE), r.y1.div_euclid(BUCKET_SIZE));
+    (bx0..=bx1).flat_map(move |bx| (by0..=by1).map(move |by| (bx, by)))
+}
+
+/// Decomposes a rectilinear polygon into rectangles.
+///
+/// Returns `None` if the polygon has edges that are neither horizontal nor vertical.
+pub fn polygon_regions(points: &[Point]) -> Option<Vec<Region>> {
+    let n = points.len();
+    let edges = (0..n).map(|i| (&points[i], &points[(i + 1) % n]));
+    let mut verticals = Vec::new();
+    for (a, b) in edges {
+        if a.x == b.x {
+            if a.y != b.y {
+                verticals.push((a.x, a.y.min(b.y), a.y.max(b.y)));
+            }
+        } else if a.y != b.y {
+            return None;
+        }
+    }
+
+    let mut ys = points.iter().map(|p| p.y).collect::<Vec<_>>();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let mut out = Vec::new();
+    for w in ys.windows(2) {
+        let (y0, y1) = (w[0], w[1]);
+        // Vertical edges crossing the band alternately enter and leave the polygon.
+        let mut xs = verticals
+            .iter()
+            .filter(|&&(_, e0, e1)| e0 <= y0 && y1 <= e1)
+            .map(|&(x, _, _)| x)
+            .collect::<Vec<_>>();
+        xs.sort_unstable();
+        for pair in xs.chunks_exact(2) {
+            out.push(Region::new(pair[0], y0, pair[1], y1));
+        }
+    }
+    Some(out)
+}
+
+/// Decomposes a path of horizontal and vertical segments into rectangles,
+/// one per segment, without extending the ends of the path.
+///
+/// Returns `None` if the path has diagonal segments.
+pub fn path_regions(points: &[Point], width: Int) -> Option<Vec<Region>> {
+    let half = width / 2;
+    points
+        .windows(2)
+        .map(|w| {
+            let (a, b) = (&w[0], &w[1]);
+            if a.y == b.y {
+                Some(Region::new(a.x, a.y - half, b.x, a.y + width - half))
+            } else if a.x == b.x {
+                Some(Region::new(a.x - half, a.y, a.x + width - half, b.y))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The placement of an instance: a reflection about the x axis, if any,
+/// followed by a rotation by a multiple of 90 degrees and a translation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Placement {
+    reflect_vert: bool,
+    quarter_turns: u8,
+    dx: Int,
+    dy: Int,
+}
+
+impl Placement {
+    pub fn from_instance(inst: &Instance) -> Result<Self> {
+        let angle = inst.angle.unwrap_or(0.0);
+        let turns = (angle / 90.0).round();
+        if (angle - 90.0 * turns).abs() > 1e-6 {
+            bail!(
+                "Instance `{}` is rotated by {} degrees; only multiples of 90 degrees are supported",
+                inst.inst_name,
+                angle
+            );
+        }
+        Ok(Self {
+            reflect_vert: inst.reflect_vert,
+            quarter_turns: (turns as i64).rem_euclid(4) as u8,
+            dx: inst.loc.x,
+            dy: inst.loc.y,
+        })
+    }
+
+    fn apply_point(&self, (x, y): (Int, Int)) -> (Int, Int) {
+        let y = if self.reflect_vert { -y } else { y };
+        let (x, y) = match self.quarter_turns {
+            0 => (x, y),
+            1 => (-y, x),
+            2 => (-x, -y),
+            _ => (y, -x),
+        };
+        (x + self.dx, y + self.dy)
+    }
+
+    pub fn apply(&self, r: &Region) -> Region {
+        let (x0, y0) = self.apply_point((r.x0, r.y0));
+        let (x1, y1) = self.apply_point((r.x1, r.y1));
+        Region::new(x0, y0, x1, y1)
+    }
+}
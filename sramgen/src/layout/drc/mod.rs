@@ -0,0 +1,308 @@
+//! A lightweight design rule checker for generated layouts.
+//!
+//! Layouts are flattened, and each layer is checked against the minimum width,
+//! minimum spacing, via size, and enclosure rules in a [`DrcRules`] table.
+//! Only Manhattan geometry is checked; other shapes are ignored. The checker is much faster than a signoff
+//! DRC tool, but does not replace one: rules that are not in the table,
+//! such as rules involving several layers other than enclosures, are not checked.
+
+use anyhow::{anyhow, Context};
+use layout21::gds21::GdsLibrary;
+use layout21::raw::{Cell, Int, LayerKey, Library};
+use layout21::utils::Ptr;
+use pdkprims::tech::sky130;
+use pdkprims::Pdk;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::layout::drc::flatten::Flattener;
+use crate::layout::drc::geometry::{
+    band_regions, bands, components, untranspose, vertical_bands, Band, Region, RegionIndex,
+    DBU_PER_MICRON,
+};
+use crate::verification::drc::{DrcResult, DrcTool, DrcViolation, DrcWaivers};
+use crate::Result;
+
+pub mod flatten;
+pub mod geometry;
+
+/// A table of design rules, by layer.
+///
+/// Rule tables are TOML files listing the rules of each layer as a `[[layer]]` table.
+/// Dimensions are in layout database units (nanometers).
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DrcRules {
+    #[serde(default, rename = "layer")]
+    pub layers: Vec<LayerRules>,
+}
+
+/// The design rules of a single layer.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LayerRules {
+    /// The name of the layer in the PDK.
+    pub name: String,
+    /// Minimum width.
+    pub width: Option<Int>,
+    /// Minimum spacing between shapes, including between the corners of diagonally placed shapes.
+    pub space: Option<Int>,
+    /// Exact width and height of every shape, as required of via and contact cuts.
+    pub size: Option<Int>,
+    /// Layers that must enclose every shape on this layer.
+    #[serde(default)]
+    pub enclosures: Vec<EnclosureRule>,
+}
+
+/// Requires shapes to be enclosed by another layer.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EnclosureRule {
+    /// The enclosing layer.
+    pub layer: String,
+    /// Minimum enclosure on all sides.
+    pub enclosure: Int,
+    /// Minimum enclosure on both sides in at least one direction,
+    /// either horizontally or vertically.
+    pub one_side: Option<Int>,
+}
+
+impl DrcRules {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading DRC rule table {path:?}"))?;
+        let rules = toml::from_str(&contents)
+            .with_context(|| format!("Error parsing DRC rule table {path:?}"))?;
+        Ok(rules)
+    }
+
+    /// The built-in SKY130 rule table.
+    pub fn sky130() -> Result<Self> {
+        Self::load(sky130_drc_rules_path())
+    }
+}
+
+pub fn sky130_drc_rules_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tech/sky130/drc_rules.toml")
+}
+
+/// Checks the layout of `cell` against `rules`.
+///
+/// The returned violations are located in the coordinates of `cell`.
+pub fn check_drc(pdk: &Pdk, cell: &Ptr<Cell>, rules: &DrcRules) -> Result<DrcResult> {
+    let layer_key = |name: &str| {
+        pdk.get_layerkey(name)
+            .ok_or_else(|| anyhow!("DRC rule table references unknown layer `{name}`"))
+    };
+
+    let mut keys = HashMap::new();
+    for layer in rules.layers.iter() {
+        keys.insert(layer.name.as_str(), layer_key(&layer.name)?);
+        for enclosure in layer.enclosures.iter() {
+            keys.insert(enclosure.layer.as_str(), layer_key(&enclosure.layer)?);
+        }
+    }
+    let layers = keys.values().copied().collect::<HashSet<LayerKey>>();
+
+    let name = cell.read().unwrap().name.clone();
+    let shapes = Flattener::new(&layers).flatten(cell)?;
+    let no_shapes = Vec::new();
+    let shapes_on = |name: &str| shapes.get(&keys[name]).unwrap_or(&no_shapes);
+
+    let mut violations = Vec::new();
+    for layer in rules.layers.iter() {
+        let rects = shapes_on(&layer.name);
+        if rects.is_empty() {
+            continue;
+        }
+        let horiz = bands(rects);
+
+        if let Some(width) = layer.width {
+            check_width(&mut violations, layer, width, &horiz, rects);
+        }
+        if let Some(space) = layer.space {
+            check_space(&mut violations, layer, space, &horiz, rects);
+        }
+        if let Some(size) = layer.size {
+            check_size(&mut violations, layer, size, &horiz);
+        }
+        for enclosure in layer.enclosures.iter() {
+            let enclosing = RegionIndex::new(band_regions(&bands(shapes_on(&enclosure.layer))));
+            check_enclosure(&mut violations, layer, enclosure, &horiz, &enclosing);
+        }
+    }
+
+    Ok(DrcResult::new(
+        DrcTool::Native,
+        name,
+        violations,
+        &DrcWaivers::default(),
+    ))
+}
+
+/// Checks the layout of the cell `name` in the GDS file at `layout_path` against `rules`.
+pub fn check_gds(layout_path: impl AsRef<Path>, name: &str, rules: &DrcRules) -> Result<DrcResult> {
+    let layout_path = layout_path.as_ref();
+    let pdk_lib = sky130::pdk_lib(name)?;
+    let gds = GdsLibrary::load(layout_path)
+        .with_context(|| format!("Error reading GDS layout {layout_path:?}"))?;
+    let lib = Library::from_gds(&gds, Some(pdk_lib.pdk.layers.clone()))?;
+    let cell = lib
+        .cell(name)
+        .ok_or_else(|| anyhow!("GDS layout {layout_path:?} does not contain cell `{name}`"))?;
+    check_drc(&pdk_lib.pdk, &cell, rules)
+}
+
+fn violation(rule: String, description: String, region: &Region) -> DrcViolation {
+    DrcViolation {
+        rule,
+        description,
+        cell: None,
+        bbox: Some(region.to_bbox()),
+    }
+}
+
+/// Formats a dimension in microns.
+fn um(x: Int) -> String {
+    format!("{}um", x as f64 / DBU_PER_MICRON)
+}
+
+/// Finds pieces of the layer narrower than `width`, measuring horizontally and vertically.
+fn check_width(
+    violations: &mut Vec<DrcViolation>,
+    layer: &LayerRules,
+    width: Int,
+    horiz: &[Band],
+    rects: &[Region],
+) {
+    let narrow = |bands: &[Band]| {
+        band_regions(bands)
+            .into_iter()
+            .filter(|r| r.width() < width)
+            .collect::<Vec<_>>()
+    };
+    let mut regions = narrow(horiz);
+    regions.extend(untranspose(narrow(&vertical_bands(rects))));
+
+    let rule = format!("{}.width", layer.name);
+    let description = format!("Minimum {} width is {}", layer.name, um(width));
+    violations.extend(
+        merge(regions)
+            .iter()
+            .map(|r| violation(rule.clone(), description.clone(), r)),
+    );
+}
+
+/// Finds gaps between shapes narrower than `space`, measuring horizontally, vertically,
+/// and between the corners of diagonally placed shapes.
+fn check_space(
+    violations: &mut Vec<DrcViolation>,
+    layer: &LayerRules,
+    space: Int,
+    horiz: &[Band],
+    rects: &[Region],
+) {
+    let gaps = |bands: &[Band]| {
+        bands
+            .iter()
+            .flat_map(|b| {
+                b.intervals
+                    .windows(2)
+                    .map(move |w| Region::new(w[0].1, b.y0, w[1].0, b.y1))
+            })
+            .filter(|r| r.width() < space)
+            .collect::<Vec<_>>()
+    };
+    let mut regions = gaps(horiz);
+    regions.extend(untranspose(gaps(&vertical_bands(rects))));
+
+    // Corner to corner spacing is measured diagonally,
+    // and only applies if nothing on the layer lies between the corners.
+    let mut pieces = band_regions(horiz);
+    pieces.sort_unstable_by_key(|r| r.x0);
+    let index = RegionIndex::new(pieces.clone());
+    for a in pieces.iter() {
+        let start = pieces.partition_point(|b| b.x0 <= a.x1);
+        for b in pieces[start..].iter().take_while(|b| b.x0 < a.x1 + space) {
+            let (y0, y1) = if b.y0 > a.y1 {
+                (a.y1, b.y0)
+            } else if a.y0 > b.y1 {
+                (b.y1, a.y0)
+            } else {
+                continue;
+            };
+            let gap = Region::new(a.x1, y0, b.x0, y1);
+            let (dx, dy) = (gap.width() as f64, gap.height() as f64);
+            if dx * dx + dy * dy < (space as f64) * (space as f64) && index.is_clear(&gap) {
+                regions.push(gap);
+            }
+        }
+    }
+
+    let rule = format!("{}.space", layer.name);
+    let description = format!("Minimum {} spacing is {}", layer.name, um(space));
+    violations.extend(
+        merge(regions)
+            .iter()
+            .map(|r| violation(rule.clone(), description.clone(), r)),
+    );
+}
+
+/// Finds shapes that are not squares of side `size`.
+fn check_size(violations: &mut Vec<DrcViolation>, layer: &LayerRules, size: Int, horiz: &[Band]) {
+    let rule = format!("{}.size", layer.name);
+    let description = format!("{} shapes must be {} by {}", layer.name, um(size), um(size));
+    violations.extend(
+        components(horiz)
+            .iter()
+            .filter(|c| c.bbox.width() != size || c.bbox.height() != size || c.area != size * size)
+            .map(|c| violation(rule.clone(), description.clone(), &c.bbox)),
+    );
+}
+
+/// Finds shapes that are not enclosed by the `enclosing` layer.
+fn check_enclosure(
+    violations: &mut Vec<DrcViolation>,
+    layer: &LayerRules,
+    rule: &EnclosureRule,
+    horiz: &[Band],
+    enclosing: &RegionIndex,
+) {
+    let e = rule.enclosure;
+    let enclosed = |r: &Region| match rule.one_side {
+        Some(one) => enclosing.covers(&r.expand(one, e)) || enclosing.covers(&r.expand(e, one)),
+        None => enclosing.covers(&r.expand(e, e)),
+    };
+
+    let name = format!("{}.enclosure.{}", layer.name, rule.layer);
+    let mut description = format!(
+        "{} must be enclosed by {} by {}",
+        layer.name,
+        rule.layer,
+        um(e)
+    );
+    if let Some(one) = rule.one_side {
+        description.push_str(&format!(", and by {} on two opposite sides", um(one)));
+    }
+    violations.extend(
+        components(horiz)
+            .iter()
+            .filter(|c| !enclosed(&c.bbox))
+            .map(|c| violation(name.clone(), description.clone(), &c.bbox)),
+    );
+}
+
+/// Removes duplicate regions, and merges regions that abut along a full edge.
+fn merge(mut regions: Vec<Region>) -> Vec<Region> {
+    regions.sort_unstable_by_key(|r| (r.x0, r.x1, r.y0, r.y1));
+    regions.dedup();
+    let mut out: Vec<Region> = Vec::with_capacity(regions.len());
+    for r in regions {
+        match out.last_mut() {
+            Some(last) if last.x0 == r.x0 && last.x1 == r.x1 && last.y1 >= r.y0 => {
+                last.y1 = last.y1.max(r.y1);
+            }
+            _ => out.push(r),
+        }
+    }
+    out
+}
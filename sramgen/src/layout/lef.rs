@@ -0,0 +1,382 @@
+//! LEF abstracts of generated layouts, for place and route.
+//!
+//! The abstract of a cell lists the shapes of its pins, and blocks every other
+//! shape drawn on a routing layer as an obstruction. Metal in an access region
+//! around each pin that is connected to the pin is listed as part of the pin.
+
+use anyhow::{anyhow, bail};
+use layout21::raw::{Cell, Int, LayerKey};
+use layout21::utils::Ptr;
+use pdkprims::Pdk;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Write};
+use std::path::Path;
+
+use crate::layout::drc::flatten::{shape_regions, Flattener};
+use crate::layout::drc::geometry::{band_regions, bands, Region, RegionIndex, DBU_PER_MICRON};
+use crate::Result;
+
+/// The tool used to generate LEF abstracts.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+pub enum LefTool {
+    /// Write the abstract directly from the generated layout.
+    Native,
+    /// Generate the abstract from the GDS layout using Cadence Abstract Generator.
+    AbstractLef,
+}
+
+impl Default for LefTool {
+    /// Abstract Generator if support for it is enabled; the native writer otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "abstract_lef") {
+            Self::AbstractLef
+        } else {
+            Self::Native
+        }
+    }
+}
+
+impl Display for LefTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Native => write!(f, "native"),
+            Self::AbstractLef => write!(f, "abstract-lef"),
+        }
+    }
+}
+
+/// The distance around each pin of a SKY130 abstract within which the pin's own metal
+/// is listed as part of the pin, in nanometers.
+pub const SKY130_PIN_ACCESS: Int = 300;
+
+/// SKY130 routing layers written to LEF abstracts, as `(PDK name, LEF name)` pairs.
+pub const SKY130_LEF_LAYERS: [(&str, &str); 5] = [
+    ("li", "li1"),
+    ("m1", "met1"),
+    ("m2", "met2"),
+    ("m3", "met3"),
+    ("m4", "met4"),
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PinDirection {
+    Input,
+    Output,
+    Inout,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PinUse {
+    Signal,
+    Clock,
+    Power,
+    Ground,
+}
+
+impl Display for PinDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Input => write!(f, "INPUT"),
+            Self::Output => write!(f, "OUTPUT"),
+            Self::Inout => write!(f, "INOUT"),
+        }
+    }
+}
+
+impl Display for PinUse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Signal => write!(f, "SIGNAL"),
+            Self::Clock => write!(f, "CLOCK"),
+            Self::Power => write!(f, "POWER"),
+            Self::Ground => write!(f, "GROUND"),
+        }
+    }
+}
+
+/// The direction and use of the pins of generated SRAMs, by pin or bus name.
+pub fn sram_pin_kind(name: &str) -> (PinDirection, PinUse) {
+    match name {
+        "clk" => (PinDirection::Input, PinUse::Clock),
+        "addr" | "din" | "we" | "wmask" => (PinDirection::Input, PinUse::Signal),
+        "dout" => (PinDirection::Output, PinUse::Signal),
+        "vdd" => (PinDirection::Inout, PinUse::Power),
+        "vss" => (PinDirection::Inout, PinUse::Ground),
+        _ => (PinDirection::Inout, PinUse::Signal),
+    }
+}
+
+/// The abstract parameters of a generated SRAM, whose layout is `cell`.
+pub fn sram_lef_params(cell: &Ptr<Cell>) -> LefParams<'_> {
+    LefParams {
+        cell,
+        layers: &SKY130_LEF_LAYERS,
+        pin_kind: sram_pin_kind,
+        pin_access: SKY130_PIN_ACCESS,
+    }
+}
+
+pub struct LefParams<'a> {
+    pub cell: &'a Ptr<Cell>,
+    /// The routing layers to write, as `(PDK name, LEF name)` pairs.
+    ///
+    /// Shapes on other layers are omitted, as are layers the PDK does not have.
+    pub layers: &'a [(&'a str, &'a str)],
+    /// The direction and use of a pin, given its name, or the name of its bus for bus bits.
+    pub pin_kind: fn(&str) -> (PinDirection, PinUse),
+    /// The distance around each pin within which shapes touching the pin
+    /// are listed as part of the pin rather than as obstructions.
+    ///
+    /// Without this access region, the metal leading up to a pin is obstructed right up to
+    /// the pin, and routers can only reach the pin from outside the cell.
+    /// Within it, routers may connect to that metal as the pin's net, and keep their
+    /// spacing to it when routing other nets. Shapes that do not touch the pin
+    /// remain obstructions.
+    pub pin_access: Int,
+}
+
+/// A LEF macro, in the coordinates of its layout.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LefMacro {
+    pub name: String,
+    /// The bounding box of the layout.
+    pub bbox: Region,
+    pub pins: Vec<LefPin>,
+    /// The obstructions on each layer, by LEF layer name.
+    pub obstructions: BTreeMap<String, Vec<Region>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LefPin {
+    pub name: String,
+    pub direction: PinDirection,
+    pub use_: PinUse,
+    /// The shapes of the pin on each layer, by LEF layer name.
+    pub shapes: BTreeMap<String, Vec<Region>>,
+}
+
+impl LefMacro {
+    /// Abstracts the layout of `params.cell`.
+    ///
+    /// Pins are the ports of the cell's abstract. Every shape on a routing layer
+    /// that is not part of a pin on that layer is an obstruction, except within
+    /// [`LefParams::pin_access`] of a pin it touches, where it is added to the pin.
+    pub fn new(pdk: &Pdk, params: &LefParams) -> Result<Self> {
+        let mut layers = BTreeMap::new();
+        for (name, lef_name) in params.layers.iter() {
+            if let Some(key) = pdk.get_layerkey(name) {
+                layers.insert(key, *lef_name);
+            }
+        }
+        let keys = layers.keys().copied().collect::<HashSet<LayerKey>>();
+        let shapes = Flattener::new(&keys).flatten(params.cell)?;
+
+        let cell = params.cell.read().unwrap();
+        let bbox = cell
+            .layout
+            .as_ref()
+            .ok_or_else(|| anyhow!("Cell `{}` has no layout", cell.name))?
+            .bbox()
+            .into_rect();
+        let bbox = Region::new(bbox.p0.x, bbox.p0.y, bbox.p1.x, bbox.p1.y);
+
+        let mut pins: BTreeMap<(String, usize), LefPin> = BTreeMap::new();
+        if let Some(ref abs) = cell.abs {
+            for port in abs.ports.iter() {
+                let (bus, index) = split_bus_bit(&port.net);
+                let (direction, use_) = (params.pin_kind)(bus);
+                let pin = pins
+                    .entry((bus.to_string(), index.unwrap_or_default()))
+                    .or_insert_with(|| LefPin {
+                        name: port.net.clone(),
+                        direction,
+                        use_,
+                        shapes: BTreeMap::new(),
+                    });
+                for (layer, layer_shapes) in port.shapes.iter() {
+                    let lef_name = match layers.get(layer) {
+                        Some(lef_name) => lef_name,
+                        None => continue,
+                    };
+                    let rects = pin.shapes.entry(lef_name.to_string()).or_default();
+                    for shape in layer_shapes.iter() {
+                        rects.extend(shape_regions(shape).unwrap_or_default());
+                    }
+                }
+            }
+        }
+        let mut pins = pins.into_values().collect::<Vec<_>>();
+        for pin in pins.iter() {
+            if pin.shapes.values().all(Vec::is_empty) {
+                bail!(
+                    "Pin `{}` of cell `{}` has no shapes on a routing layer",
+                    pin.name,
+                    cell.name
+                );
+            }
+        }
+
+        let mut obstructions = BTreeMap::new();
+        // Metal in the access region of a pin, as `(pin index, LEF layer name, shape)`.
+        let mut access_shapes = Vec::new();
+        for (layer, lef_name) in layers.iter() {
+            let rects = match shapes.get(layer) {
+                Some(rects) if !rects.is_empty() => rects,
+                _ => continue,
+            };
+            let (owners, pin_rects): (Vec<usize>, Vec<Region>) = pins
+                .iter()
+                .enumerate()
+                .filter_map(|(i, pin)| pin.shapes.get(*lef_name).map(|rects| (i, rects)))
+                .flat_map(|(i, rects)| rects.iter().map(move |r| (i, *r)))
+                .unzip();
+            let pin_index = RegionIndex::new(pin_rects.clone());
+
+            let mut obs = Vec::new();
+            for r in band_regions(&bands(rects)) {
+                let mut pieces = vec![r];
+                for i in pin_index.touching(&r) {
+                    let access = pin_rects[i].expand(params.pin_access, params.pin_access);
+                    for piece in pieces.iter() {
+                        if let Some(shared) = piece.intersection(&access) {
+                            access_shapes.extend(
+                                shared
+                                    .difference(&pin_rects[i])
+                                    .into_iter()
+                                    .map(|shape| (owners[i], *lef_name, shape)),
+                            );
+                        }
+                    }
+                    pieces = pieces
+                        .iter()
+                        .flat_map(|piece| piece.difference(&access))
+                        .collect();
+                }
+                obs.extend(pieces);
+            }
+            obstructions.insert(lef_name.to_string(), obs);
+        }
+        for (i, lef_name, shape) in access_shapes {
+            pins[i]
+                .shapes
+                .entry(lef_name.to_string())
+                .or_default()
+                .push(shape);
+        }
+
+        Ok(Self {
+            name: cell.name.clone(),
+            bbox,
+            pins,
+            obstructions,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_lef()?)?;
+        Ok(())
+    }
+
+    /// Formats the macro as a LEF 5.8 file.
+    ///
+    /// Coordinates are shifted so that the lower left corner of the bounding box
+    /// is the origin of the macro; the origin of the layout is given by `FOREIGN`.
+    pub fn to_lef(&self) -> Result<String> {
+        let mut out = String::new();
+        let name = &self.name;
+        let (dx, dy) = (self.bbox.x0, self.bbox.y0);
+
+        writeln!(out, "VERSION 5.8 ;")?;
+        writeln!(out, "BUSBITCHARS \"[]\" ;")?;
+        writeln!(out, "DIVIDERCHAR \"/\" ;")?;
+        writeln!(out, "UNITS")?;
+        writeln!(out, "  DATABASE MICRONS {} ;", DBU_PER_MICRON)?;
+        writeln!(out, "END UNITS")?;
+        writeln!(out)?;
+
+        writeln!(out, "MACRO {name}")?;
+        writeln!(out, "  CLASS BLOCK ;")?;
+        writeln!(out, "  ORIGIN 0 0 ;")?;
+        writeln!(out, "  FOREIGN {name} {} {} ;", um(-dx), um(-dy))?;
+        writeln!(
+            out,
+            "  SIZE {} BY {} ;",
+            um(self.bbox.width()),
+            um(self.bbox.height())
+        )?;
+        writeln!(out, "  SYMMETRY X Y ;")?;
+
+        for pin in self.pins.iter() {
+            writeln!(out, "  PIN {}", pin.name)?;
+            writeln!(out, "    DIRECTION {} ;", pin.direction)?;
+            writeln!(out, "    USE {} ;", pin.use_)?;
+            writeln!(out, "    PORT")?;
+            write_layers(&mut out, &pin.shapes, (dx, dy), "      ")?;
+            writeln!(out, "    END")?;
+            writeln!(out, "  END {}", pin.name)?;
+        }
+
+        if !self.obstructions.is_empty() {
+            writeln!(out, "  OBS")?;
+            write_layers(&mut out, &self.obstructions, (dx, dy), "    ")?;
+            writeln!(out, "  END")?;
+        }
+
+        writeln!(out, "END {name}")?;
+        writeln!(out)?;
+        writeln!(out, "END LIBRARY")?;
+        Ok(out)
+    }
+}
+
+/// Writes the LEF abstract of `params.cell` to `path`.
+pub fn save_lef(pdk: &Pdk, params: &LefParams, path: impl AsRef<Path>) -> Result<()> {
+    LefMacro::new(pdk, params)?.save(path)
+}
+
+/// Writes the `LAYER` and `RECT` statements of `shapes`, shifted by `-offset`.
+fn write_layers(
+    out: &mut String,
+    shapes: &BTreeMap<String, Vec<Region>>,
+    offset: (Int, Int),
+    indent: &str,
+) -> Result<()> {
+    let (dx, dy) = offset;
+    for (layer, rects) in shapes.iter() {
+        if rects.is_empty() {
+            continue;
+        }
+        writeln!(out, "{indent}LAYER {layer} ;")?;
+        for r in rects.iter() {
+            writeln!(
+                out,
+                "{indent}  RECT {} {} {} {} ;",
+                um(r.x0 - dx),
+                um(r.y0 - dy),
+                um(r.x1 - dx),
+                um(r.y1 - dy)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a pin name such as `addr[3]` into its bus name and index.
+fn split_bus_bit(name: &str) -> (&str, Option<usize>) {
+    if let Some((bus, rest)) = name.split_once('[') {
+        if let Some(index) = rest.strip_suffix(']').and_then(|i| i.parse().ok()) {
+            return (bus, Some(index));
+        }
+    }
+    (name, None)
+}
+
+/// Formats a coordinate in microns.
+fn um(x: Int) -> String {
+    format!("{:.3}", x as f64 / DBU_PER_MICRON)
+}
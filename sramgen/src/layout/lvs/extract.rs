@@ -0,0 +1,281 @@
+//! Extraction of device netlists from layouts.
+
+use anyhow::anyhow;
+use layout21::raw::{Cell, LayerKey};
+use layout21::utils::Ptr;
+use pdkprims::Pdk;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::layout::drc::flatten::{shape_regions, Flattener};
+use crate::layout::drc::geometry::{Placement, Region, RegionIndex};
+use crate::layout::lvs::netlist::{Device, Netlist, Pin};
+use crate::layout::lvs::{DeviceRule, LvsRules};
+use crate::Result;
+
+/// The shapes of each pin of a cell on conductor layers, by lowercase pin name.
+///
+/// Pins drawn only on layers other than conductors have no shapes.
+type PinShapes = BTreeMap<String, Vec<(LayerKey, Region)>>;
+
+/// A device found in a layout.
+#[derive(Debug, Clone)]
+struct FlatDevice {
+    name: String,
+    module: String,
+    pins: PinShapes,
+}
+
+/// Extracts the netlist of devices in the layout of `cell`.
+///
+/// Shapes on the conductor layers of `rules` are connected if they touch,
+/// and shapes on different layers are connected by the overlapping shapes of vias.
+/// Instances of the device cells of `rules` become devices; all of their
+/// shapes are extracted as well, but the cells they instantiate are not devices.
+/// The shapes of each pin of a device are assumed to be connected within the device.
+/// Nets are labeled with the names of the top level ports in the abstract of `cell`.
+pub fn extract(pdk: &Pdk, cell: &Ptr<Cell>, rules: &LvsRules) -> Result<Netlist> {
+    let layer_key = |name: &str| {
+        pdk.get_layerkey(name)
+            .ok_or_else(|| anyhow!("LVS rule table references unknown layer `{name}`"))
+    };
+
+    let mut conductors = Vec::new();
+    let mut vias = Vec::new();
+    for via in rules.vias.iter() {
+        let mut connects = Vec::new();
+        for name in via.connects.iter() {
+            let key = layer_key(name)?;
+            if !conductors.contains(&key) {
+                conductors.push(key);
+            }
+            connects.push(key);
+        }
+        vias.push((layer_key(&via.layer)?, connects));
+    }
+    let conductor_set = conductors.iter().copied().collect::<HashSet<_>>();
+
+    let mut layers = conductor_set.clone();
+    layers.extend(vias.iter().map(|(cut, _)| *cut));
+    let shapes = Flattener::new(&layers).flatten(cell)?;
+
+    let devices = DeviceFinder {
+        rules,
+        conductors: &conductor_set,
+        cache: HashMap::new(),
+    }
+    .devices(cell)?;
+    let labels = pin_shapes(&cell.read().unwrap(), None, &conductor_set);
+
+    // Every shape on a conductor layer is a node, numbered consecutively by layer.
+    // Device pins and top level ports are appended to the shapes of their layers.
+    let mut rects: HashMap<LayerKey, Vec<Region>> = conductors
+        .iter()
+        .map(|layer| (*layer, shapes.get(layer).cloned().unwrap_or_default()))
+        .collect();
+    let mut add_pin = |pin: &[(LayerKey, Region)]| {
+        pin.iter()
+            .map(|(layer, r)| {
+                let rects = rects.get_mut(layer).unwrap();
+                rects.push(*r);
+                (*layer, rects.len() - 1)
+            })
+            .collect::<Vec<_>>()
+    };
+    let device_pins = devices
+        .iter()
+        .map(|d| d.pins.values().map(|pin| add_pin(pin)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let label_pins = labels
+        .iter()
+        .map(|(name, pin)| (name, add_pin(pin)))
+        .collect::<Vec<_>>();
+
+    let mut offsets = HashMap::new();
+    let mut n = 0;
+    for layer in conductors.iter() {
+        offsets.insert(*layer, n);
+        n += rects[layer].len();
+    }
+    let node = |(layer, i): (LayerKey, usize)| offsets[&layer] + i;
+
+    let mut parent = (0..n).collect::<Vec<_>>();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    fn union(parent: &mut [usize], a: usize, b: usize) {
+        let (ra, rb) = (find(parent, a), find(parent, b));
+        parent[ra] = rb;
+    }
+
+    let indices = conductors
+        .iter()
+        .map(|layer| (*layer, RegionIndex::new(rects[layer].clone())))
+        .collect::<HashMap<_, _>>();
+    for layer in conductors.iter() {
+        for (i, r) in rects[layer].iter().enumerate() {
+            for j in indices[layer].touching(r) {
+                if j > i {
+                    union(&mut parent, node((*layer, i)), node((*layer, j)));
+                }
+            }
+        }
+    }
+    for (cut, connects) in vias.iter() {
+        for c in shapes.get(cut).map(Vec::as_slice).unwrap_or_default() {
+            let mut overlapping = Vec::new();
+            for layer in connects.iter() {
+                for i in indices[layer].touching(c) {
+                    if rects[layer][i].intersection(c).is_some() {
+                        overlapping.push(node((*layer, i)));
+                    }
+                }
+            }
+            for w in overlapping.windows(2) {
+                union(&mut parent, w[0], w[1]);
+            }
+        }
+    }
+
+    // Join the shapes of each pin before assigning any nets.
+    for nodes in device_pins
+        .iter()
+        .flatten()
+        .chain(label_pins.iter().map(|(_, nodes)| nodes))
+    {
+        for n in nodes.iter().skip(1) {
+            union(&mut parent, node(nodes[0]), node(*n));
+        }
+    }
+
+    let mut netlist = Netlist::default();
+    let mut nets = HashMap::new();
+    let mut net_of = |netlist: &mut Netlist, nodes: &[(LayerKey, usize)]| {
+        let root = find(&mut parent, node(*nodes.first()?));
+        Some(*nets.entry(root).or_insert_with(|| netlist.add_net()))
+    };
+
+    for (device, pins) in devices.iter().zip(device_pins) {
+        let pins = device
+            .pins
+            .keys()
+            .zip(pins)
+            .map(|(name, nodes)| Pin {
+                name: name.clone(),
+                net: net_of(&mut netlist, &nodes),
+            })
+            .collect();
+        netlist.devices.push(Device {
+            name: device.name.clone(),
+            module: device.module.clone(),
+            pins,
+        });
+    }
+    for (name, nodes) in label_pins {
+        if let Some(net) = net_of(&mut netlist, &nodes) {
+            netlist.nets[net].labels.push(name.clone());
+        }
+    }
+
+    Ok(netlist)
+}
+
+/// Finds the devices in a layout, reusing the devices of cells that are instantiated many times.
+struct DeviceFinder<'a> {
+    rules: &'a LvsRules,
+    conductors: &'a HashSet<LayerKey>,
+    cache: HashMap<String, Rc<Vec<FlatDevice>>>,
+}
+
+impl<'a> DeviceFinder<'a> {
+    /// The devices instantiated by `cell`, in the coordinates of `cell`.
+    fn devices(&mut self, cell: &Ptr<Cell>) -> Result<Rc<Vec<FlatDevice>>> {
+        let cell = cell.read().unwrap();
+        if let Some(devices) = self.cache.get(&cell.name) {
+            return Ok(Rc::clone(devices));
+        }
+
+        let mut devices = Vec::new();
+        if let Some(ref layout) = cell.layout {
+            for inst in layout.insts.iter() {
+                let placement = Placement::from_instance(inst)?;
+                let device = {
+                    let child = inst.cell.read().unwrap();
+                    self.rules
+                        .device(&child.name)
+                        .map(|rule| (rule, pin_shapes(&child, Some(rule), self.conductors)))
+                };
+                let place = |pins: &PinShapes| -> PinShapes {
+                    pins.iter()
+                        .map(|(name, shapes)| {
+                            let shapes = shapes
+                                .iter()
+                                .map(|(layer, r)| (*layer, placement.apply(r)))
+                                .collect();
+                            (name.clone(), shapes)
+                        })
+                        .collect()
+                };
+
+                if let Some((rule, pins)) = device {
+                    devices.push(FlatDevice {
+                        name: inst.inst_name.clone(),
+                        module: rule.module.clone(),
+                        pins: place(&pins),
+                    });
+                } else {
+                    let child_devices = self.devices(&inst.cell)?;
+                    devices.extend(child_devices.iter().map(|d| FlatDevice {
+                        name: format!("{}/{}", inst.inst_name, d.name),
+                        module: d.module.clone(),
+                        pins: place(&d.pins),
+                    }));
+                }
+            }
+        }
+
+        let devices = Rc::new(devices);
+        self.cache.insert(cell.name.clone(), Rc::clone(&devices));
+        Ok(devices)
+    }
+}
+
+/// The shapes of the ports in the abstract of `cell` on the `conductors` layers,
+/// by lowercase port name, renamed according to the pins of `rule`.
+/// Ports skipped by `rule` are left out.
+///
+/// Ports with no shapes on conductor layers are included without shapes.
+fn pin_shapes(cell: &Cell, rule: Option<&DeviceRule>, conductors: &HashSet<LayerKey>) -> PinShapes {
+    let mut pins = PinShapes::new();
+    if let Some(ref abs) = cell.abs {
+        for port in abs.ports.iter() {
+            if matches!(rule, Some(rule) if rule.skips(&port.net)) {
+                continue;
+            }
+            let name = port.net.to_lowercase();
+            let name = match rule {
+                Some(rule) => rule.pin_name(name),
+                None => name,
+            };
+            let shapes = pins.entry(name).or_default();
+            for (layer, layer_shapes) in port.shapes.iter() {
+                if !conductors.contains(layer) {
+                    continue;
+                }
+                for shape in layer_shapes.iter() {
+                    shapes.extend(
+                        shape_regions(shape)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|r| (*layer, r)),
+                    );
+                }
+            }
+        }
+    }
+    pins
+}
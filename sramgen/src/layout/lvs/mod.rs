@@ -0,0 +1,542 @@
+//! A lightweight layout versus schematic (LVS) checker for generated layouts.
+//!
+//! Nets are extracted from the shapes on conductor layers and the vias connecting them.
+//! Instances of device cells listed in an [`LvsRules`] table, such as bitcells,
+//! sense amplifiers, and standard cells, are recognized by name, and the nets on their pins
+//! are compared against the connections of the matching external modules in the schematic.
+//! Transistors are neither extracted nor compared; schematic instances of external modules
+//! that are neither devices nor explicitly ignored by the rules are reported as errors.
+//!
+//! Nets connected to top level ports are compared by name; other nets are compared
+//! by the device pins they connect. Connections swapped between nets that connect
+//! the same kinds of device pins are therefore not detected. The checker finds opens,
+//! shorts, and mismatched pins without a signoff LVS tool, but does not replace one.
+
+use anyhow::{anyhow, Context};
+use layout21::gds21::GdsLibrary;
+use layout21::raw::{Cell, Library};
+use layout21::utils::Ptr;
+use pdkprims::tech::sky130;
+use pdkprims::Pdk;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use vlsir::circuit::Module;
+
+use crate::layout::lvs::extract::extract;
+use crate::layout::lvs::netlist::{flatten_schematic, Netlist};
+use crate::Result;
+
+pub mod extract;
+pub mod netlist;
+
+/// Maximum number of nets listed for each kind of mismatch in an LVS report.
+const MAX_LISTED_NETS: usize = 10;
+
+/// Connectivity and device recognition rules.
+///
+/// Rule tables are TOML files listing `[[via]]` and `[[device]]` tables.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LvsRules {
+    /// External modules of the schematic that are not compared, such as transistors.
+    ///
+    /// Instances of any other external module without a device rule are an error.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default, rename = "via")]
+    pub vias: Vec<ViaRule>,
+    #[serde(default, rename = "device")]
+    pub devices: Vec<DeviceRule>,
+}
+
+/// A cut layer connecting the shapes of other layers that it overlaps.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ViaRule {
+    /// The name of the cut layer in the PDK.
+    pub layer: String,
+    /// The conductor layers connected by the cut layer.
+    pub connects: Vec<String>,
+}
+
+/// A layout cell compared as a black box against an external module of the schematic.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeviceRule {
+    /// The name of the layout cell.
+    pub cell: String,
+    /// The name of the external module.
+    pub module: String,
+    /// Schematic port names of layout pins that are named differently.
+    #[serde(default)]
+    pub pins: BTreeMap<String, String>,
+    /// Ports of the cell's abstract that are not pins of the module, such as routing blockages.
+    #[serde(default)]
+    pub skip: Vec<String>,
+}
+
+impl LvsRules {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading LVS rule table {path:?}"))?;
+        let rules = toml::from_str(&contents)
+            .with_context(|| format!("Error parsing LVS rule table {path:?}"))?;
+        Ok(rules)
+    }
+
+    /// The built-in SKY130 rule table.
+    pub fn sky130() -> Result<Self> {
+        Self::load(sky130_lvs_rules_path())
+    }
+
+    /// The device rule of the layout cell named `cell`, if it is a device.
+    pub fn device(&self, cell: &str) -> Option<&DeviceRule> {
+        self.devices.iter().find(|d| d.cell == cell)
+    }
+}
+
+impl DeviceRule {
+    /// The lowercase schematic name of the layout pin `name`.
+    pub fn pin_name(&self, name: String) -> String {
+        self.pins
+            .iter()
+            .find(|(pin, _)| pin.eq_ignore_ascii_case(&name))
+            .map(|(_, port)| port.to_lowercase())
+            .unwrap_or(name)
+    }
+
+    /// Whether the layout pin `name` is skipped.
+    pub fn skips(&self, name: &str) -> bool {
+        self.skip.iter().any(|pin| pin.eq_ignore_ascii_case(name))
+    }
+}
+
+pub fn sky130_lvs_rules_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tech/sky130/lvs_rules.toml")
+}
+
+/// The number of instances of a device in the layout and the schematic.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeviceCount {
+    pub module: String,
+    pub layout: usize,
+    pub schematic: usize,
+}
+
+/// Pins of a device, or top level ports, that differ between the layout and the schematic.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PinMismatch {
+    /// The module of the device, or the name of the top level cell.
+    pub module: String,
+    /// Pins in the schematic that are not in the layout.
+    pub missing: Vec<String>,
+    /// Pins in the layout that are not in the schematic.
+    pub extra: Vec<String>,
+}
+
+/// A top level port split across several nets in the layout.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Open {
+    pub net: String,
+    /// The number of layout nets labeled with the port.
+    pub pieces: usize,
+}
+
+/// Top level ports of different schematic nets connected to each other in the layout.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Short {
+    pub nets: Vec<String>,
+}
+
+/// A net connected to a top level port that connects different device pins
+/// in the layout and the schematic.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NetMismatch {
+    pub net: String,
+    /// The number of device pins on the net in the layout, by `module.pin`.
+    pub layout: BTreeMap<String, usize>,
+    /// The number of device pins on the net in the schematic, by `module.pin`.
+    pub schematic: BTreeMap<String, usize>,
+}
+
+/// Internal nets connecting the same device pins that occur a different number of times
+/// in the layout and the schematic.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UnmatchedNets {
+    /// The number of device pins on each net, by `module.pin`.
+    pub pins: BTreeMap<String, usize>,
+    pub layout: usize,
+    pub schematic: usize,
+}
+
+/// The result of comparing a layout against a schematic.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LvsReport {
+    /// The name of the checked cell.
+    pub cell: String,
+    pub devices: Vec<DeviceCount>,
+    pub pin_mismatches: Vec<PinMismatch>,
+    pub opens: Vec<Open>,
+    pub shorts: Vec<Short>,
+    pub net_mismatches: Vec<NetMismatch>,
+    pub unmatched_nets: Vec<UnmatchedNets>,
+}
+
+impl LvsReport {
+    /// Whether the layout matches the schematic.
+    pub fn passed(&self) -> bool {
+        self.devices.iter().all(|d| d.layout == d.schematic)
+            && self.pin_mismatches.is_empty()
+            && self.opens.is_empty()
+            && self.shorts.is_empty()
+            && self.net_mismatches.is_empty()
+            && self.unmatched_nets.is_empty()
+    }
+}
+
+impl Display for LvsReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.passed() { "PASSED" } else { "FAILED" };
+        writeln!(f, "LVS of {} using native: {}", self.cell, status)?;
+
+        writeln!(f, "\nDevices (layout / schematic):")?;
+        for d in self.devices.iter() {
+            let mark = if d.layout == d.schematic {
+                ""
+            } else {
+                "  MISMATCH"
+            };
+            writeln!(f, "    {}: {} / {}{mark}", d.module, d.layout, d.schematic)?;
+        }
+
+        if !self.pin_mismatches.is_empty() {
+            writeln!(f, "\nMismatched pins:")?;
+            for p in self.pin_mismatches.iter() {
+                write!(f, "    {}:", p.module)?;
+                if !p.missing.is_empty() {
+                    write!(f, " missing from layout {}", p.missing.join(", "))?;
+                }
+                if !p.extra.is_empty() {
+                    write!(f, " not in schematic {}", p.extra.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        write_listed(f, "opens", &self.opens, |f, o| {
+            writeln!(f, "    {} is split into {} layout nets", o.net, o.pieces)
+        })?;
+        write_listed(f, "shorts", &self.shorts, |f, s| {
+            writeln!(f, "    {}", s.nets.join(", "))
+        })?;
+        write_listed(f, "mismatched nets", &self.net_mismatches, |f, n| {
+            writeln!(f, "    {}:", n.net)?;
+            writeln!(f, "        layout: {}", PinCounts(&n.layout))?;
+            writeln!(f, "        schematic: {}", PinCounts(&n.schematic))
+        })?;
+        write_listed(
+            f,
+            "kinds of unmatched nets",
+            &self.unmatched_nets,
+            |f, n| {
+                writeln!(
+                    f,
+                    "    {} in layout, {} in schematic: {}",
+                    n.layout,
+                    n.schematic,
+                    PinCounts(&n.pins)
+                )
+            },
+        )?;
+        Ok(())
+    }
+}
+
+/// Lists up to [`MAX_LISTED_NETS`] items using `write_item`.
+fn write_listed<T>(
+    f: &mut std::fmt::Formatter<'_>,
+    kind: &str,
+    items: &[T],
+    write_item: impl Fn(&mut std::fmt::Formatter<'_>, &T) -> std::fmt::Result,
+) -> std::fmt::Result {
+    if items.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "\n{} {kind}:", items.len())?;
+    for item in items.iter().take(MAX_LISTED_NETS) {
+        write_item(f, item)?;
+    }
+    if items.len() > MAX_LISTED_NETS {
+        writeln!(f, "    ... and {} more", items.len() - MAX_LISTED_NETS)?;
+    }
+    Ok(())
+}
+
+/// Formats device pin counts as `module.pin x2, module.pin`.
+struct PinCounts<'a>(&'a BTreeMap<String, usize>);
+
+impl Display for PinCounts<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "no device pins");
+        }
+        for (i, (pin, &count)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{pin}")?;
+            if count > 1 {
+                write!(f, " x{count}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares the layout of `cell` against the schematic module of the same name in `modules`.
+pub fn check_lvs(
+    pdk: &Pdk,
+    cell: &Ptr<Cell>,
+    modules: &[Module],
+    rules: &LvsRules,
+) -> Result<LvsReport> {
+    let name = cell.read().unwrap().name.clone();
+    let layout = extract(pdk, cell, rules)?;
+    let device_modules = rules
+        .devices
+        .iter()
+        .map(|d| d.module.as_str())
+        .collect::<HashSet<_>>();
+    let ignored = rules
+        .ignore
+        .iter()
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    let schematic = flatten_schematic(modules, &name, &device_modules, &ignored)?;
+    Ok(compare(name, &layout, &schematic))
+}
+
+/// Compares the cell `name` in the GDS file at `layout_path` against
+/// the schematic module of the same name in `modules`.
+pub fn check_gds(
+    layout_path: impl AsRef<Path>,
+    modules: &[Module],
+    name: &str,
+    rules: &LvsRules,
+) -> Result<LvsReport> {
+    let layout_path = layout_path.as_ref();
+    let pdk_lib = sky130::pdk_lib(name)?;
+    let gds = GdsLibrary::load(layout_path)
+        .with_context(|| format!("Error reading GDS layout {layout_path:?}"))?;
+    let lib = Library::from_gds(&gds, Some(pdk_lib.pdk.layers.clone()))?;
+    let cell = lib
+        .cell(name)
+        .ok_or_else(|| anyhow!("GDS layout {layout_path:?} does not contain cell `{name}`"))?;
+    check_lvs(&pdk_lib.pdk, &cell, modules, rules)
+}
+
+/// Compares the netlist extracted from the layout of `cell` against the schematic netlist.
+pub fn compare(cell: String, layout: &Netlist, schematic: &Netlist) -> LvsReport {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for d in layout.devices.iter() {
+        counts.entry(d.module.as_str()).or_default().0 += 1;
+    }
+    for d in schematic.devices.iter() {
+        counts.entry(d.module.as_str()).or_default().1 += 1;
+    }
+    let devices = counts
+        .into_iter()
+        .map(|(module, (layout, schematic))| DeviceCount {
+            module: module.to_string(),
+            layout,
+            schematic,
+        })
+        .collect();
+
+    let mut pin_mismatches = Vec::new();
+    let layout_pins = pin_names(layout);
+    let schematic_pins = pin_names(schematic);
+    for (module, pins) in layout_pins.iter() {
+        if let Some(expected) = schematic_pins.get(module) {
+            push_pin_mismatch(&mut pin_mismatches, module, expected, pins);
+        }
+    }
+    push_pin_mismatch(
+        &mut pin_mismatches,
+        &cell,
+        &labels(schematic).into_keys().collect(),
+        &labels(layout).into_keys().collect(),
+    );
+
+    // Only pins that could be extracted from the layout are compared.
+    let checked = layout
+        .devices
+        .iter()
+        .flat_map(|d| {
+            d.pins
+                .iter()
+                .filter(|p| p.net.is_some())
+                .map(|p| (d.module.as_str(), p.name.as_str()))
+        })
+        .collect::<HashSet<_>>();
+    let layout_terminals = terminals(layout, &checked);
+    let schematic_terminals = terminals(schematic, &checked);
+
+    let schematic_labels = labels(schematic);
+    let layout_labels = labels(layout);
+
+    let mut opens = Vec::new();
+    for (label, nets) in layout_labels.iter() {
+        if nets.len() > 1 && schematic_labels.contains_key(label) {
+            opens.push(Open {
+                net: label.to_string(),
+                pieces: nets.len(),
+            });
+        }
+    }
+
+    // The schematic nets connected to each layout net by their labels.
+    let matches = layout
+        .nets
+        .iter()
+        .map(|net| {
+            net.labels
+                .iter()
+                .filter_map(|label| schematic_labels.get(label.as_str()))
+                .map(|nets| nets[0])
+                .collect::<BTreeSet<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut shorts = Vec::new();
+    for (net, matches) in layout.nets.iter().zip(matches.iter()) {
+        if matches.len() > 1 {
+            let mut nets = net.labels.clone();
+            nets.sort();
+            shorts.push(Short { nets });
+        }
+    }
+
+    let mut net_mismatches = Vec::new();
+    for (i, net) in schematic.nets.iter().enumerate() {
+        let name = match net.labels.iter().min() {
+            Some(name) => name,
+            None => continue,
+        };
+        let layout_nets = net
+            .labels
+            .iter()
+            .filter_map(|label| layout_labels.get(label.as_str()))
+            .flatten()
+            .copied()
+            .collect::<BTreeSet<_>>();
+        // Opens and shorts are reported separately.
+        if layout_nets.len() != 1 {
+            continue;
+        }
+        let j = *layout_nets.iter().next().unwrap();
+        if matches[j].len() == 1 && layout_terminals[j] != schematic_terminals[i] {
+            net_mismatches.push(NetMismatch {
+                net: name.clone(),
+                layout: layout_terminals[j].clone(),
+                schematic: schematic_terminals[i].clone(),
+            });
+        }
+    }
+    net_mismatches.sort_by(|a, b| a.net.cmp(&b.net));
+
+    let mut histogram: BTreeMap<&BTreeMap<String, usize>, (usize, usize)> = BTreeMap::new();
+    for (net, pins) in layout.nets.iter().zip(layout_terminals.iter()) {
+        if net.labels.is_empty() && !pins.is_empty() {
+            histogram.entry(pins).or_default().0 += 1;
+        }
+    }
+    for (net, pins) in schematic.nets.iter().zip(schematic_terminals.iter()) {
+        if net.labels.is_empty() && !pins.is_empty() {
+            histogram.entry(pins).or_default().1 += 1;
+        }
+    }
+    let unmatched_nets = histogram
+        .into_iter()
+        .filter(|(_, (layout, schematic))| layout != schematic)
+        .map(|(pins, (layout, schematic))| UnmatchedNets {
+            pins: pins.clone(),
+            layout,
+            schematic,
+        })
+        .collect();
+
+    LvsReport {
+        cell,
+        devices,
+        pin_mismatches,
+        opens,
+        shorts,
+        net_mismatches,
+        unmatched_nets,
+    }
+}
+
+/// The names of the pins of the devices in `netlist`, by module.
+fn pin_names(netlist: &Netlist) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut pins: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for d in netlist.devices.iter() {
+        pins.entry(d.module.as_str())
+            .or_default()
+            .extend(d.pins.iter().map(|p| p.name.as_str()));
+    }
+    pins
+}
+
+/// The nets labeled with each top level port of `netlist`.
+fn labels(netlist: &Netlist) -> BTreeMap<&str, Vec<usize>> {
+    let mut labels: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, net) in netlist.nets.iter().enumerate() {
+        for label in net.labels.iter() {
+            let nets = labels.entry(label.as_str()).or_default();
+            if !nets.contains(&i) {
+                nets.push(i);
+            }
+        }
+    }
+    labels
+}
+
+fn push_pin_mismatch(
+    mismatches: &mut Vec<PinMismatch>,
+    module: &str,
+    expected: &BTreeSet<&str>,
+    found: &BTreeSet<&str>,
+) {
+    let missing = expected
+        .difference(found)
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+    let extra = found
+        .difference(expected)
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() || !extra.is_empty() {
+        mismatches.push(PinMismatch {
+            module: module.to_string(),
+            missing,
+            extra,
+        });
+    }
+}
+
+/// The number of `checked` device pins on each net of `netlist`, by `module.pin`.
+fn terminals(netlist: &Netlist, checked: &HashSet<(&str, &str)>) -> Vec<BTreeMap<String, usize>> {
+    let mut out = vec![BTreeMap::new(); netlist.nets.len()];
+    for d in netlist.devices.iter() {
+        for p in d.pins.iter() {
+            if let Some(net) = p.net {
+                if checked.contains(&(d.module.as_str(), p.name.as_str())) {
+                    *out[net]
+                        .entry(format!("{}.{}", d.module, p.name))
+                        .or_default() += 1;
+                }
+            }
+        }
+    }
+    out
+}
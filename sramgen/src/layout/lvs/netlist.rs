@@ -0,0 +1,211 @@
+//! Flat netlists of the devices compared by the layout versus schematic checker.
+
+use anyhow::{anyhow, bail};
+use std::collections::{HashMap, HashSet};
+use vlsir::circuit::Module;
+use vlsir::reference::To;
+
+use crate::bus_bit;
+use crate::schematic::netlist::{connection_bits, port_signal, signal_bits, signal_widths};
+use crate::Result;
+
+/// A flat netlist of devices, extracted from a layout or a schematic.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Netlist {
+    pub devices: Vec<Device>,
+    pub nets: Vec<Net>,
+}
+
+/// An instance of a device.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Device {
+    /// The hierarchical instance name of the device, with levels separated by `/`.
+    pub name: String,
+    /// The schematic module of the device.
+    pub module: String,
+    pub pins: Vec<Pin>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pin {
+    /// The name of the pin, in lowercase.
+    pub name: String,
+    /// The index of the net connected to the pin,
+    /// or `None` if the connection could not be extracted.
+    pub net: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Net {
+    /// The lowercase names of the top level ports on the net.
+    pub labels: Vec<String>,
+}
+
+impl Netlist {
+    /// Adds an unlabeled net, returning its index.
+    pub fn add_net(&mut self) -> usize {
+        self.nets.push(Net::default());
+        self.nets.len() - 1
+    }
+}
+
+/// Flattens the schematic module `top` into the instances of the external modules `devices`.
+///
+/// Instances of the external modules in `ignored`, such as transistors, are dropped.
+/// Instances of any other external module are an error, since they could not be compared.
+/// Nets connected to the ports of `top` are labeled with the names of the ports.
+pub fn flatten_schematic(
+    modules: &[Module],
+    top: &str,
+    devices: &HashSet<&str>,
+    ignored: &HashSet<&str>,
+) -> Result<Netlist> {
+    let mut flattener = SchematicFlattener {
+        modules: modules.iter().map(|m| (m.name.as_str(), m)).collect(),
+        devices,
+        ignored,
+        netlist: Netlist::default(),
+    };
+    let top = *flattener
+        .modules
+        .get(top)
+        .ok_or_else(|| anyhow!("Schematic does not contain module `{top}`"))?;
+
+    let mut ports = HashMap::new();
+    for port in top.ports.iter() {
+        for bit in signal_bits(port_signal(port)?) {
+            let net = flattener.netlist.add_net();
+            flattener.netlist.nets[net].labels.push(bit.to_lowercase());
+            ports.insert(bit, net);
+        }
+    }
+    flattener.flatten(top, "", ports)?;
+
+    Ok(flattener.netlist)
+}
+
+struct SchematicFlattener<'a> {
+    modules: HashMap<&'a str, &'a Module>,
+    devices: &'a HashSet<&'a str>,
+    ignored: &'a HashSet<&'a str>,
+    netlist: Netlist,
+}
+
+impl<'a> SchematicFlattener<'a> {
+    /// Flattens `module`, whose port bits are connected to the nets in `nets`.
+    fn flatten(
+        &mut self,
+        module: &Module,
+        prefix: &str,
+        mut nets: HashMap<String, usize>,
+    ) -> Result<()> {
+        for sig in module.signals.iter() {
+            for bit in signal_bits(sig) {
+                if !nets.contains_key(&bit) {
+                    let net = self.netlist.add_net();
+                    nets.insert(bit, net);
+                }
+            }
+        }
+
+        let widths = signal_widths(module);
+        let net_of = |bit: &str| {
+            nets.get(bit).copied().ok_or_else(|| {
+                anyhow!(
+                    "Module `{}` connects undeclared signal `{}`",
+                    module.name,
+                    bit
+                )
+            })
+        };
+
+        for inst in module.instances.iter() {
+            let to = inst
+                .module
+                .as_ref()
+                .and_then(|r| r.to.as_ref())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Instance `{}` in `{}` has no module",
+                        inst.name,
+                        module.name
+                    )
+                })?;
+            let name = format!("{prefix}{}", inst.name);
+
+            match to {
+                To::Local(child) => {
+                    let child = *self.modules.get(child.as_str()).ok_or_else(|| {
+                        anyhow!(
+                            "Instance `{}` refers to unknown module `{}`",
+                            inst.name,
+                            child
+                        )
+                    })?;
+                    let mut child_nets = HashMap::new();
+                    for port in child.ports.iter() {
+                        let sig = port_signal(port)?;
+                        let conn = inst.connections.get(&sig.name).ok_or_else(|| {
+                            anyhow!(
+                                "Port `{}` of instance `{}` in `{}` is unconnected",
+                                sig.name,
+                                inst.name,
+                                module.name
+                            )
+                        })?;
+                        let bits = connection_bits(conn, &widths)?;
+                        let port_bits = signal_bits(sig);
+                        if bits.len() != port_bits.len() {
+                            bail!(
+                                "Port `{}` of instance `{}` in `{}` has width {}, but was connected to {} bits",
+                                sig.name,
+                                inst.name,
+                                module.name,
+                                port_bits.len(),
+                                bits.len()
+                            );
+                        }
+                        for (port_bit, bit) in port_bits.into_iter().zip(bits) {
+                            child_nets.insert(port_bit, net_of(&bit)?);
+                        }
+                    }
+                    self.flatten(child, &format!("{name}/"), child_nets)?;
+                }
+                To::External(qn) if self.devices.contains(qn.name.as_str()) => {
+                    let mut pins = Vec::new();
+                    for (port, conn) in inst.connections.iter() {
+                        let port = port.to_lowercase();
+                        let bits = connection_bits(conn, &widths)?;
+                        let n = bits.len();
+                        for (i, bit) in bits.iter().enumerate() {
+                            let name = if n == 1 {
+                                port.clone()
+                            } else {
+                                bus_bit(&port, n - 1 - i)
+                            };
+                            pins.push(Pin {
+                                name,
+                                net: Some(net_of(bit)?),
+                            });
+                        }
+                    }
+                    pins.sort_by(|a, b| a.name.cmp(&b.name));
+                    self.netlist.devices.push(Device {
+                        name,
+                        module: qn.name.clone(),
+                        pins,
+                    });
+                }
+                To::External(qn) if self.ignored.contains(qn.name.as_str()) => {}
+                To::External(qn) => bail!(
+                    "Instance `{}` in `{}` refers to external module `{}`, which has no LVS device rule and is not ignored",
+                    inst.name,
+                    module.name,
+                    qn.name
+                ),
+            }
+        }
+
+        Ok(())
+    }
+}
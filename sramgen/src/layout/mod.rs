@@ -12,13 +12,18 @@ pub mod col_inv;
 pub mod common;
 pub mod control;
 pub mod decoder;
+pub mod def;
 pub mod dff;
 pub mod dout_buffer;
+pub mod drc;
 pub mod gate;
 pub mod guard_ring;
 pub mod inv_chain;
 pub mod latch;
+pub mod lef;
+pub mod lvs;
 pub mod mux;
+pub mod pins;
 pub mod power;
 pub mod precharge;
 pub mod route;
@@ -0,0 +1,487 @@
+//! Placement of SRAM signal pins on the edges of the layout.
+//!
+//! The routes of all signal pins leave the SRAM periphery on m3 at the bottom of the
+//! guard ring. Pins that the [`PinConfig`] assigns to an edge are routed around
+//! the guard ring, through channels of horizontal m2 tracks below and above the ring
+//! and vertical m3 tracks to its left and right. Every other pin stays where its
+//! route leaves the periphery.
+//!
+//! If the configuration gives routing tracks for a pin layer, the pins on that layer
+//! are centered on those tracks, so that place and route tools can access them on-track.
+//! Pins that stay in place are shifted onto the nearest track.
+
+use anyhow::bail;
+use layout21::raw::{Cell, Dir, Int, Point, Rect, Span};
+use pdkprims::LayerIdx;
+use std::collections::HashMap;
+
+use crate::config::pins::{BitOrder, PinConfig, PortGroup, Side, TrackConfig};
+use crate::layout::route::grid::{Grid, TrackLocator};
+use crate::layout::route::{Router, RouterConfig, Trace};
+use crate::Result;
+
+/// Where the route of a signal pin leaves the SRAM periphery.
+pub struct PinExit {
+    pub group: PortGroup,
+    /// The name of the pin, such as `addr[3]`.
+    pub net: String,
+    /// The bit of the port group, which orders the pins of the group.
+    pub bit: usize,
+    /// The end of the route, a vertical m3 rectangle whose bottom edge
+    /// lies on the bottom edge of the guard ring.
+    pub rect: Rect,
+    /// The height of the pin, if it is not moved.
+    pub pin_height: Int,
+}
+
+/// A pin moved to an edge by the pin configuration.
+struct PlacedPin {
+    /// The index of the pin's [`PinExit`].
+    exit: usize,
+    side: Side,
+    /// The coordinate of the center of the pin along its edge.
+    center: Int,
+}
+
+/// Routes the pins of `exits` to the edges assigned by `config`, and adds them to `cell`.
+///
+/// `straps` are the m3 power straps that run down to the bottom of the guard ring.
+/// Pins that are shifted onto a track must not run into them.
+///
+/// Returns the boundary of the SRAM, which encloses `ring` and the routing channels.
+pub fn place_pins(
+    router: &mut Router,
+    cell: &mut Cell,
+    ring: Rect,
+    exits: &[PinExit],
+    straps: &[Rect],
+    config: &PinConfig,
+) -> Result<Rect> {
+    let cfg = router.cfg();
+    let (m2, m3) = (cfg.layerkey(2), cfg.layerkey(3));
+    // Tracks are two pitches apart, leaving room for vias.
+    let hpitch = 2 * (cfg.line(2) + cfg.space(2));
+    let vpitch = 2 * (cfg.line(3) + cfg.space(3));
+    let margin = 2 * std::cmp::max(hpitch, vpitch);
+
+    // The moved pins of each group, in the order in which they are placed along their edge.
+    let mut groups = Vec::new();
+    for group in PortGroup::ALL {
+        if let Some(group_config) = config.group(group) {
+            let mut pins = exits
+                .iter()
+                .enumerate()
+                .filter(|(_, exit)| exit.group == group)
+                .map(|(i, exit)| (exit.bit, i))
+                .collect::<Vec<_>>();
+            pins.sort_unstable();
+            if group_config.order == BitOrder::MsbFirst {
+                pins.reverse();
+            }
+            let pins = pins.into_iter().map(|(_, i)| i).collect::<Vec<_>>();
+            groups.push((group, group_config, pins));
+        }
+    }
+
+    // Pins on the top edge are routed up whichever side of the ring their route leaves nearer.
+    let top_left = |i: usize| exits[i].rect.center().x < ring.center().x;
+    let mut columns: HashMap<Side, Vec<usize>> = HashMap::new();
+    let mut counts: HashMap<Side, usize> = HashMap::new();
+    for (_, group_config, pins) in groups.iter() {
+        *counts.entry(Side::Bottom).or_default() += pins.len();
+        for &i in pins.iter() {
+            match group_config.side {
+                Side::Left | Side::Right => {
+                    columns.entry(group_config.side).or_default().push(i);
+                }
+                Side::Top => {
+                    *counts.entry(Side::Top).or_default() += 1;
+                    let side = if top_left(i) { Side::Left } else { Side::Right };
+                    columns.entry(side).or_default().push(i);
+                }
+                Side::Bottom => {}
+            }
+        }
+    }
+    for (side, column) in columns.iter() {
+        counts.insert(*side, column.len());
+    }
+
+    let channel = |side: Side, pitch: Int| match counts.get(&side) {
+        Some(&n) if n > 0 => 2 * margin + (n as Int - 1) * pitch,
+        _ => 0,
+    };
+    let boundary = Rect::from_spans(
+        Span::new(
+            ring.left() - channel(Side::Left, vpitch),
+            ring.right() + channel(Side::Right, vpitch),
+        ),
+        Span::new(
+            ring.bottom() - channel(Side::Bottom, hpitch),
+            ring.top() + channel(Side::Top, hpitch),
+        ),
+    );
+
+    let h_tracks = track_grid(&cfg, boundary, 2, config.tracks.m2.as_ref())?;
+    let v_tracks = track_grid(&cfg, boundary, 3, config.tracks.m3.as_ref())?;
+
+    // Place the pins along their edges, and check that they fit.
+    let mut placed = Vec::new();
+    let mut next: HashMap<Side, Int> = HashMap::new();
+    for (group, group_config, pins) in groups.iter() {
+        let side = group_config.side;
+        let (start, span, min_pitch, tracks, layer) = match side.dir() {
+            Dir::Horiz => (boundary.bottom(), ring.vspan(), hpitch, &h_tracks, "m2"),
+            Dir::Vert => (boundary.left(), ring.hspan(), vpitch, &v_tracks, "m3"),
+        };
+        let allowed = Span::new(span.start() + margin, span.stop() - margin);
+
+        let pitch = match (group_config.pitch, tracks) {
+            (Some(pitch), _) => pitch,
+            (None, Some((_, track_pitch))) => {
+                (min_pitch + track_pitch - 1) / track_pitch * track_pitch
+            }
+            (None, None) => min_pitch,
+        };
+        if pitch < min_pitch {
+            bail!(
+                "Pin pitch of `{}` must be at least {} nm on the {} side, but is {} nm",
+                group,
+                min_pitch,
+                side,
+                pitch
+            );
+        }
+        if let Some((_, track_pitch)) = tracks {
+            if pitch % track_pitch != 0 {
+                bail!(
+                    "Pin pitch of `{}` is {} nm, which is not a multiple of the {} track pitch of {} nm",
+                    group,
+                    pitch,
+                    layer,
+                    track_pitch
+                );
+            }
+        }
+
+        let first = match (group_config.offset, tracks) {
+            (Some(offset), Some((grid, _))) => {
+                let first = start + offset;
+                if grid
+                    .get_track(side.dir(), first, TrackLocator::Nearest)
+                    .center()
+                    != first
+                {
+                    bail!(
+                        "Pin offset of `{}` is {} nm, which places its first pin off the {} tracks",
+                        group,
+                        offset,
+                        layer
+                    );
+                }
+                first
+            }
+            (Some(offset), None) => start + offset,
+            (None, tracks) => {
+                let first = *next.entry(side).or_insert(allowed.start());
+                match tracks {
+                    Some((grid, _)) => grid
+                        .get_track(side.dir(), first, TrackLocator::StartsBeyond)
+                        .center(),
+                    None => first,
+                }
+            }
+        };
+
+        for (j, &i) in pins.iter().enumerate() {
+            let center = first + j as Int * pitch;
+            if center < allowed.start() || center > allowed.stop() {
+                bail!(
+                    "Pin `{}` is placed {} nm along the {} side, outside the range of {} to {} nm \
+                     alongside the guard ring",
+                    exits[i].net,
+                    center - start,
+                    side,
+                    allowed.start() - start,
+                    allowed.stop() - start
+                );
+            }
+            placed.push(PlacedPin {
+                exit: i,
+                side,
+                center,
+            });
+        }
+        next.insert(side, first + pins.len() as Int * pitch);
+    }
+
+    for side in [Side::Left, Side::Right, Side::Bottom, Side::Top] {
+        let min_pitch = match side.dir() {
+            Dir::Horiz => hpitch,
+            Dir::Vert => vpitch,
+        };
+        let mut pins = placed.iter().filter(|p| p.side == side).collect::<Vec<_>>();
+        pins.sort_unstable_by_key(|p| p.center);
+        for w in pins.windows(2) {
+            if w[1].center - w[0].center < min_pitch {
+                bail!(
+                    "Pins `{}` and `{}` on the {} side are {} nm apart, less than the minimum pitch of {} nm",
+                    exits[w[0].exit].net,
+                    exits[w[1].exit].net,
+                    side,
+                    w[1].center - w[0].center,
+                    min_pitch
+                );
+            }
+        }
+    }
+
+    // Pins moved along the bottom edge take the outermost tracks below the ring,
+    // so that they drop to the edge below the routes of all other moved pins.
+    let mut bottom_tracks = placed.iter().collect::<Vec<_>>();
+    bottom_tracks.sort_by_key(|p| p.side == Side::Bottom);
+    let bottom_track = bottom_tracks
+        .iter()
+        .enumerate()
+        .map(|(k, p)| (p.exit, ring.bottom() - margin - k as Int * hpitch))
+        .collect::<HashMap<_, _>>();
+    let column_track = |i: usize| {
+        for (side, column) in columns.iter() {
+            if let Some(k) = column.iter().position(|&j| j == i) {
+                let offset = margin + k as Int * vpitch;
+                return match side {
+                    Side::Left => ring.left() - offset,
+                    _ => ring.right() + offset,
+                };
+            }
+        }
+        unreachable!("Pins on the left, right, and top sides have column tracks")
+    };
+    let top_track = placed
+        .iter()
+        .filter(|p| p.side == Side::Top)
+        .enumerate()
+        .map(|(k, p)| (p.exit, ring.top() + margin + k as Int * hpitch))
+        .collect::<HashMap<_, _>>();
+
+    // Vertical m3 routes below the ring, to check that the routes of pins moved along
+    // the bottom edge do not run into the routes of other pins.
+    let mut stubs = Vec::new();
+    let mut drops = Vec::new();
+
+    let mut moved = vec![false; exits.len()];
+    for pin in placed.iter() {
+        let exit = &exits[pin.exit];
+        moved[pin.exit] = true;
+        let track = bottom_track[&pin.exit];
+
+        let mut trace = router.trace(exit.rect, 3);
+        trace.place_cursor(Dir::Vert, false);
+        draw_to_center(&mut trace, track, Dir::Vert);
+        stubs.push((pin.exit, trace.rect()));
+        trace.down().set_min_width();
+
+        match pin.side {
+            Side::Bottom => {
+                draw_to_center(&mut trace, pin.center, Dir::Horiz);
+                trace.up().set_min_width().vert_to(boundary.bottom());
+                let rect = trace.rect();
+                drops.push((pin.exit, rect));
+                cell.add_pin(
+                    exit.net.clone(),
+                    m3,
+                    Rect::from_spans(
+                        rect.hspan(),
+                        Span::new(boundary.bottom(), boundary.bottom() + 3 * cfg.line(3)),
+                    ),
+                );
+            }
+            Side::Left | Side::Right => {
+                draw_to_center(&mut trace, column_track(pin.exit), Dir::Horiz);
+                trace.up().set_min_width();
+                draw_to_center(&mut trace, pin.center, Dir::Vert);
+                trace.down().set_min_width();
+                let (edge, span) = if pin.side == Side::Left {
+                    let edge = boundary.left();
+                    (edge, Span::new(edge, edge + 3 * cfg.line(2)))
+                } else {
+                    let edge = boundary.right();
+                    (edge, Span::new(edge - 3 * cfg.line(2), edge))
+                };
+                trace.horiz_to(edge);
+                cell.add_pin(
+                    exit.net.clone(),
+                    m2,
+                    Rect::from_spans(span, trace.rect().vspan()),
+                );
+            }
+            Side::Top => {
+                draw_to_center(&mut trace, column_track(pin.exit), Dir::Horiz);
+                trace.up().set_min_width();
+                draw_to_center(&mut trace, top_track[&pin.exit], Dir::Vert);
+                trace.down().set_min_width();
+                draw_to_center(&mut trace, pin.center, Dir::Horiz);
+                trace.up().set_min_width().vert_to(boundary.top());
+                cell.add_pin(
+                    exit.net.clone(),
+                    m3,
+                    Rect::from_spans(
+                        trace.rect().hspan(),
+                        Span::new(boundary.top() - 3 * cfg.line(3), boundary.top()),
+                    ),
+                );
+            }
+        }
+    }
+
+    // Pins that are not moved are extended down to the bottom edge,
+    // and shifted onto the nearest track.
+    let mut shifted = Vec::new();
+    for (i, exit) in exits.iter().enumerate() {
+        if moved[i] {
+            continue;
+        }
+        if boundary.bottom() < ring.bottom() {
+            let mut trace = router.trace(exit.rect, 3);
+            trace
+                .set_width(exit.rect.width())
+                .place_cursor(Dir::Vert, false)
+                .vert_to(boundary.bottom());
+            stubs.push((i, trace.rect()));
+        }
+
+        let vspan = Span::new(boundary.bottom(), boundary.bottom() + exit.pin_height);
+        let hspan = match &v_tracks {
+            Some((grid, _)) => {
+                let center = grid
+                    .get_track(Dir::Vert, exit.rect.center().x, TrackLocator::Nearest)
+                    .center();
+                let half = exit.rect.width() / 2;
+                Span::new(center - half, center - half + exit.rect.width())
+            }
+            None => exit.rect.hspan(),
+        };
+        if hspan.start() != exit.rect.left() {
+            let jog = Rect::from_spans(
+                Span::new(
+                    std::cmp::min(hspan.start(), exit.rect.left()),
+                    std::cmp::max(hspan.stop(), exit.rect.right()),
+                ),
+                vspan,
+            );
+            router.trace(jog, 3);
+            stubs.push((i, jog));
+            shifted.push((i, jog));
+        }
+        cell.add_pin(exit.net.clone(), m3, Rect::from_spans(hspan, vspan));
+    }
+
+    let space = cfg.space(3);
+    for (i, r) in shifted.iter() {
+        let padded = r.expand(space);
+        if straps
+            .iter()
+            .any(|s| padded.hspan().intersects(&s.hspan()) && padded.vspan().intersects(&s.vspan()))
+        {
+            bail!(
+                "Pin `{}` cannot be shifted onto the m3 tracks without running into a power strap; \
+                 assign its group to an edge in the pin configuration",
+                exits[*i].net
+            );
+        }
+        for (j, exit) in exits.iter().enumerate() {
+            if *i == j || moved[j] {
+                continue;
+            }
+            let other = match shifted.iter().find(|(k, _)| *k == j) {
+                Some((_, jog)) => jog.hspan(),
+                None => exit.rect.hspan(),
+            };
+            if Span::new(r.left() - space, r.right() + space).intersects(&other) {
+                bail!(
+                    "Pin `{}` cannot be shifted onto the m3 tracks without running into pin `{}`; \
+                     assign its group to an edge in the pin configuration",
+                    exits[*i].net,
+                    exits[j].net
+                );
+            }
+        }
+    }
+
+    let space = cfg.space(3) + cfg.line(3);
+    for (i, r) in drops.iter() {
+        for (j, s) in stubs.iter() {
+            if i != j
+                && Span::new(r.left() - space, r.right() + space).intersects(&s.hspan())
+                && r.vspan().intersects(&s.vspan())
+            {
+                bail!(
+                    "The routes of pins `{}` and `{}` overlap below the guard ring; \
+                     move one of them to a different position",
+                    exits[*i].net,
+                    exits[*j].net
+                );
+            }
+        }
+    }
+
+    Ok(boundary)
+}
+
+/// Returns the routing tracks of metal `layer` given by `tracks`, and their pitch.
+///
+/// Track offsets are measured from the lower left corner of `boundary`.
+fn track_grid(
+    cfg: &RouterConfig,
+    boundary: Rect,
+    layer: LayerIdx,
+    tracks: Option<&TrackConfig>,
+) -> Result<Option<(Grid, Int)>> {
+    let tracks = match tracks {
+        Some(tracks) => tracks,
+        None => return Ok(None),
+    };
+    let (line, grid) = (cfg.line(layer), cfg.grid());
+    if tracks.pitch <= line {
+        bail!(
+            "Track pitch of m{} must be more than the minimum width of {} nm, but is {} nm",
+            layer,
+            line,
+            tracks.pitch
+        );
+    }
+    if tracks.pitch % grid != 0 || (tracks.offset - line / 2) % grid != 0 {
+        bail!(
+            "Tracks of m{} with a pitch of {} nm and an offset of {} nm do not lie on the {} nm manufacturing grid",
+            layer,
+            tracks.pitch,
+            tracks.offset,
+            grid
+        );
+    }
+
+    // Place the reference track at or just beyond the lower left corner,
+    // so that all pins are at non-negative positions relative to it.
+    let offset = tracks.offset.rem_euclid(tracks.pitch);
+    let grid = Grid::builder()
+        .line(line)
+        .space(tracks.pitch - line)
+        .center(Point::new(
+            boundary.left() + offset,
+            boundary.bottom() + offset,
+        ))
+        .grid(grid)
+        .build()?;
+    Ok(Some((grid, tracks.pitch)))
+}
+
+/// Extends `trace` along `dir` until its cursor is centered on `center`.
+fn draw_to_center(trace: &mut Trace, center: Int, dir: Dir) {
+    let span = trace.cursor_rect().span(dir);
+    let half = span.length() / 2;
+    if center < span.center() {
+        trace.draw_to(center - half, dir);
+    } else {
+        trace.draw_to(center + half, dir);
+    }
+}
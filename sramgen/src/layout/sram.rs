@@ -14,6 +14,7 @@ use crate::config::dff::DffGridParams;
 use crate::config::dout_buffer::{DoutBufArrayParams, DoutBufParams};
 use crate::config::gate::{AndParams, GateParams, Size};
 use crate::config::mux::{ReadMuxArrayParams, ReadMuxParams, WriteMuxArrayParams, WriteMuxParams};
+use crate::config::pins::{PortGroup, Side};
 use crate::config::precharge::{PrechargeArrayParams, PrechargeParams};
 use crate::config::sense_amp::SenseAmpArrayParams;
 use crate::config::sram::{ControlMode, SramParams};
@@ -30,6 +31,7 @@ use crate::layout::dout_buffer::draw_dout_buf_array;
 use crate::layout::guard_ring::{draw_guard_ring, GuardRingParams};
 use crate::layout::mux::read::draw_read_mux_array;
 use crate::layout::mux::write::draw_write_mux_array;
+use crate::layout::pins::{place_pins, PinExit};
 use crate::layout::power::{PowerSource, PowerStrapGen, PowerStrapOpts};
 use crate::layout::precharge::draw_precharge_array;
 use crate::layout::route::grid::{Grid, TrackLocator};
@@ -43,32 +45,6 @@ use crate::{bus_bit, clog2, Result};
 
 pub const M1_PWR_OVERHANG: Int = 200;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-enum Side {
-    Left,
-    Right,
-    Bottom,
-    Top,
-}
-
-impl Side {
-    #[inline]
-    pub fn dir(&self) -> Dir {
-        match *self {
-            Side::Left | Side::Right => Dir::Horiz,
-            Side::Bottom | Side::Top => Dir::Vert,
-        }
-    }
-
-    /// Indicates if this side is a positive-going direction
-    pub fn pos(&self) -> bool {
-        match *self {
-            Side::Left | Side::Bottom => false,
-            Side::Right | Side::Top => true,
-        }
-    }
-}
-
 pub struct PhysicalDesign {
     pub cell: Ptr<Cell>,
 }
@@ -1441,6 +1417,7 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
     let guard_ring_bbox = guard_ring_inst.bbox().into_rect();
 
     // Route input and output pins
+    let mut exits = Vec::new();
     #[allow(clippy::needless_range_loop)]
     for i in 0..(cols / mux_ratio) {
         let src = din_dffs.port(bus_bit("d", i)).largest_rect(m2).unwrap();
@@ -1456,14 +1433,13 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
 
         let rect = trace.rect();
         power_grid.add_padded_blockage(3, rect.expand(10));
-        cell.add_pin(
-            bus_bit("din", i),
-            m3,
-            Rect::from_spans(
-                rect.hspan(),
-                Span::new(rect.bottom(), rect.bottom() + 3 * cfg.line(3)),
-            ),
-        );
+        exits.push(PinExit {
+            group: PortGroup::Din,
+            net: bus_bit("din", i),
+            bit: i,
+            rect,
+            pin_height: 3 * cfg.line(3),
+        });
 
         // Route sense amp output to dout buffers
         for (sa_port, buf_input, buf_output, span, pin) in [
@@ -1512,14 +1488,13 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
                     .down();
                 power_grid.add_padded_blockage(2, dout_trace.rect().expand(500));
 
-                cell.add_pin(
-                    bus_bit("dout", i),
-                    m3,
-                    Rect::from_spans(
-                        dout_rect.hspan(),
-                        Span::new(dout_rect.bottom(), dout_rect.bottom() + 3 * cfg.line(3)),
-                    ),
-                );
+                exits.push(PinExit {
+                    group: PortGroup::Dout,
+                    net: bus_bit("dout", i),
+                    bit: i,
+                    rect: dout_rect,
+                    pin_height: 3 * cfg.line(3),
+                });
             }
         }
     }
@@ -1540,14 +1515,13 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
 
             let rect = trace.rect();
             power_grid.add_padded_blockage(3, rect.expand(10));
-            cell.add_pin(
-                bus_bit("wmask", i),
-                m3,
-                Rect::from_spans(
-                    rect.hspan(),
-                    Span::new(rect.bottom(), rect.bottom() + 3 * cfg.line(3)),
-                ),
-            );
+            exits.push(PinExit {
+                group: PortGroup::Wmask,
+                net: bus_bit("wmask", i),
+                bit: i,
+                rect,
+                pin_height: 3 * cfg.line(3),
+            });
         }
     }
 
@@ -1557,15 +1531,14 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
         .up()
         .set_width(420)
         .vert_to(guard_ring_bbox.bottom());
-    let clk_pin = Rect::from_spans(
-        clk_trace.rect().hspan(),
-        Span::new(
-            guard_ring_bbox.bottom(),
-            guard_ring_bbox.bottom() + 3 * cfg.line(3),
-        ),
-    );
     power_grid.add_padded_blockage(3, clk_trace.rect());
-    cell.add_pin("clk", m3, clk_pin);
+    exits.push(PinExit {
+        group: PortGroup::Ctrl,
+        net: "clk".to_string(),
+        bit: 0,
+        rect: clk_trace.rect(),
+        pin_height: 3 * cfg.line(3),
+    });
 
     // Route address and write enable pins
     for i in 0..=total_addr_bits {
@@ -1579,23 +1552,25 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
 
         let rect = trace.rect();
         power_grid.add_padded_blockage(3, rect.expand(20));
-        let net = if i == total_addr_bits {
-            "we".to_string()
+        let (group, net, bit) = if i == total_addr_bits {
+            (PortGroup::Ctrl, "we".to_string(), 1)
         } else {
-            bus_bit("addr", total_addr_bits - i - 1)
+            let bit = total_addr_bits - i - 1;
+            (PortGroup::Addr, bus_bit("addr", bit), bit)
         };
-        cell.add_pin(
+        exits.push(PinExit {
+            group,
             net,
-            m3,
-            Rect::from_spans(
-                rect.hspan(),
-                Span::new(rect.bottom(), rect.bottom() + 3 * cfg.line(2)),
-            ),
-        )
+            bit,
+            rect,
+            pin_height: 3 * cfg.line(2),
+        });
     }
 
     let straps = power_grid.generate()?;
 
+    // The m3 routes of the straps to the bottom of the guard ring.
+    let mut bottom_straps = Vec::new();
     for side in [Side::Left, Side::Right, Side::Top, Side::Bottom] {
         let (srcs, layer) = match side {
             Side::Left => (&straps.left, 2),
@@ -1626,11 +1601,50 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
                 Dir::Vert => trace.vert_to_rect(dst),
             };
             trace.contact_down(dst);
+            if side == Side::Bottom {
+                bottom_straps.push(trace.rect());
+            }
         }
     }
 
-    cell.add_pin("vdd", m2, guard_ring.vdd_ring.top());
-    cell.add_pin("vss", m2, guard_ring.vss_ring.top());
+    let boundary = place_pins(
+        &mut router,
+        &mut cell,
+        guard_ring_bbox,
+        &exits,
+        &bottom_straps,
+        &params.pins,
+    )?;
+
+    // Expose the supplies on the top edge. If pins are routed above the guard ring,
+    // the supplies are brought up to the edge on m3 from the top corners of their rings,
+    // outside the range of the top pins: vdd on the left, and vss on the right.
+    for (net, ring) in [("vdd", &guard_ring.vdd_ring), ("vss", &guard_ring.vss_ring)] {
+        if boundary.top() == guard_ring_bbox.top() {
+            cell.add_pin(net, m2, ring.top());
+            continue;
+        }
+        let corner = if net == "vdd" {
+            ring.left()
+        } else {
+            ring.right()
+        };
+        let contact = Rect::from_spans(corner.hspan(), ring.top().vspan());
+        let mut trace = router.trace(contact, 2);
+        trace
+            .place_cursor_centered()
+            .up()
+            .set_min_width()
+            .vert_to(boundary.top());
+        cell.add_pin(
+            net,
+            m3,
+            Rect::from_spans(
+                trace.rect().hspan(),
+                Span::new(boundary.top() - 3 * cfg.line(3), boundary.top()),
+            ),
+        );
+    }
 
     let routing = router.finish();
 
@@ -1643,6 +1657,10 @@ pub fn draw_sram(lib: &mut PdkLib, params: &SramParams) -> Result<PhysicalDesign
     cell.layout_mut()
         .draw_rect(lib.pdk.get_layerkey("dnwell").unwrap(), dnwell_rect);
 
+    // Draw the macro boundary
+    cell.layout_mut()
+        .draw_rect(lib.pdk.get_layerkey("outline").unwrap(), boundary);
+
     let ptr = Ptr::new(cell);
     lib.lib.cells.push(ptr.clone());
 
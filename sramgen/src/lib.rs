@@ -9,6 +9,7 @@ pub mod config;
 pub mod layout;
 #[cfg(feature = "liberate_mx")]
 pub mod liberate;
+pub mod liberty;
 pub mod paths;
 pub mod plan;
 pub mod schematic;
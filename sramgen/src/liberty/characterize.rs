@@ -0,0 +1,398 @@
+//! An open source SRAM characterization flow.
+//!
+//! Timing is measured by simulating the functional SRAM testbench
+//! at each combination of input slew and output load. Setup and hold times
+//! are found by bisecting on the skew between an input and the clock,
+//! using functional correctness as the pass criterion.
+
+use anyhow::{anyhow, bail, Context};
+use derive_builder::Builder;
+use psf_ascii::parser::transient::TransientData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::sram::SramParams;
+use crate::config::tech::TechConfig;
+use crate::liberty::{ConstraintTables, DelayTables, SramLib, SramPower};
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::power::measure_power;
+use crate::verification::pvt::PvtCorner;
+use crate::verification::timing::{crossings, Edge, DELAY_THRESHOLD};
+use crate::verification::{
+    simulate_testbench, sram_tb_builder, verify_simulation, Op, PortClass, SimulatorKind, Stimulus,
+    TbParams, TbParamsBuilder, TestCase,
+};
+use crate::{bus_bit, Result};
+
+/// Options of the native characterization flow.
+///
+/// Read from the `[lib]` section of the SRAM configuration; omitted entries take the defaults below.
+#[derive(Debug, Clone, PartialEq, Builder, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CharParams {
+    /// Input slews at which to characterize the SRAM.
+    #[builder(default = "vec![50e-12, 200e-12, 800e-12]", setter(into))]
+    pub slews: Vec<f64>,
+    /// Output loads at which to characterize the SRAM.
+    #[builder(default = "vec![5e-15, 20e-15, 80e-15]", setter(into))]
+    pub loads: Vec<f64>,
+    /// Clock period of the characterization testbenches.
+    #[builder(default = "20e-9")]
+    pub clk_period: f64,
+    /// Resolution of setup and hold time searches.
+    #[builder(default = "10e-12")]
+    pub tolerance: f64,
+}
+
+impl CharParams {
+    #[inline]
+    pub fn builder() -> CharParamsBuilder {
+        CharParamsBuilder::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.slews.is_empty() || self.loads.is_empty() {
+            bail!("At least one slew and one load are required for characterization");
+        }
+        if let Some(slew) = self.slews.iter().find(|&&slew| slew <= 0.0) {
+            bail!("Characterization slews must be positive, but {slew} was given");
+        }
+        if let Some(load) = self.loads.iter().find(|&&load| load < 0.0) {
+            bail!("Characterization loads must not be negative, but {load} was given");
+        }
+        if self.clk_period <= 0.0 {
+            bail!("Characterization clock period must be positive");
+        }
+        if self.tolerance <= 0.0 {
+            bail!("Characterization tolerance must be positive");
+        }
+        Ok(())
+    }
+}
+
+impl Default for CharParams {
+    fn default() -> Self {
+        Self::builder().build().unwrap()
+    }
+}
+
+/// Characterizes the SRAM described by `params` at `corner`.
+///
+/// Simulations are run in `work_dir/lib/<corner>`.
+pub fn characterize_sram(
+    params: &SramParams,
+    tech: &TechConfig,
+    corner: &PvtCorner,
+    simulator: SimulatorKind,
+    work_dir: impl AsRef<Path>,
+    char_params: &CharParams,
+) -> Result<SramLib> {
+    let work_dir = work_dir.as_ref();
+    let models = tech.models(simulator);
+    if models.is_empty() {
+        bail!("No {simulator} models specified in the tech configuration");
+    }
+    tech.check_corner(&corner.process)?;
+    let input_cap = tech
+        .input_cap
+        .ok_or_else(|| anyhow!("No input capacitance specified in the tech configuration"))?;
+    char_params.validate()?;
+
+    let char_dir = work_dir.join("lib").join(corner.name());
+    let test_case = char_test_case(params, char_params.clk_period)?;
+    let includes = models.includes(&corner.process)?;
+    let libs = models.libs(&corner.process)?;
+
+    let tb = |dir: PathBuf, slew: f64, load: f64| -> Result<TbParamsBuilder> {
+        let mut tb = sram_tb_builder(
+            params,
+            work_dir,
+            &params.name,
+            simulator.verification_task(),
+            &Stimulus::default(),
+        )?;
+        tb.test_case(test_case.clone())
+            .simulator(simulator)
+            .vdd(corner.vdd)
+            .temp(corner.temp)
+            .tr(slew)
+            .tf(slew)
+            .c_load(load)
+            .idle_time(4.0 * char_params.clk_period)
+            .includes(includes.clone())
+            .libs(libs.clone())
+            .work_dir(dir);
+        Ok(tb)
+    };
+
+    let num_slews = char_params.slews.len();
+    let num_loads = char_params.loads.len();
+    let table = || vec![vec![0.0; num_loads]; num_slews];
+    let mut clk_to_dout = DelayTables {
+        cell_rise: table(),
+        cell_fall: table(),
+        rise_transition: table(),
+        fall_transition: table(),
+    };
+    let mut power = None;
+
+    for (i, &slew) in char_params.slews.iter().enumerate() {
+        for (j, &load) in char_params.loads.iter().enumerate() {
+            let tb = tb(char_dir.join(format!("delay_{i}_{j}")), slew, load)?.build()?;
+            let data = simulate_testbench(&tb)?;
+            verify_simulation(&data, &tb).with_context(|| {
+                format!("SRAM failed functional verification at slew {slew}, load {load}")
+            })?;
+
+            let (rise, fall) = measure_read_delays(&data, &tb)?;
+            let tables = &mut clk_to_dout;
+            for (cell, transition, delays) in [
+                (&mut tables.cell_rise, &mut tables.rise_transition, rise),
+                (&mut tables.cell_fall, &mut tables.fall_transition, fall),
+            ] {
+                cell[i][j] = delays.delay;
+                transition[i][j] = delays.transition;
+            }
+
+            // Power is reported at the smallest slew and load.
+            if i == 0 && j == 0 {
+                let report = measure_power(&data, &tb)?;
+                power = Some(SramPower {
+                    read_energy: report
+                        .read_energy
+                        .ok_or_else(|| anyhow!("No reads found in characterization testbench"))?,
+                    write_energy: report
+                        .write_energy
+                        .ok_or_else(|| anyhow!("No writes found in characterization testbench"))?,
+                    leakage_power: report.leakage_power.ok_or_else(|| {
+                        anyhow!("No idle time found in characterization testbench")
+                    })?,
+                });
+            }
+        }
+    }
+
+    let mut inputs = vec![PortClass::Addr, PortClass::DataIn, PortClass::WriteEnable];
+    if params.wmask_width > 1 {
+        inputs.push(PortClass::WriteMask);
+    }
+
+    // Constraints are measured at the smallest load.
+    let load = char_params.loads[0];
+    let period = char_params.clk_period;
+    let mut constraints = Vec::with_capacity(inputs.len());
+    for port_class in inputs {
+        let mut tables = ConstraintTables::default();
+        for (i, &slew) in char_params.slews.iter().enumerate() {
+            let mut run = 0;
+            let mut passes = |setup: f64| -> Result<bool> {
+                let dir =
+                    char_dir.join(format!("constraint_{port_class:?}_{i}_{run}").to_lowercase());
+                run += 1;
+                let tb = tb(dir, slew, load)?
+                    .setup_times(HashMap::from([(port_class, setup)]))
+                    .build()?;
+                let data = simulate_testbench(&tb)?;
+                Ok(verify_simulation(&data, &tb).is_ok())
+            };
+
+            let setup = bisect(
+                char_params.tolerance,
+                period / 2.0,
+                char_params.tolerance,
+                &mut passes,
+            )
+            .with_context(|| format!("Error measuring setup time of {port_class:?}"))?;
+
+            // Inputs that change `hold` after a clock edge transition `period - hold`
+            // before the next clock edge. A negative hold time means that the input
+            // may change before the clock edge that captures its previous value.
+            let hold = bisect(-period / 2.0, period / 2.0, char_params.tolerance, |hold| {
+                passes(period - hold)
+            })
+            .with_context(|| format!("Error measuring hold time of {port_class:?}"))?;
+
+            tables.setup.push(setup);
+            tables.hold.push(hold);
+        }
+        constraints.push((port_class, tables));
+    }
+
+    Ok(SramLib {
+        cell_name: params.name.clone(),
+        corner: corner.clone(),
+        num_words: params.num_words,
+        data_width: params.data_width,
+        addr_width: params.addr_width,
+        wmask_width: params.wmask_width,
+        slews: char_params.slews.clone(),
+        loads: char_params.loads.clone(),
+        input_cap,
+        clk_to_dout,
+        constraints,
+        power,
+    })
+}
+
+/// Finds the smallest value in `[lo, hi]` for which `passes` returns true,
+/// to within `tol`.
+///
+/// Assumes that `passes` is monotonic. Fails if `passes(hi)` is false.
+pub fn bisect(
+    mut lo: f64,
+    mut hi: f64,
+    tol: f64,
+    mut passes: impl FnMut(f64) -> Result<bool>,
+) -> Result<f64> {
+    if !passes(hi)? {
+        bail!("Test fails at the upper bound of the search range ({hi})");
+    }
+    if passes(lo)? {
+        return Ok(lo);
+    }
+    while hi - lo > tol {
+        let mid = (lo + hi) / 2.0;
+        if passes(mid)? {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(hi)
+}
+
+/// A test case in which every address, data, and write mask bit
+/// toggles on every cycle, and every data output toggles on every read.
+fn char_test_case(params: &SramParams, clk_period: f64) -> Result<TestCase> {
+    let &SramParams {
+        wmask_width,
+        data_width,
+        addr_width,
+        ..
+    } = params;
+
+    let addr0 = BitSignal::zeros(addr_width);
+    let addr1 = BitSignal::ones(addr_width);
+    let data0 = BitSignal::from_u64(0x5555555555555555u64, data_width);
+    let data1 = BitSignal::from_u64(0xAAAAAAAAAAAAAAAAu64, data_width);
+
+    let mut ops = vec![
+        Op::Write {
+            addr: addr0.clone(),
+            data: data0.clone(),
+        },
+        Op::Write {
+            addr: addr1.clone(),
+            data: data1.clone(),
+        },
+        Op::Read {
+            addr: addr0.clone(),
+        },
+        Op::Read {
+            addr: addr1.clone(),
+        },
+        Op::Read {
+            addr: addr0.clone(),
+        },
+    ];
+
+    if wmask_width > 1 {
+        ops.extend([
+            Op::WriteMasked {
+                addr: addr1.clone(),
+                data: data0,
+                mask: BitSignal::from_u64(0x5555555555555555u64, wmask_width),
+            },
+            Op::WriteMasked {
+                addr: addr0,
+                data: data1,
+                mask: BitSignal::from_u64(0xAAAAAAAAAAAAAAAAu64, wmask_width),
+            },
+            Op::Read {
+                addr: addr1.clone(),
+            },
+        ]);
+    }
+
+    Ok(TestCase::builder()
+        .clk_period(clk_period)
+        .ops(ops)
+        .build()?)
+}
+
+/// The worst case delay and transition time of data outputs switching in one direction.
+#[derive(Default)]
+struct EdgeDelays {
+    delay: f64,
+    transition: f64,
+    found: bool,
+}
+
+impl EdgeDelays {
+    fn add(&mut self, delay: f64, transition: f64) {
+        self.delay = self.delay.max(delay);
+        self.transition = self.transition.max(transition);
+        self.found = true;
+    }
+}
+
+/// Measures the worst case clock to data output delays and output transition times
+/// over all reads in the test case, as `(rise, fall)`.
+fn measure_read_delays(data: &TransientData, tb: &TbParams) -> Result<(EdgeDelays, EdgeDelays)> {
+    let signal = |name: &str| {
+        data.signal(name)
+            .ok_or_else(|| anyhow!("Unable to find signal {}", name))
+    };
+
+    let time = &data.time;
+    let vdd = tb.vdd;
+    let period = tb.test_case.clk_period;
+    let clk = signal(&format!("v({})", tb.clk_port))?;
+
+    let mut rise = EdgeDelays::default();
+    let mut fall = EdgeDelays::default();
+
+    for (i, op) in tb.test_case.ops.iter().enumerate() {
+        if !matches!(op, Op::Read { .. }) {
+            continue;
+        }
+        let t_cycle = (i + 1) as f64 * period;
+        let t_next = t_cycle + period;
+        let t_clk = *crossings(
+            time,
+            clk,
+            DELAY_THRESHOLD * vdd,
+            Edge::Rising,
+            t_cycle,
+            t_cycle + period / 2.0,
+        )
+        .first()
+        .ok_or_else(|| anyhow!("Unable to find a rising clock edge at time {}", t_cycle))?;
+
+        for j in 0..tb.data_width {
+            let dout = signal(&format!("v({})", bus_bit(&tb.data_out_port, j)))?;
+            for (edge, lo, hi, delays) in [
+                (Edge::Rising, 0.2, 0.8, &mut rise),
+                (Edge::Falling, 0.8, 0.2, &mut fall),
+            ] {
+                let last = |threshold: f64| {
+                    crossings(time, dout, threshold * vdd, edge, t_clk, t_next)
+                        .last()
+                        .copied()
+                };
+                if let (Some(t_mid), Some(t_lo), Some(t_hi)) =
+                    (last(DELAY_THRESHOLD), last(lo), last(hi))
+                {
+                    delays.add(t_mid - t_clk, t_hi - t_lo);
+                }
+            }
+        }
+    }
+
+    if !rise.found || !fall.found {
+        bail!("Data outputs did not both rise and fall during characterization");
+    }
+
+    Ok((rise, fall))
+}
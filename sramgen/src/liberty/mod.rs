@@ -0,0 +1,356 @@
+//! Liberty (`.lib`) timing models for generated SRAMs.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+use std::path::Path;
+
+use crate::verification::pvt::PvtCorner;
+use crate::verification::PortClass;
+use crate::Result;
+
+pub mod characterize;
+
+/// Liberty time unit, in seconds.
+pub const TIME_UNIT: f64 = 1e-9;
+/// Liberty capacitance unit, in farads.
+pub const CAP_UNIT: f64 = 1e-12;
+/// Liberty leakage power unit, in watts.
+pub const LEAKAGE_POWER_UNIT: f64 = 1e-9;
+/// Liberty internal energy unit, in joules.
+///
+/// Internal power is specified in units of capacitance times voltage squared.
+pub const ENERGY_UNIT: f64 = 1e-12;
+
+/// The tool used to characterize SRAMs and generate Liberty files.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+pub enum Characterizer {
+    /// Characterize the SRAM using the built-in simulation flow.
+    Native,
+    /// Characterize the SRAM using Liberate MX.
+    LiberateMx,
+}
+
+impl Default for Characterizer {
+    /// Liberate MX if support for it is enabled; the native flow otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "liberate_mx") {
+            Self::LiberateMx
+        } else {
+            Self::Native
+        }
+    }
+}
+
+impl Display for Characterizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Native => write!(f, "native"),
+            Self::LiberateMx => write!(f, "liberate-mx"),
+        }
+    }
+}
+
+/// NLDM tables indexed by input slew, then output load.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DelayTables {
+    pub cell_rise: Vec<Vec<f64>>,
+    pub cell_fall: Vec<Vec<f64>>,
+    pub rise_transition: Vec<Vec<f64>>,
+    pub fall_transition: Vec<Vec<f64>>,
+}
+
+/// Setup and hold times of an input, indexed by slew.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstraintTables {
+    pub setup: Vec<f64>,
+    pub hold: Vec<f64>,
+}
+
+/// Energy and leakage of an SRAM, in SI units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SramPower {
+    /// Average energy per read.
+    pub read_energy: f64,
+    /// Average energy per write.
+    pub write_energy: f64,
+    /// Power drawn while idle.
+    pub leakage_power: f64,
+}
+
+/// The characterized timing of an SRAM at a single corner.
+///
+/// All quantities are in SI units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SramLib {
+    pub cell_name: String,
+    pub corner: PvtCorner,
+    pub num_words: usize,
+    pub data_width: usize,
+    pub addr_width: usize,
+    pub wmask_width: usize,
+    /// Input slews at which the SRAM was characterized.
+    pub slews: Vec<f64>,
+    /// Output loads at which the SRAM was characterized.
+    pub loads: Vec<f64>,
+    /// Capacitance reported for each input pin.
+    pub input_cap: f64,
+    /// Clock to data output delay.
+    pub clk_to_dout: DelayTables,
+    /// Timing constraints of each input relative to the clock.
+    pub constraints: Vec<(PortClass, ConstraintTables)>,
+    pub power: Option<SramPower>,
+}
+
+impl SramLib {
+    /// The name of the Liberty library, eg. `sramgen_sram_32x32m2w8_simple_tt_1p8v_25c`.
+    pub fn lib_name(&self) -> String {
+        format!("{}_{}", self.cell_name, self.corner.name())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_liberty()?)?;
+        Ok(())
+    }
+
+    pub fn to_liberty(&self) -> Result<String> {
+        let mut out = String::new();
+        let corner = &self.corner;
+        let lib_name = self.lib_name();
+
+        writeln!(out, "library ({lib_name}) {{")?;
+        writeln!(out, "  delay_model : table_lookup;")?;
+        writeln!(out, "  time_unit : \"1ns\";")?;
+        writeln!(out, "  voltage_unit : \"1V\";")?;
+        writeln!(out, "  current_unit : \"1uA\";")?;
+        writeln!(out, "  leakage_power_unit : \"1nW\";")?;
+        writeln!(out, "  pulling_resistance_unit : \"1kohm\";")?;
+        writeln!(out, "  capacitive_load_unit (1, pf);")?;
+        writeln!(out)?;
+        for (kind, pct) in [
+            ("input_threshold_pct", 50),
+            ("output_threshold_pct", 50),
+            ("slew_lower_threshold_pct", 20),
+            ("slew_upper_threshold_pct", 80),
+        ] {
+            writeln!(out, "  {kind}_rise : {pct};")?;
+            writeln!(out, "  {kind}_fall : {pct};")?;
+        }
+        writeln!(out, "  slew_derate_from_library : 1;")?;
+        writeln!(out)?;
+        writeln!(out, "  nom_process : 1;")?;
+        writeln!(out, "  nom_voltage : {};", corner.vdd)?;
+        writeln!(out, "  nom_temperature : {};", corner.temp)?;
+        writeln!(out, "  operating_conditions ({}) {{", corner.name())?;
+        writeln!(out, "    process : 1;")?;
+        writeln!(out, "    voltage : {};", corner.vdd)?;
+        writeln!(out, "    temperature : {};", corner.temp)?;
+        writeln!(out, "  }}")?;
+        writeln!(out, "  default_operating_conditions : {};", corner.name())?;
+        writeln!(out, "  voltage_map (vdd, {});", corner.vdd)?;
+        writeln!(out, "  voltage_map (vss, 0);")?;
+        writeln!(out)?;
+
+        writeln!(out, "  lu_table_template (delay_template) {{")?;
+        writeln!(out, "    variable_1 : input_net_transition;")?;
+        writeln!(out, "    variable_2 : total_output_net_capacitance;")?;
+        writeln!(
+            out,
+            "    index_1 (\"{}\");",
+            fmt_values(&self.slews, TIME_UNIT)
+        )?;
+        writeln!(
+            out,
+            "    index_2 (\"{}\");",
+            fmt_values(&self.loads, CAP_UNIT)
+        )?;
+        writeln!(out, "  }}")?;
+        writeln!(out, "  lu_table_template (constraint_template) {{")?;
+        writeln!(out, "    variable_1 : related_pin_transition;")?;
+        writeln!(
+            out,
+            "    index_1 (\"{}\");",
+            fmt_values(&self.slews, TIME_UNIT)
+        )?;
+        writeln!(out, "  }}")?;
+        writeln!(out)?;
+
+        let mut buses = vec![("addr", self.addr_width), ("data", self.data_width)];
+        if self.wmask_width > 1 {
+            buses.push(("wmask", self.wmask_width));
+        }
+        for (name, width) in buses {
+            writeln!(out, "  type ({name}_bus) {{")?;
+            writeln!(out, "    base_type : array;")?;
+            writeln!(out, "    data_type : bit;")?;
+            writeln!(out, "    bit_width : {width};")?;
+            writeln!(out, "    bit_from : {};", width - 1)?;
+            writeln!(out, "    bit_to : 0;")?;
+            writeln!(out, "    downto : true;")?;
+            writeln!(out, "  }}")?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "  cell ({}) {{", self.cell_name)?;
+        writeln!(out, "    interface_timing : true;")?;
+        if let Some(power) = &self.power {
+            writeln!(
+                out,
+                "    cell_leakage_power : {};",
+                fmt_value(power.leakage_power, LEAKAGE_POWER_UNIT)
+            )?;
+            writeln!(out, "    leakage_power () {{")?;
+            writeln!(
+                out,
+                "      value : {};",
+                fmt_value(power.leakage_power, LEAKAGE_POWER_UNIT)
+            )?;
+            writeln!(out, "      related_pg_pin : vdd;")?;
+            writeln!(out, "    }}")?;
+        }
+        writeln!(out, "    memory () {{")?;
+        writeln!(out, "      type : ram;")?;
+        writeln!(out, "      address_width : {};", self.addr_width)?;
+        writeln!(out, "      word_width : {};", self.data_width)?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "    pg_pin (vdd) {{")?;
+        writeln!(out, "      voltage_name : vdd;")?;
+        writeln!(out, "      pg_type : primary_power;")?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "    pg_pin (vss) {{")?;
+        writeln!(out, "      voltage_name : vss;")?;
+        writeln!(out, "      pg_type : primary_ground;")?;
+        writeln!(out, "    }}")?;
+
+        writeln!(out, "    pin (clk) {{")?;
+        self.write_input_attrs(&mut out)?;
+        writeln!(out, "      clock : true;")?;
+        if let Some(power) = &self.power {
+            for (when, energy) in [("!we", power.read_energy), ("we", power.write_energy)] {
+                writeln!(out, "      internal_power () {{")?;
+                writeln!(out, "        when : \"{when}\";")?;
+                writeln!(out, "        related_pg_pin : vdd;")?;
+                writeln!(out, "        rise_power (scalar) {{")?;
+                writeln!(
+                    out,
+                    "          values (\"{}\");",
+                    fmt_value(energy, ENERGY_UNIT)
+                )?;
+                writeln!(out, "        }}")?;
+                writeln!(out, "        fall_power (scalar) {{")?;
+                writeln!(out, "          values (\"0\");")?;
+                writeln!(out, "        }}")?;
+                writeln!(out, "      }}")?;
+            }
+        }
+        writeln!(out, "    }}")?;
+
+        for (port_class, constraints) in self.constraints.iter() {
+            let (group, name, bus_type) = match port_class {
+                PortClass::Addr => ("bus", "addr", Some("addr_bus")),
+                PortClass::DataIn => ("bus", "din", Some("data_bus")),
+                PortClass::WriteMask => ("bus", "wmask", Some("wmask_bus")),
+                PortClass::WriteEnable => ("pin", "we", None),
+                other => anyhow::bail!("Cannot write timing constraints for {:?}", other),
+            };
+            writeln!(out, "    {group} ({name}) {{")?;
+            if let Some(bus_type) = bus_type {
+                writeln!(out, "      bus_type : {bus_type};")?;
+            }
+            self.write_input_attrs(&mut out)?;
+            for (timing_type, values) in [
+                ("setup_rising", &constraints.setup),
+                ("hold_rising", &constraints.hold),
+            ] {
+                writeln!(out, "      timing () {{")?;
+                writeln!(out, "        related_pin : clk;")?;
+                writeln!(out, "        timing_type : {timing_type};")?;
+                for table in ["rise_constraint", "fall_constraint"] {
+                    writeln!(out, "        {table} (constraint_template) {{")?;
+                    writeln!(
+                        out,
+                        "          values (\"{}\");",
+                        fmt_values(values, TIME_UNIT)
+                    )?;
+                    writeln!(out, "        }}")?;
+                }
+                writeln!(out, "      }}")?;
+            }
+            writeln!(out, "    }}")?;
+        }
+
+        writeln!(out, "    bus (dout) {{")?;
+        writeln!(out, "      bus_type : data_bus;")?;
+        writeln!(out, "      direction : output;")?;
+        writeln!(
+            out,
+            "      max_capacitance : {};",
+            fmt_value(self.loads.iter().copied().fold(0.0, f64::max), CAP_UNIT)
+        )?;
+        writeln!(out, "      related_power_pin : vdd;")?;
+        writeln!(out, "      related_ground_pin : vss;")?;
+        writeln!(out, "      timing () {{")?;
+        writeln!(out, "        related_pin : clk;")?;
+        writeln!(out, "        timing_type : rising_edge;")?;
+        writeln!(out, "        timing_sense : non_unate;")?;
+        let tables = &self.clk_to_dout;
+        for (name, values) in [
+            ("cell_rise", &tables.cell_rise),
+            ("cell_fall", &tables.cell_fall),
+            ("rise_transition", &tables.rise_transition),
+            ("fall_transition", &tables.fall_transition),
+        ] {
+            write_table(&mut out, name, "delay_template", values)?;
+        }
+        writeln!(out, "      }}")?;
+        writeln!(out, "    }}")?;
+
+        writeln!(out, "  }}")?;
+        writeln!(out, "}}")?;
+
+        Ok(out)
+    }
+
+    fn write_input_attrs(&self, out: &mut String) -> Result<()> {
+        writeln!(out, "      direction : input;")?;
+        writeln!(
+            out,
+            "      capacitance : {};",
+            fmt_value(self.input_cap, CAP_UNIT)
+        )?;
+        writeln!(out, "      related_power_pin : vdd;")?;
+        writeln!(out, "      related_ground_pin : vss;")?;
+        Ok(())
+    }
+}
+
+fn write_table(out: &mut String, name: &str, template: &str, values: &[Vec<f64>]) -> Result<()> {
+    writeln!(out, "        {name} ({template}) {{")?;
+    let rows = values
+        .iter()
+        .map(|row| format!("\"{}\"", fmt_values(row, TIME_UNIT)))
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "          values ({});",
+        rows.join(", \\\n            ")
+    )?;
+    writeln!(out, "        }}")?;
+    Ok(())
+}
+
+/// Formats `x` in multiples of `unit`.
+fn fmt_value(x: f64, unit: f64) -> String {
+    format!("{:.6}", x / unit)
+}
+
+fn fmt_values(values: &[f64], unit: f64) -> String {
+    values
+        .iter()
+        .map(|&x| fmt_value(x, unit))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,4 +1,5 @@
 use crate::config::sram::SramConfig;
+use crate::schematic::NetlistFormat;
 use std::path::{Path, PathBuf};
 
 pub fn out_sram(config: &SramConfig) -> String {
@@ -17,6 +18,15 @@ pub fn out_bin(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
     PathBuf::from(work_dir.as_ref()).join(format!("{}.pb.bin", name))
 }
 
+pub fn out_spice(work_dir: impl AsRef<Path>, name: &str, format: NetlistFormat) -> PathBuf {
+    let ext = match format {
+        NetlistFormat::NgSpice => "ngspice.spice",
+        NetlistFormat::Spectre => "spectre.spice",
+        NetlistFormat::Cdl => "spice",
+    };
+    PathBuf::from(work_dir.as_ref()).join(format!("{}.{}", name, ext))
+}
+
 pub fn out_gds(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
     PathBuf::from(work_dir.as_ref()).join(format!("{}.gds", name))
 }
@@ -25,11 +35,18 @@ pub fn out_verilog(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
     PathBuf::from(work_dir.as_ref()).join(format!("{}.v", name))
 }
 
-#[cfg(feature = "abstract_lef")]
 pub fn out_lef(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
     PathBuf::from(work_dir.as_ref()).join(format!("{}.lef", name))
 }
 
+pub fn out_def(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
+    PathBuf::from(work_dir.as_ref()).join(format!("{}.def", name))
+}
+
+pub fn out_lib(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
+    PathBuf::from(work_dir.as_ref()).join(format!("{}.lib", name))
+}
+
 pub fn out_pex(work_dir: impl AsRef<Path>, name: &str) -> PathBuf {
     PathBuf::from(work_dir.as_ref()).join(format!("{}.pex.netlist", name))
 }
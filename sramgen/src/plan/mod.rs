@@ -1,14 +1,30 @@
 use crate::cli::progress::StepContext;
 use crate::config::sram::{ControlMode, SramConfig, SramParams};
+use crate::config::tech::TechConfig;
+use crate::layout::def::save_def;
+use crate::layout::lef::{save_lef, sram_lef_params, LefMacro, LefTool};
 use crate::layout::sram::draw_sram;
-use crate::paths::{out_bin, out_gds, out_pex, out_sram, out_verilog};
+use crate::liberty::characterize::{characterize_sram, CharParams};
+use crate::liberty::Characterizer;
+use crate::paths::{out_bin, out_def, out_gds, out_lef, out_lib, out_pex, out_sram, out_verilog};
 use crate::plan::extract::ExtractionResult;
 use crate::schematic::sram::sram;
 use crate::schematic::{generate_netlist, save_modules};
+use crate::verification::drc::{run_sram_drc, DrcTool};
+use crate::verification::lvs::{run_sram_lvs, LvsTool};
+use crate::verification::pvt::PvtCorner;
+use crate::verification::read_margin::{run_read_margin, ReadMarginParams};
+use crate::verification::snm::run_snm;
+use crate::verification::{
+    run_sram_testbench, sram_coverage, SimOptions, SimulatorKind, VerificationTask,
+};
 use crate::verilog::save_1rw_verilog;
 use crate::{clog2, Result};
 use anyhow::{bail, Context};
+use layout21::raw::Cell;
+use layout21::utils::Ptr;
 use pdkprims::tech::sky130;
+use pdkprims::Pdk;
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -19,6 +35,12 @@ pub mod extract;
 /// Has a 1-1 mapping with a schematic.
 pub struct SramPlan {
     pub sram_params: SramParams,
+    /// Models and rule decks used to verify the SRAM.
+    pub tech: TechConfig,
+    /// Corners at which to simulate the SRAM.
+    pub corners: Vec<PvtCorner>,
+    /// Options of the native Liberty characterization flow.
+    pub char_params: CharParams,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
@@ -27,24 +49,16 @@ pub enum TaskKey {
     GenerateNetlist,
     GenerateLayout,
     GenerateVerilog,
-    #[cfg(feature = "abstract_lef")]
     GenerateLef,
-    #[cfg(feature = "calibre")]
+    GenerateDef,
     RunDrc,
-    #[cfg(feature = "calibre")]
     RunLvs,
     #[cfg(all(feature = "calibre", feature = "pex"))]
     RunPex,
-    #[cfg(feature = "liberate_mx")]
     GenerateLib,
-    #[cfg(feature = "spectre")]
-    RunSpectre,
-    #[cfg(any(
-        feature = "abstract_lef",
-        feature = "liberate_mx",
-        feature = "calibre",
-        feature = "spectre"
-    ))]
+    RunSim,
+    RunReadMargin,
+    RunSnm,
     All,
 }
 
@@ -53,6 +67,20 @@ pub struct ExecutePlanParams<'a> {
     pub plan: &'a SramPlan,
     pub tasks: &'a HashSet<TaskKey>,
     pub ctx: Option<&'a mut StepContext>,
+    /// Options for functional verification.
+    ///
+    /// The simulator is also used to generate Liberty files natively.
+    pub sim: SimOptions,
+    /// Tool used to generate LEF files.
+    pub lef_tool: LefTool,
+    /// Tool used to generate Liberty files.
+    pub characterizer: Characterizer,
+    /// Tool used to run DRC.
+    pub drc_tool: DrcTool,
+    /// Tool used to run LVS.
+    pub lvs_tool: LvsTool,
+    /// Options for Monte Carlo read margin analysis.
+    pub read_margin: ReadMarginParams,
 }
 
 pub fn generate_plan(
@@ -65,6 +93,11 @@ pub fn generate_plan(
         mux_ratio,
         write_size,
         control,
+        ref netlist_formats,
+        ref tech,
+        ref corners,
+        pins,
+        ref lib,
     } = config;
 
     if control != ControlMode::Simple && control != ControlMode::ReplicaV1 {
@@ -75,6 +108,25 @@ pub fn generate_plan(
     if data_width % write_size != 0 {
         bail!("Data width must be a multiple of write size");
     }
+    if netlist_formats.is_empty() {
+        bail!("At least one netlist format must be specified");
+    }
+    lib.validate()
+        .with_context(|| "Invalid `[lib]` configuration")?;
+
+    let tech = TechConfig::load(tech).with_context(|| "Error loading tech configuration")?;
+
+    let corners = if corners.is_empty() {
+        vec![PvtCorner::nominal(&tech)]
+    } else {
+        corners.clone()
+    };
+    for corner in corners.iter() {
+        tech.check_corner(&corner.process)?;
+        if corner.vdd <= 0.0 {
+            bail!("Supply voltage must be positive at corner {}", corner);
+        }
+    }
 
     let name = out_sram(config);
     let rows = (num_words / mux_ratio) as usize;
@@ -102,7 +154,12 @@ pub fn generate_plan(
             data_width,
             addr_width,
             control,
+            pins,
+            netlist_formats: netlist_formats.clone(),
         },
+        tech,
+        corners,
+        char_params: lib.clone(),
     })
 }
 
@@ -114,12 +171,6 @@ macro_rules! try_finish_task {
     };
 }
 
-#[cfg(any(
-    feature = "abstract_lef",
-    feature = "liberate_mx",
-    feature = "calibre",
-    feature = "spectre"
-))]
 macro_rules! try_execute_task {
     ( $tasks:expr, $task:expr, $body:expr, $ctx:expr) => {
         if $tasks.contains(&$task) || $tasks.contains(&TaskKey::All) {
@@ -130,6 +181,8 @@ macro_rules! try_execute_task {
 }
 
 pub fn execute_plan(params: ExecutePlanParams) -> Result<()> {
+    check_netlist_formats(&params)?;
+
     let ExecutePlanParams {
         work_dir,
         plan,
@@ -143,16 +196,20 @@ pub fn execute_plan(params: ExecutePlanParams) -> Result<()> {
 
     let name = &plan.sram_params.name;
 
-    let bin_path = out_bin(work_dir, name);
-    save_modules(&bin_path, name, modules).with_context(|| "Error saving netlist binaries")?;
+    for &format in plan.sram_params.netlist_formats.iter() {
+        let bin_path = out_bin(work_dir, &format!("{name}_{format}"));
+        save_modules(&bin_path, name, modules.clone(), format)
+            .with_context(|| "Error saving netlist binaries")?;
 
-    generate_netlist(&bin_path, work_dir)
-        .with_context(|| "Error converting netlists to SPICE format")?;
+        generate_netlist(&bin_path, work_dir, format)
+            .with_context(|| format!("Error converting netlists to {format} SPICE format"))?;
+    }
 
     try_finish_task!(ctx, TaskKey::GenerateNetlist);
 
     let mut lib = sky130::pdk_lib(name)?;
-    draw_sram(&mut lib, &plan.sram_params).with_context(|| "Error generating SRAM layout")?;
+    let sram_layout =
+        draw_sram(&mut lib, &plan.sram_params).with_context(|| "Error generating SRAM layout")?;
 
     let gds_path = out_gds(work_dir, name);
     lib.save_gds(&gds_path)
@@ -166,39 +223,51 @@ pub fn execute_plan(params: ExecutePlanParams) -> Result<()> {
 
     try_finish_task!(ctx, TaskKey::GenerateVerilog);
 
-    #[cfg(feature = "abstract_lef")]
-    {
-        try_execute_task!(
-            params.tasks,
-            TaskKey::GenerateLef,
-            crate::abs::run_sram_abstract(
-                work_dir,
-                name,
-                &crate::paths::out_lef(work_dir, name),
-                &gds_path,
-                &verilog_path
-            )?,
-            ctx
-        );
-    }
+    try_execute_task!(
+        params.tasks,
+        TaskKey::GenerateLef,
+        generate_lef(
+            work_dir,
+            name,
+            &lib.pdk,
+            &sram_layout.cell,
+            &gds_path,
+            &verilog_path,
+            params.lef_tool
+        )?,
+        ctx
+    );
+    try_execute_task!(
+        params.tasks,
+        TaskKey::GenerateDef,
+        generate_def(work_dir, name, &lib.pdk, &sram_layout.cell)?,
+        ctx
+    );
 
     let pex_netlist_path = out_pex(work_dir, name);
 
-    #[cfg(feature = "calibre")]
+    try_execute_task!(
+        params.tasks,
+        TaskKey::RunDrc,
+        run_sram_drc(work_dir, name, params.drc_tool, &plan.tech)?,
+        ctx
+    );
+    try_execute_task!(
+        params.tasks,
+        TaskKey::RunLvs,
+        run_sram_lvs(
+            work_dir,
+            name,
+            plan.sram_params.control,
+            &modules,
+            params.lvs_tool,
+            &plan.tech
+        )?,
+        ctx
+    );
+
+    #[cfg(all(feature = "calibre", feature = "pex"))]
     {
-        try_execute_task!(
-            params.tasks,
-            TaskKey::RunDrc,
-            crate::verification::calibre::run_sram_drc(work_dir, name)?,
-            ctx
-        );
-        try_execute_task!(
-            params.tasks,
-            TaskKey::RunLvs,
-            crate::verification::calibre::run_sram_lvs(work_dir, name, plan.sram_params.control)?,
-            ctx
-        );
-        #[cfg(feature = "pex")]
         try_execute_task!(
             params.tasks,
             TaskKey::RunPex,
@@ -206,57 +275,211 @@ pub fn execute_plan(params: ExecutePlanParams) -> Result<()> {
                 work_dir,
                 &pex_netlist_path,
                 name,
-                plan.sram_params.control
+                plan.sram_params.control,
+                &plan.tech.calibre
             )?,
             ctx
         );
     }
 
-    #[cfg(feature = "spectre")]
     try_execute_task!(
         params.tasks,
-        TaskKey::RunSpectre,
-        crate::verification::spectre::run_sram_spectre(&plan.sram_params, work_dir, name)?,
+        TaskKey::RunSim,
+        {
+            if let Some(ctx) = ctx.as_mut() {
+                let coverage = sram_coverage(&plan.sram_params, &params.sim.stimulus)?;
+                ctx.report(format!("Test coverage:\n{coverage}"));
+            }
+            run_sram_testbench(
+                &plan.sram_params,
+                &plan.tech,
+                &plan.corners,
+                work_dir,
+                name,
+                &params.sim,
+            )?
+        },
+        ctx
+    );
+
+    try_execute_task!(
+        params.tasks,
+        TaskKey::RunReadMargin,
+        {
+            let reports = run_read_margin(
+                &plan.sram_params,
+                &plan.tech,
+                &plan.corners,
+                work_dir,
+                &params.read_margin,
+            )?;
+            if let Some(ctx) = ctx.as_ref() {
+                for report in reports.iter() {
+                    ctx.report(report);
+                }
+            }
+        },
+        ctx
+    );
+
+    try_execute_task!(
+        params.tasks,
+        TaskKey::RunSnm,
+        {
+            let report = run_snm(&plan.tech, &plan.corners, work_dir)?;
+            if let Some(ctx) = ctx.as_ref() {
+                ctx.report(&report);
+            }
+        },
+        ctx
+    );
+
+    try_execute_task!(
+        params.tasks,
+        TaskKey::GenerateLib,
+        generate_lib(
+            plan,
+            work_dir,
+            &pex_netlist_path,
+            params.sim.simulator,
+            params.characterizer
+        )?,
         ctx
     );
 
-    #[cfg(feature = "liberate_mx")]
+    Ok(())
+}
+
+/// Checks that the netlist formats of the plan include those read by the enabled tasks.
+fn check_netlist_formats(params: &ExecutePlanParams) -> Result<()> {
+    let enabled =
+        |task: TaskKey| params.tasks.contains(&task) || params.tasks.contains(&TaskKey::All);
+
+    let mut needed = Vec::new();
+    if enabled(TaskKey::RunLvs) && params.lvs_tool != LvsTool::Native {
+        needed.push(("LVS", VerificationTask::Lvs));
+    }
+    #[cfg(all(feature = "calibre", feature = "pex"))]
     {
-        try_execute_task!(
-            params.tasks,
-            TaskKey::GenerateLib,
-            {
-                use crate::verification::{source_files, VerificationTask};
-                use liberate_mx::LibParams;
-
-                let source_paths = if pex_netlist_path.exists() {
-                    vec![pex_netlist_path]
-                } else {
-                    source_files(
-                        work_dir,
-                        &plan.sram_params.name,
-                        VerificationTask::SpectreSim,
-                        plan.sram_params.control,
-                    )
-                };
-
-                let params = LibParams::builder()
-                    .work_dir(work_dir.join("lib"))
-                    .save_dir(work_dir)
-                    .corner("tt")
-                    .cell_name(&plan.sram_params.name)
-                    .num_words(plan.sram_params.num_words)
-                    .data_width(plan.sram_params.data_width)
-                    .addr_width(plan.sram_params.addr_width)
-                    .wmask_width(plan.sram_params.wmask_width)
-                    .mux_ratio(plan.sram_params.mux_ratio)
-                    .source_paths(source_paths)
-                    .build()?;
-
-                crate::liberate::generate_sram_lib(&params)?;
-            },
-            ctx
-        );
+        if enabled(TaskKey::RunPex) {
+            needed.push(("PEX", VerificationTask::Pex));
+        }
+    }
+    if enabled(TaskKey::RunSim) {
+        needed.push(("Simulation", params.sim.simulator.verification_task()));
+    }
+    if enabled(TaskKey::GenerateLib) {
+        let task = match params.characterizer {
+            Characterizer::Native => params.sim.simulator.verification_task(),
+            Characterizer::LiberateMx => VerificationTask::SpectreSim,
+        };
+        needed.push(("Liberty generation", task));
+    }
+
+    let formats = &params.plan.sram_params.netlist_formats;
+    for (name, task) in needed {
+        let format = task.netlist_format();
+        if !formats.contains(&format) {
+            bail!(
+                "{name} needs the {format} netlist, but `netlist_formats` does not include `{format:?}`"
+            );
+        }
+    }
+    Ok(())
+}
+
+fn generate_lef(
+    work_dir: &Path,
+    name: &str,
+    pdk: &Pdk,
+    cell: &Ptr<Cell>,
+    #[cfg_attr(not(feature = "abstract_lef"), allow(unused_variables))] gds_path: &Path,
+    #[cfg_attr(not(feature = "abstract_lef"), allow(unused_variables))] verilog_path: &Path,
+    lef_tool: LefTool,
+) -> Result<()> {
+    let lef_path = out_lef(work_dir, name);
+    match lef_tool {
+        LefTool::Native => {
+            save_lef(pdk, &sram_lef_params(cell), &lef_path)
+                .with_context(|| "Error generating LEF abstract")?;
+        }
+        #[cfg(feature = "abstract_lef")]
+        LefTool::AbstractLef => {
+            crate::abs::run_sram_abstract(work_dir, name, &lef_path, gds_path, verilog_path)?;
+        }
+        #[cfg(not(feature = "abstract_lef"))]
+        LefTool::AbstractLef => {
+            bail!("Abstract Generator support is not enabled; rebuild with the `abstract_lef` feature")
+        }
+    }
+    Ok(())
+}
+
+fn generate_def(work_dir: &Path, name: &str, pdk: &Pdk, cell: &Ptr<Cell>) -> Result<()> {
+    let abs =
+        LefMacro::new(pdk, &sram_lef_params(cell)).with_context(|| "Error generating DEF view")?;
+    save_def(&abs, out_def(work_dir, name))?;
+    Ok(())
+}
+
+fn generate_lib(
+    plan: &SramPlan,
+    work_dir: &Path,
+    #[cfg_attr(not(feature = "liberate_mx"), allow(unused_variables))] pex_netlist_path: &Path,
+    simulator: SimulatorKind,
+    characterizer: Characterizer,
+) -> Result<()> {
+    match characterizer {
+        Characterizer::Native => {
+            for corner in plan.corners.iter() {
+                let lib = characterize_sram(
+                    &plan.sram_params,
+                    &plan.tech,
+                    corner,
+                    simulator,
+                    work_dir,
+                    &plan.char_params,
+                )
+                .with_context(|| format!("Error characterizing SRAM at corner {corner}"))?;
+                lib.save(out_lib(work_dir, &lib.lib_name()))
+                    .with_context(|| "Error saving Liberty file")?;
+            }
+        }
+        #[cfg(feature = "liberate_mx")]
+        Characterizer::LiberateMx => {
+            use crate::verification::source_files;
+            use liberate_mx::LibParams;
+
+            let source_paths = if pex_netlist_path.exists() {
+                vec![pex_netlist_path.to_path_buf()]
+            } else {
+                source_files(
+                    work_dir,
+                    &plan.sram_params.name,
+                    VerificationTask::SpectreSim,
+                    plan.sram_params.control,
+                )
+            };
+
+            let params = LibParams::builder()
+                .work_dir(work_dir.join("lib"))
+                .save_dir(work_dir)
+                .corner(plan.tech.default_corner.as_str())
+                .cell_name(&plan.sram_params.name)
+                .num_words(plan.sram_params.num_words)
+                .data_width(plan.sram_params.data_width)
+                .addr_width(plan.sram_params.addr_width)
+                .wmask_width(plan.sram_params.wmask_width)
+                .mux_ratio(plan.sram_params.mux_ratio)
+                .source_paths(source_paths)
+                .build()?;
+
+            crate::liberate::generate_sram_lib(&params)?;
+        }
+        #[cfg(not(feature = "liberate_mx"))]
+        Characterizer::LiberateMx => {
+            bail!("Liberate MX support is not enabled; rebuild with the `liberate_mx` feature")
+        }
     }
     Ok(())
 }
@@ -1,13 +1,17 @@
+use std::fmt::Display;
 use std::path::Path;
-use std::process::{Command, Stdio};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
+use prost::Message;
+use serde::{Deserialize, Serialize};
 use vlsir::circuit::{port, ExternalModule, Package, Port};
 use vlsir::reference::To;
 use vlsir::spice::SimInput;
 use vlsir::{Module, QualifiedName, Reference};
 
+use crate::paths::out_spice;
 use crate::schematic::conns::signal;
+use crate::schematic::netlist::netlist_package;
 use crate::tech::all_external_modules;
 use crate::Result;
 
@@ -22,6 +26,7 @@ pub mod inv_chain;
 pub mod latch;
 pub mod mos;
 pub mod mux;
+pub mod netlist;
 pub mod precharge;
 pub mod sense_amp;
 pub mod sram;
@@ -30,12 +35,28 @@ pub mod wmask_control;
 
 pub mod conns;
 
-pub const GENERATE_SCRIPT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/generate.py");
-pub const NETLIST_FORMAT: NetlistFormat = NetlistFormat::Spectre;
-
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum NetlistFormat {
     NgSpice,
+    #[default]
     Spectre,
+    /// SPICE with CDL pin annotations, used for LVS and PEX.
+    Cdl,
+}
+
+impl NetlistFormat {
+    /// All supported netlist formats.
+    pub const ALL: [NetlistFormat; 3] = [Self::NgSpice, Self::Spectre, Self::Cdl];
+}
+
+impl Display for NetlistFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::NgSpice => write!(f, "ngspice"),
+            Self::Spectre => write!(f, "spectre"),
+            Self::Cdl => write!(f, "cdl"),
+        }
+    }
 }
 
 pub fn simple_ext_module(
@@ -68,8 +89,13 @@ pub fn local_reference(name: impl Into<String>) -> Option<Reference> {
     })
 }
 
-pub fn save_modules(path: impl AsRef<Path>, name: &str, modules: Vec<Module>) -> Result<()> {
-    let ext_modules = all_external_modules();
+pub fn save_modules(
+    path: impl AsRef<Path>,
+    name: &str,
+    modules: Vec<Module>,
+    format: NetlistFormat,
+) -> Result<()> {
+    let ext_modules = all_external_modules(format);
     let pkg = vlsir::circuit::Package {
         domain: format!("sramgen_{}", name),
         desc: "Sramgen generated cells".to_string(),
@@ -97,21 +123,29 @@ pub fn save_bin(path: impl AsRef<Path>, name: &str, pkg: Package) -> Result<()>
     Ok(())
 }
 
-pub fn generate_netlist(bin_path: impl AsRef<Path>, output_dir: impl AsRef<Path>) -> Result<()> {
-    let status = Command::new("python3")
-        .arg(GENERATE_SCRIPT_PATH)
-        .arg(bin_path.as_ref())
-        .arg("-o")
-        .arg(output_dir.as_ref())
-        .stdout(Stdio::null())
-        .status()?;
-
-    if status.success() {
-        Ok(())
-    } else {
-        Err(anyhow!(
-            "Netlist generation script failed with status {:?}",
-            status.code()
-        ))
-    }
+/// Writes a SPICE netlist in the given [`NetlistFormat`] for the VLSIR binary at `bin_path`.
+///
+/// The netlist is named after the top cell of the binary, and saved in `output_dir`.
+pub fn generate_netlist(
+    bin_path: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    format: NetlistFormat,
+) -> Result<()> {
+    let bin_path = bin_path.as_ref();
+    let bytes = std::fs::read(bin_path)
+        .with_context(|| format!("Failed to read VLSIR binary {:?}", bin_path))?;
+    let input = SimInput::decode(bytes.as_slice())
+        .with_context(|| format!("Failed to decode VLSIR binary {:?}", bin_path))?;
+    let pkg = input
+        .pkg
+        .ok_or_else(|| anyhow!("VLSIR binary {:?} has no circuit package", bin_path))?;
+    let name = &input.top;
+
+    let netlist = netlist_package(&pkg, format)
+        .with_context(|| format!("Failed to generate {} netlist for `{}`", format, name))?;
+
+    std::fs::create_dir_all(output_dir.as_ref())?;
+    std::fs::write(out_spice(&output_dir, name, format), netlist)?;
+
+    Ok(())
 }
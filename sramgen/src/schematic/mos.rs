@@ -44,6 +44,7 @@ pub fn ext_nmos(format: NetlistFormat) -> ExternalModule {
     let name = match format {
         NetlistFormat::NgSpice => "sky130_fd_pr__nfet_01v8",
         NetlistFormat::Spectre => "sky130_fd_pr__nfet_01v8",
+        NetlistFormat::Cdl => "sky130_fd_pr__nfet_01v8",
     };
 
     ExternalModule {
@@ -77,6 +78,7 @@ pub fn ext_pmos(format: NetlistFormat) -> ExternalModule {
     let name = match format {
         NetlistFormat::NgSpice => "sky130_fd_pr__pfet_01v8",
         NetlistFormat::Spectre => "sky130_fd_pr__pfet_01v8",
+        NetlistFormat::Cdl => "sky130_fd_pr__pfet_01v8",
     };
 
     ExternalModule {
@@ -0,0 +1,260 @@
+//! A SPICE netlister for VLSIR circuit packages.
+//!
+//! Bus signals are flattened MSB first, with bits named `name[i]`,
+//! matching the pin names used in layout.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{anyhow, bail};
+use vlsir::circuit::connection::Stype;
+use vlsir::circuit::parameter_value::Value;
+use vlsir::circuit::{port, Connection, Instance, Module, Package, Port, Signal};
+use vlsir::reference::To;
+
+use crate::bus_bit;
+use crate::schematic::NetlistFormat;
+use crate::tech::sky130_prelude;
+use crate::Result;
+
+/// Netlists every module in `pkg` in the given format.
+///
+/// External modules are not defined in the output, with the exception
+/// of the SKY130 device prelude emitted for Spectre and CDL netlists.
+pub fn netlist_package(pkg: &Package, format: NetlistFormat) -> Result<String> {
+    let mut out = String::new();
+
+    writeln!(&mut out, "* {}", pkg.desc)?;
+    writeln!(&mut out, "* Domain: {}", pkg.domain)?;
+    writeln!(&mut out)?;
+
+    if let Some(prelude) = sky130_prelude(format) {
+        writeln!(&mut out, "{}", prelude)?;
+    }
+
+    let mut ports = HashMap::new();
+    for m in pkg.modules.iter() {
+        ports.insert(m.name.as_str(), m.ports.as_slice());
+    }
+    let mut ext_ports = HashMap::new();
+    for m in pkg.ext_modules.iter() {
+        let name = m
+            .name
+            .as_ref()
+            .ok_or_else(|| anyhow!("Found an external module without a name"))?;
+        ext_ports.insert(
+            (name.domain.as_str(), name.name.as_str()),
+            m.ports.as_slice(),
+        );
+    }
+
+    let ctx = NetlistContext {
+        format,
+        ports,
+        ext_ports,
+    };
+
+    for m in pkg.modules.iter() {
+        ctx.write_module(&mut out, m)?;
+    }
+
+    Ok(out)
+}
+
+struct NetlistContext<'a> {
+    format: NetlistFormat,
+    ports: HashMap<&'a str, &'a [Port]>,
+    ext_ports: HashMap<(&'a str, &'a str), &'a [Port]>,
+}
+
+impl<'a> NetlistContext<'a> {
+    fn write_module(&self, out: &mut String, m: &Module) -> Result<()> {
+        if !m.parameters.is_empty() {
+            bail!(
+                "Module `{}` has parameters, which are not supported",
+                m.name
+            );
+        }
+
+        writeln!(out, ".SUBCKT {}", m.name)?;
+        for port in m.ports.iter() {
+            for bit in signal_bits(port_signal(port)?) {
+                writeln!(out, "+ {bit}")?;
+            }
+        }
+        if self.format == NetlistFormat::Cdl {
+            write_pininfo(out, &m.ports)?;
+        }
+        writeln!(out)?;
+
+        let widths = signal_widths(m);
+
+        for inst in m.instances.iter() {
+            self.write_instance(out, &m.name, &widths, inst)?;
+        }
+
+        writeln!(out, ".ENDS {}", m.name)?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    fn write_instance(
+        &self,
+        out: &mut String,
+        parent: &str,
+        widths: &HashMap<&str, i64>,
+        inst: &Instance,
+    ) -> Result<()> {
+        let to = inst
+            .module
+            .as_ref()
+            .and_then(|r| r.to.as_ref())
+            .ok_or_else(|| anyhow!("Instance `{}` in `{}` has no module", inst.name, parent))?;
+
+        let (module_name, ports) = match to {
+            To::Local(name) => (
+                name.as_str(),
+                *self.ports.get(name.as_str()).ok_or_else(|| {
+                    anyhow!(
+                        "Instance `{}` refers to unknown module `{}`",
+                        inst.name,
+                        name
+                    )
+                })?,
+            ),
+            To::External(qn) => (
+                qn.name.as_str(),
+                *self
+                    .ext_ports
+                    .get(&(qn.domain.as_str(), qn.name.as_str()))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Instance `{}` refers to unknown external module `{}.{}`",
+                            inst.name,
+                            qn.domain,
+                            qn.name
+                        )
+                    })?,
+            ),
+        };
+
+        writeln!(out, "x{}", inst.name)?;
+        for port in ports.iter() {
+            let sig = port_signal(port)?;
+            let conn = inst.connections.get(&sig.name).ok_or_else(|| {
+                anyhow!(
+                    "Port `{}` of instance `{}` in `{}` is unconnected",
+                    sig.name,
+                    inst.name,
+                    parent
+                )
+            })?;
+            let bits = connection_bits(conn, widths)?;
+            if bits.len() as i64 != sig.width {
+                bail!(
+                    "Port `{}` of instance `{}` in `{}` has width {}, but was connected to {} bits",
+                    sig.name,
+                    inst.name,
+                    parent,
+                    sig.width,
+                    bits.len()
+                );
+            }
+            writeln!(out, "+ {}", bits.join(" "))?;
+        }
+        writeln!(out, "+ {module_name}")?;
+
+        let mut params = inst.parameters.iter().collect::<Vec<_>>();
+        params.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, value) in params {
+            let value = match value.value.as_ref() {
+                Some(Value::Double(x)) => x.to_string(),
+                other => bail!(
+                    "Unsupported value {:?} for parameter `{}` of instance `{}`",
+                    other,
+                    name,
+                    inst.name
+                ),
+            };
+            writeln!(out, "+ {name}={value}")?;
+        }
+        writeln!(out)?;
+
+        Ok(())
+    }
+}
+
+pub(crate) fn port_signal(port: &Port) -> Result<&Signal> {
+    port.signal
+        .as_ref()
+        .ok_or_else(|| anyhow!("Found a port without a signal"))
+}
+
+fn write_pininfo(out: &mut String, ports: &[Port]) -> Result<()> {
+    write!(out, "*.PININFO")?;
+    for port in ports.iter() {
+        let dir = match port::Direction::from_i32(port.direction) {
+            Some(port::Direction::Input) => "I",
+            Some(port::Direction::Output) => "O",
+            _ => "B",
+        };
+        for bit in signal_bits(port_signal(port)?) {
+            write!(out, " {bit}:{dir}")?;
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// The widths of the ports and signals of `m`, by name.
+pub(crate) fn signal_widths(m: &Module) -> HashMap<&str, i64> {
+    m.ports
+        .iter()
+        .filter_map(|p| p.signal.as_ref())
+        .chain(m.signals.iter())
+        .map(|s| (s.name.as_str(), s.width))
+        .collect()
+}
+
+/// The names of the bits of `sig`, MSB first.
+pub(crate) fn signal_bits(sig: &Signal) -> Vec<String> {
+    if sig.width == 1 {
+        vec![sig.name.clone()]
+    } else {
+        (0..sig.width as usize)
+            .rev()
+            .map(|i| bus_bit(&sig.name, i))
+            .collect()
+    }
+}
+
+/// The names of the nets connected by `conn`, MSB first.
+///
+/// `widths` maps the signals of the enclosing module to their widths,
+/// so that slices of single-bit signals are named without an index.
+pub(crate) fn connection_bits(
+    conn: &Connection,
+    widths: &HashMap<&str, i64>,
+) -> Result<Vec<String>> {
+    let stype = conn
+        .stype
+        .as_ref()
+        .ok_or_else(|| anyhow!("Found an empty connection"))?;
+    Ok(match stype {
+        Stype::Sig(sig) => signal_bits(sig),
+        Stype::Slice(slice) if widths.get(slice.signal.as_str()) == Some(&1) => {
+            vec![slice.signal.clone()]
+        }
+        Stype::Slice(slice) => (slice.bot..=slice.top)
+            .rev()
+            .map(|i| bus_bit(&slice.signal, i as usize))
+            .collect(),
+        Stype::Concat(concat) => {
+            let mut bits = Vec::new();
+            for part in concat.parts.iter() {
+                bits.extend(connection_bits(part, widths)?);
+            }
+            bits
+        }
+    })
+}
@@ -36,6 +36,33 @@ use crate::tech::{
     sramgen_control_simple_ref,
 };
 
+/// The number of replica columns and the dummy bitcells surrounding the bitcell array.
+pub(crate) fn bitcell_array_border(control: ControlMode) -> (usize, BitcellArrayDummyParams) {
+    match control {
+        ControlMode::Simple => (1, BitcellArrayDummyParams::equal(2)),
+        ControlMode::ReplicaV1 => (1, BitcellArrayDummyParams::enumerate(2, 2, 1, 2)),
+    }
+}
+
+/// Parameters of each bitline pair's precharge devices.
+pub(crate) fn precharge_params() -> PrechargeParams {
+    PrechargeParams {
+        name: "precharge".to_string(),
+        length: 150,
+        pull_up_width: 1_000,
+        equalizer_width: 1_000,
+    }
+}
+
+/// Parameters of each bitline pair's read mux devices.
+pub(crate) fn read_mux_params() -> ReadMuxParams {
+    ReadMuxParams {
+        name: "read_mux".to_string(),
+        length: 150,
+        width: 1_200,
+    }
+}
+
 pub fn sram(params: &SramParams) -> Vec<Module> {
     assert!(params.row_bits > 0);
     assert!(params.col_bits > 0);
@@ -88,10 +115,7 @@ pub fn sram(params: &SramParams) -> Vec<Module> {
         },
     });
 
-    let (replica_cols, dummy_params) = match params.control {
-        ControlMode::Simple => (1, BitcellArrayDummyParams::equal(2)),
-        ControlMode::ReplicaV1 => (1, BitcellArrayDummyParams::enumerate(2, 2, 1, 2)),
-    };
+    let (replica_cols, dummy_params) = bitcell_array_border(params.control);
 
     let bitcells = bitcell_array(&BitcellArrayParams {
         name: "bitcell_array".to_string(),
@@ -111,12 +135,7 @@ pub fn sram(params: &SramParams) -> Vec<Module> {
         name: "precharge_array".to_string(),
         width: pc_cols,
         flip_toggle: false,
-        instance_params: PrechargeParams {
-            name: "precharge".to_string(),
-            length: 150,
-            pull_up_width: 1_000,
-            equalizer_width: 1_000,
-        },
+        instance_params: precharge_params(),
     });
 
     let mut write_muxes = write_mux_array(&WriteMuxArrayParams {
@@ -136,11 +155,7 @@ pub fn sram(params: &SramParams) -> Vec<Module> {
         name: "read_mux_array".to_string(),
         cols,
         mux_ratio,
-        mux_params: ReadMuxParams {
-            name: "read_mux".to_string(),
-            length: 150,
-            width: 1_200,
-        },
+        mux_params: read_mux_params(),
     });
 
     let mut col_inv = col_inv_array(&ColInvArrayParams {
@@ -11,7 +11,7 @@ use vlsir::reference::To;
 use vlsir::{QualifiedName, Reference};
 
 use crate::schematic::mos::{ext_nmos, ext_pmos};
-use crate::schematic::{simple_ext_module, NETLIST_FORMAT};
+use crate::schematic::{simple_ext_module, NetlistFormat};
 
 pub const SKY130_DOMAIN: &str = "sky130";
 pub const SRAM_SP_CELL: &str = "sram_sp_cell";
@@ -542,10 +542,10 @@ pub fn control_logic_bufbuf_16_ref() -> Reference {
 }
 
 #[inline]
-pub fn all_external_modules() -> Vec<ExternalModule> {
+pub fn all_external_modules(format: NetlistFormat) -> Vec<ExternalModule> {
     vec![
-        ext_nmos(NETLIST_FORMAT),
-        ext_pmos(NETLIST_FORMAT),
+        ext_nmos(format),
+        ext_pmos(format),
         sram_sp_cell(),
         sram_sp_colend(),
         sram_sp_cell_replica(),
@@ -558,6 +558,37 @@ pub fn all_external_modules() -> Vec<ExternalModule> {
     ]
 }
 
+/// SKY130 device subcircuits, and the foundry model each one maps to.
+pub const SKY130_DEVICE_MODELS: [(&str, &str); 7] = [
+    ("sky130_fd_pr__special_nfet_pass", "npass"),
+    ("sky130_fd_pr__special_nfet_latch", "npd"),
+    ("sky130_fd_pr__nfet_01v8", "nshort"),
+    ("sky130_fd_pr__pfet_01v8", "pshort"),
+    ("sky130_fd_pr__special_pfet_pass", "ppu"),
+    ("sky130_fd_pr__pfet_01v8_hvt", "phighvt"),
+    ("sky130_fd_pr__nfet_01v8_lvt", "nlowvt"),
+];
+
+/// Subcircuit definitions mapping the open source SKY130 device names
+/// to the foundry models used by proprietary tools.
+///
+/// Returns [`None`] for netlist formats that use the open source models directly.
+pub fn sky130_prelude(format: NetlistFormat) -> Option<String> {
+    let (flavor, params) = match format {
+        NetlistFormat::NgSpice => return None,
+        NetlistFormat::Spectre => ("SPECTRE", "\n.PARAM w=1.0 l=1.0 mult=1"),
+        NetlistFormat::Cdl => ("SPICE", " PARAMS: w=1.0 l=1.0 mult=1"),
+    };
+
+    let mut out = format!("*SPICE NETLIST\n* OPEN SOURCE CONVERSION PRELUDE ({flavor})\n");
+    for (name, model) in SKY130_DEVICE_MODELS {
+        out.push_str(&format!(
+            "\n.SUBCKT {name} d g s b{params}\nM0 d g s b {model} l='l' w='w' mult='mult'\n.ENDS\n"
+        ));
+    }
+    Some(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,7 +3,7 @@ use crate::layout::array::*;
 use crate::layout::draw_bitcell;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::bitcell_array::*;
-use crate::schematic::{generate_netlist, save_bin};
+use crate::schematic::{generate_netlist, save_bin, NetlistFormat};
 use crate::tech::all_external_modules;
 use crate::tests::test_work_dir;
 use crate::Result;
@@ -35,7 +35,7 @@ fn test_bitcell_array_32x32() -> Result<()> {
     };
 
     let bitcells = bitcell_array(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -48,7 +48,7 @@ fn test_bitcell_array_32x32() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_bitcell_array(&mut lib, &params)?;
@@ -71,7 +71,7 @@ fn test_bitcell_array_2x2() -> Result<()> {
     };
 
     let bitcells = bitcell_array(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -84,7 +84,7 @@ fn test_bitcell_array_2x2() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_bitcell_array(&mut lib, &params)?;
@@ -107,7 +107,7 @@ fn test_replica_bitcell_array_2x2() -> Result<()> {
     };
 
     let bitcells = bitcell_array(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -120,7 +120,7 @@ fn test_replica_bitcell_array_2x2() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_bitcell_array(&mut lib, &params)?;
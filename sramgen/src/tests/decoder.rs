@@ -3,7 +3,7 @@ use crate::config::gate::{GateParams, Size};
 use crate::layout::decoder::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::decoder::*;
-use crate::schematic::{generate_netlist, save_modules};
+use crate::schematic::{generate_netlist, save_modules, NetlistFormat};
 use crate::tech::BITCELL_HEIGHT;
 use crate::tests::test_work_dir;
 use crate::Result;
@@ -185,9 +185,9 @@ fn test_hier_decode_4bit() -> Result<()> {
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, modules)?;
+    save_modules(&bin_path, name, modules, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_hier_decode(&mut lib, name, &tree.root)?;
@@ -212,9 +212,9 @@ fn test_hier_decode_5bit() -> Result<()> {
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, modules)?;
+    save_modules(&bin_path, name, modules, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_hier_decode(&mut lib, name, &tree.root)?;
@@ -239,9 +239,9 @@ fn test_hier_decode_7bit() -> Result<()> {
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, modules)?;
+    save_modules(&bin_path, name, modules, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_hier_decode(&mut lib, name, &tree.root)?;
@@ -2,7 +2,7 @@ use crate::config::dff::*;
 use crate::layout::dff::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::dff::*;
-use crate::schematic::{generate_netlist, save_bin};
+use crate::schematic::{generate_netlist, save_bin, NetlistFormat};
 use crate::tech::{all_external_modules, COLUMN_WIDTH};
 use crate::tests::test_work_dir;
 use crate::Result;
@@ -21,7 +21,7 @@ fn test_dff_array() -> Result<()> {
         .build()?;
     let dffs = dff_grid(&dff_params);
 
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -34,7 +34,7 @@ fn test_dff_array() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_dff_grid(&mut lib, &dff_params)?;
@@ -58,7 +58,7 @@ fn test_dff_grid() -> Result<()> {
         .build()?;
     let dffs = dff_grid(&params);
 
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -71,7 +71,7 @@ fn test_dff_grid() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_dff_grid(&mut lib, &params)?;
@@ -0,0 +1,119 @@
+use std::fmt::Write;
+
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::diagnostics::{diagnose_read, InternalNets};
+use crate::verification::ngspice::parse_rawfile;
+use crate::verification::{Op, PortClass, PortOrder, SimulatorKind, TbParams, TestCase};
+use crate::Result;
+
+const PERIOD: f64 = 1e-9;
+
+/// Builds an ngspice rawfile from sample times (in clock periods) and named traces.
+fn rawfile(times: &[f64], traces: &[(&str, Vec<f64>)]) -> Result<String> {
+    let mut raw = String::new();
+    writeln!(raw, "Title: diagnostics")?;
+    writeln!(raw, "Plotname: Transient Analysis")?;
+    writeln!(raw, "Flags: real")?;
+    writeln!(raw, "No. Variables: {}", traces.len() + 1)?;
+    writeln!(raw, "No. Points: {}", times.len())?;
+    writeln!(raw, "Variables:")?;
+    writeln!(raw, "\t0\ttime\ttime")?;
+    for (i, (name, _)) in traces.iter().enumerate() {
+        writeln!(raw, "\t{}\tv(xdut0.{})\tvoltage", i + 1, name)?;
+    }
+    writeln!(raw, "Values:")?;
+    for (i, t) in times.iter().enumerate() {
+        writeln!(raw, " {}\t{:e}", i, t * PERIOD)?;
+        for (_, values) in traces {
+            writeln!(raw, "\t{:e}", values[i])?;
+        }
+    }
+    Ok(raw)
+}
+
+#[test]
+fn test_diagnose_read() -> Result<()> {
+    // Write to and then read from address 1. With a mux ratio of 2,
+    // address 1 is in row 0, and bit 1 is read from columns 2 and 3.
+    let times = [0.0, 1.0, 1.5, 2.0, 2.25, 2.5, 2.75, 3.0];
+    let low = vec![0.0; times.len()];
+    let high = vec![1.8; times.len()];
+    let traces = [
+        ("wl[0]", vec![0.0, 0.0, 1.8, 0.0, 0.0, 1.8, 1.8, 0.0]),
+        ("wl[1]", low.clone()),
+        ("write_driver_en[0]", low),
+        (
+            "write_driver_en[1]",
+            vec![0.0, 0.0, 1.8, 0.0, 0.0, 0.0, 0.0, 0.0],
+        ),
+        ("wl_en", vec![0.0, 0.0, 1.8, 0.0, 0.0, 1.8, 1.8, 0.0]),
+        ("sense_amp_en", vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.8, 0.0]),
+        ("bl[2]", high.clone()),
+        ("br[2]", high.clone()),
+        ("bl[3]", vec![1.8, 1.8, 1.8, 1.8, 1.8, 1.6, 1.4, 1.8]),
+        ("br[3]", high),
+    ];
+    let data = parse_rawfile(&rawfile(&times, &traces)?)?;
+
+    let addr = BitSignal::from_u64(1, 2);
+    let tb = TbParams::builder()
+        .test_case(
+            TestCase::builder()
+                .clk_period(PERIOD)
+                .ops(vec![
+                    Op::Write {
+                        addr: addr.clone(),
+                        data: BitSignal::from_u64(0b11, 2),
+                    },
+                    Op::Read { addr },
+                ])
+                .build()?,
+        )
+        .sram_name("sram")
+        .tr(50e-12)
+        .tf(50e-12)
+        .vdd(1.8)
+        .c_load(5e-15)
+        .data_width(2)
+        .addr_width(2)
+        .wmask_width(1)
+        .ports(vec![(PortClass::Clock, PortOrder::MsbFirst)])
+        .clk_port("clk")
+        .write_enable_port("we")
+        .addr_port("addr")
+        .data_in_port("din")
+        .data_out_port("dout")
+        .pwr_port("vdd")
+        .gnd_port("vss")
+        .work_dir("build/test_diagnose_read")
+        .simulator(SimulatorKind::Ngspice)
+        .internal_nets(InternalNets {
+            rows: 2,
+            cols: 4,
+            mux_ratio: 2,
+            replica: false,
+        })
+        .build()?;
+
+    let diagnostic = diagnose_read(&data, &tb, 1, 1)?;
+    assert_eq!(diagnostic.expected_row, 0);
+    assert_eq!(diagnostic.wordlines, vec![0]);
+    assert_eq!(diagnostic.column, 3);
+    assert!(diagnostic.split < -0.1);
+
+    let t_split = diagnostic.t_bitline_split.unwrap();
+    let t_sae = diagnostic.t_sense_amp_en.unwrap();
+    assert!(t_split > 2.25 * PERIOD && t_split < 2.5 * PERIOD);
+    assert!(t_sae > t_split);
+
+    let write = diagnostic.last_write.as_ref().unwrap();
+    assert_eq!(write.op, 0);
+    assert_eq!(write.wordlines, vec![0]);
+    assert_eq!(write.write_drivers, vec![1]);
+
+    let report = diagnostic.to_string();
+    assert!(report.contains("Wordlines fired: wl[0] (expected row 0)"));
+    assert!(report.contains("write drivers enabled: write_driver_en[1]"));
+
+    Ok(())
+}
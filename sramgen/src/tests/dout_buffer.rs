@@ -5,7 +5,7 @@ use crate::config::dout_buffer::*;
 use crate::layout::dout_buffer::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::dout_buffer::*;
-use crate::schematic::{generate_netlist, save_bin, save_modules};
+use crate::schematic::{generate_netlist, save_bin, save_modules, NetlistFormat};
 use crate::tech::all_external_modules;
 use crate::tests::test_work_dir;
 use crate::Result;
@@ -23,7 +23,7 @@ fn test_dout_buf() -> Result<()> {
     };
 
     let buf = dout_buf(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -36,7 +36,7 @@ fn test_dout_buf() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_dout_buf(&mut lib, &params)?;
@@ -69,9 +69,9 @@ fn test_dout_buf_array() -> Result<()> {
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, modules)?;
+    save_modules(&bin_path, name, modules, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_dout_buf_array(&mut lib, &params)?;
@@ -0,0 +1,422 @@
+use layout21::raw::{Cell, Point, Rect};
+use layout21::utils::Ptr;
+use pdkprims::tech::sky130;
+use pdkprims::PdkLib;
+use std::path::Path;
+
+use crate::layout::draw_rect;
+use crate::layout::drc::{check_drc, DrcRules, EnclosureRule, LayerRules};
+use crate::verification::drc::{Bbox, DrcResult, DrcTool, DrcViolation, DrcWaivers, DRC_DIR};
+use crate::verification::klayout::{parse_lyrdb, write_lyrdb};
+use crate::verification::magic::{parse_drc_report, tcl_escape};
+use crate::Result;
+
+/// Checks `cell` using the built-in design rule checker and the SKY130 rule table,
+/// saving the result to `work_dir/drc`.
+pub(crate) fn assert_drc_clean(lib: &PdkLib, cell: &Ptr<Cell>, work_dir: &Path) -> Result<()> {
+    assert_rules_clean(lib, cell, work_dir, &DrcRules::sky130()?)
+}
+
+/// The layers that route signals and supplies between the cells of an SRAM.
+///
+/// Bitcells follow SRAM-specific rules on the layers below, so those layers
+/// are not checked in complete SRAM layouts.
+const SRAM_ROUTING_LAYERS: [&str; 3] = ["m2", "via2", "m3"];
+
+/// Checks the routing layers of the SRAM layout `cell` using the built-in
+/// design rule checker and the SKY130 rule table, saving the result to `work_dir/drc`.
+pub(crate) fn assert_sram_drc_clean(lib: &PdkLib, cell: &Ptr<Cell>, work_dir: &Path) -> Result<()> {
+    let mut rules = DrcRules::sky130()?;
+    rules
+        .layers
+        .retain(|layer| SRAM_ROUTING_LAYERS.contains(&layer.name.as_str()));
+    assert_rules_clean(lib, cell, work_dir, &rules)
+}
+
+fn assert_rules_clean(
+    lib: &PdkLib,
+    cell: &Ptr<Cell>,
+    work_dir: &Path,
+    rules: &DrcRules,
+) -> Result<()> {
+    let result = check_drc(&lib.pdk, cell, rules)?;
+    result.save(work_dir.join(DRC_DIR))?;
+    assert!(result.passed(), "{result}");
+    Ok(())
+}
+
+const MAGIC_REPORT: &str = "0.0\t0.0\t0.14\t1.0\tMetal1 spacing < 0.14um (met1.2)
+2.5\t3.0\t2.6\t3.2\tMetal1 spacing < 0.14um (met1.2)
+-1.0\t-1.0\t1.0\t1.0\tThis layer can't abut or partially overlap between subcells
+";
+
+const LYRDB: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<report-database>
+ <description>SKY130 DRC runset</description>
+ <categories>
+  <category>
+   <name>m1.2</name>
+   <description>m1.2 : min. m1 spacing : 0.14um</description>
+   <categories>
+   </categories>
+  </category>
+  <category>
+   <name>li.3</name>
+   <description>li.3 : min. li spacing : 0.17um</description>
+   <categories>
+   </categories>
+  </category>
+ </categories>
+ <cells>
+  <cell>
+   <name>sram</name>
+  </cell>
+ </cells>
+ <items>
+  <item>
+   <tags/>
+   <category>'m1.2'</category>
+   <cell>sram</cell>
+   <visited>false</visited>
+   <multiplicity>1</multiplicity>
+   <values>
+    <value>edge-pair: (0.1,0.2;0.3,0.2)|(0.3,0.25;0.1,0.25)</value>
+   </values>
+  </item>
+  <item>
+   <tags/>
+   <category>'li.3'</category>
+   <cell>sram</cell>
+   <visited>false</visited>
+   <multiplicity>1</multiplicity>
+   <values>
+    <value>polygon: (-1,-2;-1,0;2,0;2,-2)</value>
+    <value>float: 0.15</value>
+   </values>
+  </item>
+ </items>
+</report-database>
+";
+
+#[test]
+fn test_parse_magic_drc_report() -> Result<()> {
+    let violations = parse_drc_report(MAGIC_REPORT)?;
+    assert_eq!(violations.len(), 3);
+    assert_eq!(violations[0].rule, "met1.2");
+    assert_eq!(
+        violations[0].description,
+        "Metal1 spacing < 0.14um (met1.2)"
+    );
+    assert_eq!(
+        violations[1].bbox,
+        Some(Bbox {
+            x0: 2.5,
+            y0: 3.0,
+            x1: 2.6,
+            y1: 3.2
+        })
+    );
+    assert_eq!(violations[2].rule, violations[2].description);
+
+    assert!(parse_drc_report("0.0\t0.0\tmet1.2").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_tcl_escape() {
+    assert_eq!(tcl_escape("/work/sram.gds"), "/work/sram.gds");
+    assert_eq!(
+        tcl_escape("/my work/[sram]{1}$x;\\.gds"),
+        "/my\\ work/\\[sram\\]\\{1\\}\\$x\\;\\\\.gds"
+    );
+    assert_eq!(tcl_escape("a\tb\nc"), "a\\tb\\nc");
+}
+
+#[test]
+fn test_parse_lyrdb() -> Result<()> {
+    let violations = parse_lyrdb(LYRDB)?;
+    assert_eq!(violations.len(), 2);
+    assert_eq!(violations[0].rule, "m1.2");
+    assert_eq!(violations[0].description, "m1.2 : min. m1 spacing : 0.14um");
+    assert_eq!(
+        violations[0].bbox,
+        Some(Bbox {
+            x0: 0.1,
+            y0: 0.2,
+            x1: 0.3,
+            y1: 0.25
+        })
+    );
+    assert_eq!(
+        violations[1].bbox,
+        Some(Bbox {
+            x0: -1.0,
+            y0: -2.0,
+            x1: 2.0,
+            y1: 0.0
+        })
+    );
+
+    assert_eq!(violations[0].cell.as_deref(), Some("sram"));
+
+    let result = DrcResult::new(DrcTool::Klayout, "sram", violations, &DrcWaivers::default());
+    assert!(!result.passed());
+    let summary = result.to_string();
+    assert!(summary.contains("DRC of sram using klayout: FAILED"));
+    assert!(summary.contains("li.3: 1 violations (li.3 : min. li spacing : 0.17um)"));
+
+    assert!(parse_lyrdb("<report-database><items/></report-database>")?.is_empty());
+    Ok(())
+}
+
+const WAIVERS: &str = r#"
+[[waiver]]
+rule = "*"
+except = ["r_*"]
+
+[[waiver]]
+rule = "r_1252_metblk.?"
+
+[[waiver]]
+rule = "r_met1.2"
+cell = "precharge"
+bbox = { x0 = 0.0, y0 = 0.0, x1 = 2.0, y1 = 2.0 }
+reason = "False positive at the bitcell boundary."
+"#;
+
+fn violation(rule: &str, cell: Option<&str>, bbox: Option<Bbox>) -> DrcViolation {
+    DrcViolation {
+        rule: rule.to_string(),
+        description: String::new(),
+        cell: cell.map(str::to_string),
+        bbox,
+    }
+}
+
+#[test]
+fn test_drc_waivers() -> Result<()> {
+    let waivers: DrcWaivers = toml::from_str(WAIVERS)?;
+    assert_eq!(waivers.waivers.len(), 3);
+
+    let inside = Some(Bbox {
+        x0: 0.5,
+        y0: 0.5,
+        x1: 1.0,
+        y1: 1.0,
+    });
+    let overlapping = Some(Bbox {
+        x0: 1.5,
+        y0: 1.5,
+        x1: 2.5,
+        y1: 2.5,
+    });
+
+    let violations = vec![
+        violation("density_info", None, None),
+        violation("r_1252_metblk.6", None, None),
+        violation("r_1252_metblk.16", None, None),
+        violation("r_met1.2", Some("precharge"), inside),
+        violation("r_met1.2", Some("precharge"), overlapping),
+        violation("r_met1.2", None, inside),
+        violation("r_met1.2", Some("precharge"), None),
+    ];
+
+    let result = DrcResult::new(DrcTool::Calibre, "sram", violations, &waivers);
+    assert!(!result.passed());
+    assert_eq!(result.waived.len(), 3);
+    assert_eq!(
+        result
+            .violations
+            .iter()
+            .map(|v| v.rule.as_str())
+            .collect::<Vec<_>>(),
+        vec!["r_1252_metblk.16", "r_met1.2", "r_met1.2", "r_met1.2"]
+    );
+
+    let summary = result.to_string();
+    assert!(summary.contains("4 violations of 2 rules:"));
+    assert!(summary.contains("3 waived violations of 3 rules:"));
+    assert!(summary.contains("(1.500, 1.500) - (2.500, 2.500) in precharge"));
+    assert!(summary.contains("(0.500, 0.500) - (1.000, 1.000) in sram"));
+
+    let waived_only = DrcResult::new(
+        DrcTool::Calibre,
+        "sram",
+        vec![violation("density_info", None, None)],
+        &waivers,
+    );
+    assert!(waived_only.passed());
+
+    Ok(())
+}
+
+/// Draws a cell containing the given rectangles, as `(layer, x0, y0, x1, y1)`.
+fn draw_rects(
+    lib: &mut PdkLib,
+    name: &str,
+    rects: &[(&str, isize, isize, isize, isize)],
+) -> Ptr<Cell> {
+    let mut cell = Cell::empty(name);
+    for &(layer, x0, y0, x1, y1) in rects {
+        let layer = lib.pdk.get_layerkey(layer).unwrap();
+        cell.layout_mut().add(draw_rect(
+            Rect::new(Point::new(x0, y0), Point::new(x1, y1)),
+            layer,
+        ));
+    }
+    let ptr = Ptr::new(cell);
+    lib.lib.cells.push(ptr.clone());
+    ptr
+}
+
+fn count(result: &DrcResult, rule: &str) -> usize {
+    result.violations.iter().filter(|v| v.rule == rule).count()
+}
+
+#[test]
+fn test_native_drc_width_and_space() -> Result<()> {
+    let name = "sramgen_native_drc_width_and_space";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_rects(
+        &mut lib,
+        name,
+        &[
+            // Wide enough.
+            ("m1", 0, 0, 1_000, 140),
+            // Too narrow.
+            ("m1", 0, 500, 1_000, 600),
+            // Too close horizontally.
+            ("m1", 3_000, 0, 3_500, 500),
+            ("m1", 3_600, 0, 4_100, 500),
+            // Corners too close diagonally.
+            ("m1", 6_000, 0, 6_500, 500),
+            ("m1", 6_580, 580, 7_080, 1_080),
+            // Corners far enough apart diagonally.
+            ("m1", 9_000, 0, 9_500, 500),
+            ("m1", 9_700, 700, 10_200, 1_200),
+        ],
+    );
+    let rules = DrcRules {
+        layers: vec![LayerRules {
+            name: "m1".to_string(),
+            width: Some(140),
+            space: Some(140),
+            ..Default::default()
+        }],
+    };
+
+    let result = check_drc(&lib.pdk, &cell, &rules)?;
+    assert_eq!(result.tool, DrcTool::Native);
+    assert_eq!(result.cell, name);
+    assert_eq!(count(&result, "m1.width"), 1, "{result}");
+    assert_eq!(count(&result, "m1.space"), 2, "{result}");
+
+    let bboxes = result
+        .violations
+        .iter()
+        .filter_map(|v| v.bbox)
+        .collect::<Vec<_>>();
+    for expected in [
+        Bbox {
+            x0: 0.0,
+            y0: 0.5,
+            x1: 1.0,
+            y1: 0.6,
+        },
+        Bbox {
+            x0: 3.5,
+            y0: 0.0,
+            x1: 3.6,
+            y1: 0.5,
+        },
+        Bbox {
+            x0: 6.5,
+            y0: 0.5,
+            x1: 6.58,
+            y1: 0.58,
+        },
+    ] {
+        assert!(bboxes.contains(&expected), "{result}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_native_drc_vias() -> Result<()> {
+    let name = "sramgen_native_drc_vias";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_rects(
+        &mut lib,
+        name,
+        &[
+            // Enclosed by 85 horizontally and 55 vertically.
+            ("m1", 0, 0, 150, 150),
+            ("m2", -85, -55, 235, 205),
+            // Enclosed by only 55 on all sides.
+            ("m1", 1_000, 0, 1_150, 150),
+            ("m2", 945, -55, 1_205, 205),
+            // Not square.
+            ("m1", 2_000, 0, 2_200, 150),
+            ("m2", 1_800, -200, 2_400, 350),
+        ],
+    );
+    // Treats m1 as a cut layer, to avoid depending on the names of via layers.
+    let rules = DrcRules {
+        layers: vec![LayerRules {
+            name: "m1".to_string(),
+            size: Some(150),
+            enclosures: vec![EnclosureRule {
+                layer: "m2".to_string(),
+                enclosure: 55,
+                one_side: Some(85),
+            }],
+            ..Default::default()
+        }],
+    };
+
+    let result = check_drc(&lib.pdk, &cell, &rules)?;
+    assert_eq!(count(&result, "m1.size"), 1, "{result}");
+    assert_eq!(count(&result, "m1.enclosure.m2"), 1, "{result}");
+    let enclosure = result
+        .violations
+        .iter()
+        .find(|v| v.rule == "m1.enclosure.m2")
+        .unwrap();
+    assert_eq!(
+        enclosure.bbox,
+        Some(Bbox {
+            x0: 1.0,
+            y0: 0.0,
+            x1: 1.15,
+            y1: 0.15,
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sky130_drc_rules() -> Result<()> {
+    let rules = DrcRules::sky130()?;
+    let m1 = rules.layers.iter().find(|l| l.name == "m1").unwrap();
+    assert_eq!(m1.width, Some(140));
+    assert!(rules
+        .layers
+        .iter()
+        .all(|l| l.width.is_some() || l.size.is_some()));
+    Ok(())
+}
+
+#[test]
+fn test_write_lyrdb() -> Result<()> {
+    let violations = parse_lyrdb(LYRDB)?;
+    let result = DrcResult::new(DrcTool::Native, "sram", violations, &DrcWaivers::default());
+
+    let mut lyrdb = Vec::new();
+    write_lyrdb(&result, &mut lyrdb)?;
+    let parsed = parse_lyrdb(std::str::from_utf8(&lyrdb)?)?;
+    assert_eq!(parsed, result.violations);
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::config::gate::{AndParams, GateParams, Size};
 use crate::paths::out_bin;
 use crate::schematic::edge_detector::{edge_detector, EdgeDetectorParams};
-use crate::schematic::{generate_netlist, save_bin};
+use crate::schematic::{generate_netlist, save_bin, NetlistFormat};
 use crate::tech::all_external_modules;
 use crate::tests::test_work_dir;
 use crate::Result;
@@ -37,7 +37,7 @@ fn test_edge_detector() -> Result<()> {
         and_params,
     };
     let modules = edge_detector(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -50,7 +50,7 @@ fn test_edge_detector() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     Ok(())
 }
@@ -3,7 +3,7 @@ use crate::config::gate::*;
 use crate::layout::gate::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::gate::*;
-use crate::schematic::{generate_netlist, save_modules};
+use crate::schematic::{generate_netlist, save_modules, NetlistFormat};
 use crate::tests::test_work_dir;
 use crate::Result;
 use pdkprims::tech::sky130;
@@ -62,9 +62,9 @@ fn test_and2() -> Result<()> {
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, and2)?;
+    save_modules(&bin_path, name, and2, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_and2(&mut lib, &params)?;
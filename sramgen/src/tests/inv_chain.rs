@@ -5,7 +5,7 @@ use crate::config::inv_chain::*;
 use crate::layout::inv_chain::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::inv_chain::*;
-use crate::schematic::{generate_netlist, save_bin};
+use crate::schematic::{generate_netlist, save_bin, NetlistFormat};
 use crate::tech::all_external_modules;
 use crate::tests::test_work_dir;
 use crate::Result;
@@ -20,7 +20,7 @@ fn test_inv_chain_grid() -> Result<()> {
         cols: 9,
     };
     let inv_chain = inv_chain_grid(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -33,7 +33,7 @@ fn test_inv_chain_grid() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_inv_chain_grid(&mut lib, &params)?;
@@ -2,7 +2,7 @@ use crate::config::gate::{GateParams, Size};
 use crate::layout::latch::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::latch::{sr_latch, SrLatchParams};
-use crate::schematic::{generate_netlist, save_modules};
+use crate::schematic::{generate_netlist, save_modules, NetlistFormat};
 use crate::tests::test_work_dir;
 use crate::Result;
 use pdkprims::tech::sky130;
@@ -29,9 +29,9 @@ fn test_sr_latch() -> Result<()> {
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, modules)?;
+    save_modules(&bin_path, name, modules, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_sr_latch(&mut lib, &params)?;
@@ -0,0 +1,154 @@
+use layout21::raw::{Cell, Point, Rect};
+use layout21::utils::Ptr;
+use pdkprims::tech::sky130;
+use pdkprims::PdkLib;
+
+use crate::config::sram::{default_netlist_formats, ControlMode, SramConfig};
+use crate::layout::def::{save_def, to_def};
+use crate::layout::draw_rect;
+use crate::layout::drc::geometry::Region;
+use crate::layout::lef::{sram_lef_params, LefMacro, PinDirection, PinUse};
+use crate::layout::sram::draw_sram;
+use crate::paths::{out_def, out_lef};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::generate_plan;
+use crate::tests::test_work_dir;
+use crate::Result;
+
+fn rect(x0: isize, y0: isize, x1: isize, y1: isize) -> Rect {
+    Rect::new(Point::new(x0, y0), Point::new(x1, y1))
+}
+
+/// Draws a wire on m1 with a pin at either end, and a supply rail on m2.
+fn draw_wire(lib: &mut PdkLib, name: &str) -> Ptr<Cell> {
+    let m1 = lib.pdk.get_layerkey("m1").unwrap();
+    let m2 = lib.pdk.get_layerkey("m2").unwrap();
+    let mut cell = Cell::empty(name);
+    cell.layout_mut()
+        .add(draw_rect(rect(-500, 0, 1_500, 200), m1));
+    cell.layout_mut()
+        .add(draw_rect(rect(-500, 1_000, 1_500, 1_200), m2));
+    cell.add_pin("din[1]", m1, rect(1_300, 0, 1_500, 200));
+    cell.add_pin("din[0]", m1, rect(-500, 0, -300, 200));
+    cell.add_pin("vdd", m2, rect(-500, 1_000, 1_500, 1_200));
+    let ptr = Ptr::new(cell);
+    lib.lib.cells.push(ptr.clone());
+    ptr
+}
+
+#[test]
+fn test_native_lef() -> Result<()> {
+    let name = "sramgen_native_lef";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_wire(&mut lib, name);
+    let lef = LefMacro::new(&lib.pdk, &sram_lef_params(&cell))?;
+
+    assert_eq!(lef.bbox, Region::new(-500, 0, 1_500, 1_200));
+    let pins = lef
+        .pins
+        .iter()
+        .map(|pin| (pin.name.as_str(), pin.direction, pin.use_))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pins,
+        vec![
+            ("din[0]", PinDirection::Input, PinUse::Signal),
+            ("din[1]", PinDirection::Input, PinUse::Signal),
+            ("vdd", PinDirection::Inout, PinUse::Power),
+        ]
+    );
+    // The 300 nm of wire beyond each pin is part of the pin, for pin access.
+    assert_eq!(
+        lef.obstructions["met1"],
+        vec![Region::new(0, 0, 1_000, 200)]
+    );
+    assert_eq!(
+        lef.pins[0].shapes["met1"],
+        vec![
+            Region::new(-500, 0, -300, 200),
+            Region::new(-300, 0, 0, 200)
+        ]
+    );
+    assert_eq!(
+        lef.pins[1].shapes["met1"],
+        vec![
+            Region::new(1_300, 0, 1_500, 200),
+            Region::new(1_000, 0, 1_300, 200)
+        ]
+    );
+    assert!(lef.obstructions["met2"].is_empty());
+    assert_eq!(
+        lef.pins[2].shapes["met2"],
+        vec![Region::new(-500, 1_000, 1_500, 1_200)]
+    );
+
+    let text = lef.to_lef()?;
+    assert!(text.contains(&format!("FOREIGN {name} 0.500 0.000 ;")));
+    assert!(text.contains("SIZE 2.000 BY 1.200 ;"));
+    assert!(text.contains("  PIN vdd\n    DIRECTION INOUT ;\n    USE POWER ;\n"));
+    assert!(text.contains(
+        "  PIN din[0]\n    DIRECTION INPUT ;\n    USE SIGNAL ;\n    PORT\n      LAYER met1 ;\n        RECT 0.000 0.000 0.200 0.200 ;\n        RECT 0.200 0.000 0.500 0.200 ;\n    END\n"
+    ));
+    assert!(text.contains("  OBS\n    LAYER met1 ;\n      RECT 0.500 0.000 1.500 0.200 ;\n  END\n"));
+
+    lef.save(out_lef(test_work_dir(name), name))?;
+    Ok(())
+}
+
+#[test]
+fn test_native_def() -> Result<()> {
+    let name = "sramgen_native_def";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_wire(&mut lib, name);
+    let lef = LefMacro::new(&lib.pdk, &sram_lef_params(&cell))?;
+
+    let text = to_def(&lef)?;
+    assert!(text.contains(&format!("DESIGN {name} ;")));
+    assert!(text.contains("DIEAREA ( 0 0 ) ( 2000 1200 ) ;"));
+    assert!(text.contains("PINS 3 ;"));
+    assert!(text.contains(
+        "  - din[0] + NET din[0] + DIRECTION INPUT + USE SIGNAL\n    + PORT\n      + LAYER met1 ( 0 0 ) ( 200 200 )\n      + FIXED ( 0 0 ) N\n    + PORT\n      + LAYER met1 ( 0 0 ) ( 300 200 )\n      + FIXED ( 200 0 ) N\n  ;\n"
+    ));
+    assert!(text.contains("+ LAYER met2 ( 0 0 ) ( 2000 200 )\n      + FIXED ( 0 1000 ) N"));
+    assert!(text.contains("BLOCKAGES 1 ;\n  - LAYER met1\n    RECT ( 500 0 ) ( 1500 200 )\n  ;\n"));
+
+    save_def(&lef, out_def(test_work_dir(name), name))?;
+    Ok(())
+}
+
+#[test]
+fn test_sram_lef() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &SramConfig {
+            num_words: 32,
+            data_width: 8,
+            mux_ratio: 2,
+            write_size: 4,
+            control: ControlMode::ReplicaV1,
+            netlist_formats: default_netlist_formats(),
+            tech: Default::default(),
+            corners: Vec::new(),
+            pins: Default::default(),
+            lib: Default::default(),
+        },
+    )?;
+    let sram = &plan.sram_params;
+    let name = &sram.name;
+
+    let mut lib = sky130::pdk_lib(name)?;
+    let layout = draw_sram(&mut lib, sram)?;
+    let lef = LefMacro::new(&lib.pdk, &sram_lef_params(&layout.cell))?;
+
+    // Data in, data out, write mask, address, clock, write enable, and both supplies.
+    let num_pins = 2 * sram.data_width + sram.wmask_width + sram.addr_width + 4;
+    assert_eq!(lef.pins.len(), num_pins);
+    assert!(lef
+        .pins
+        .iter()
+        .any(|pin| pin.name == "clk" && pin.use_ == PinUse::Clock));
+    assert!(lef.obstructions.contains_key("met1"));
+
+    lef.save(out_lef(test_work_dir(name), name))?;
+    Ok(())
+}
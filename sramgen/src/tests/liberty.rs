@@ -0,0 +1,137 @@
+use approx::assert_relative_eq;
+
+use crate::config::sram::SramConfig;
+use crate::liberty::characterize::{bisect, CharParams};
+use crate::liberty::{ConstraintTables, DelayTables, SramLib, SramPower};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::generate_plan;
+use crate::verification::pvt::{PvtCorner, NOMINAL_TEMP, NOMINAL_VDD};
+use crate::verification::PortClass;
+use crate::Result;
+
+#[test]
+fn test_bisect() -> Result<()> {
+    let mut runs = 0;
+    let x = bisect(0.0, 1.0, 1e-3, |x| {
+        runs += 1;
+        Ok(x >= 0.3)
+    })?;
+    assert!(x >= 0.3 && x - 0.3 <= 1e-3);
+    assert!(runs < 15);
+
+    assert_relative_eq!(bisect(0.0, 1.0, 1e-3, |_| Ok(true))?, 0.0);
+    assert!(bisect(0.0, 1.0, 1e-3, |_| Ok(false)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sram_liberty() -> Result<()> {
+    let table = vec![vec![1e-9, 2e-9], vec![1.5e-9, 2.5e-9]];
+    let lib = SramLib {
+        cell_name: "sramgen_sram_test".to_string(),
+        corner: PvtCorner {
+            process: "tt".to_string(),
+            vdd: NOMINAL_VDD,
+            temp: NOMINAL_TEMP,
+        },
+        num_words: 32,
+        data_width: 8,
+        addr_width: 5,
+        wmask_width: 2,
+        slews: vec![50e-12, 200e-12],
+        loads: vec![5e-15, 20e-15],
+        input_cap: 5e-15,
+        clk_to_dout: DelayTables {
+            cell_rise: table.clone(),
+            cell_fall: table.clone(),
+            rise_transition: table.clone(),
+            fall_transition: table,
+        },
+        constraints: vec![
+            (
+                PortClass::Addr,
+                ConstraintTables {
+                    setup: vec![100e-12, 150e-12],
+                    hold: vec![0.0, 0.0],
+                },
+            ),
+            (
+                PortClass::WriteEnable,
+                ConstraintTables {
+                    setup: vec![100e-12, 150e-12],
+                    hold: vec![10e-12, 20e-12],
+                },
+            ),
+        ],
+        power: Some(SramPower {
+            read_energy: 1.5e-12,
+            write_energy: 2e-12,
+            leakage_power: 30e-9,
+        }),
+    };
+
+    assert_eq!(lib.lib_name(), "sramgen_sram_test_tt_1p8v_25c");
+    let liberty = lib.to_liberty()?;
+
+    assert!(liberty.starts_with("library (sramgen_sram_test_tt_1p8v_25c) {"));
+    assert!(liberty.contains("    pg_pin (vdd) {"));
+    assert!(liberty.contains("    pg_pin (vss) {"));
+    assert!(liberty.contains("    index_1 (\"0.050000, 0.200000\");"));
+    assert!(liberty.contains("    index_2 (\"0.005000, 0.020000\");"));
+    assert!(liberty.contains("  type (wmask_bus) {"));
+    assert!(liberty.contains("    bus (addr) {\n      bus_type : addr_bus;"));
+    assert!(liberty.contains("    pin (we) {\n      direction : input;"));
+    assert!(liberty.contains("        timing_type : hold_rising;"));
+    assert!(liberty.contains("          values (\"0.010000, 0.020000\");"));
+    assert!(liberty.contains(
+        "          values (\"1.000000, 2.000000\", \\\n            \"1.500000, 2.500000\");"
+    ));
+    assert!(liberty.contains("    cell_leakage_power : 30.000000;"));
+    assert!(liberty.contains(
+        "        when : \"!we\";\n        related_pg_pin : vdd;\n        rise_power (scalar) {\n          values (\"1.500000\");"
+    ));
+    assert_eq!(liberty.matches('{').count(), liberty.matches('}').count());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_lib_config() -> Result<()> {
+    let config = r#"
+        num_words = 32
+        data_width = 8
+        mux_ratio = 2
+        write_size = 8
+        control = "ReplicaV1"
+
+        [lib]
+        slews = [100e-12, 400e-12]
+        clk_period = 10e-9
+        "#;
+    let config: SramConfig = toml::from_str(config)?;
+    assert_eq!(
+        config.lib,
+        CharParams {
+            slews: vec![100e-12, 400e-12],
+            clk_period: 10e-9,
+            ..Default::default()
+        }
+    );
+
+    let plan = generate_plan(ExtractionResult {}, &config)?;
+    assert_eq!(plan.char_params, config.lib);
+
+    let config = SramConfig {
+        lib: CharParams {
+            loads: Vec::new(),
+            ..Default::default()
+        },
+        ..config
+    };
+    let err = generate_plan(ExtractionResult {}, &config)
+        .err()
+        .expect("characterization without loads should be rejected");
+    assert!(format!("{err:#}").contains("At least one slew and one load"));
+    Ok(())
+}
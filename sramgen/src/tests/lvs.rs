@@ -0,0 +1,321 @@
+use layout21::raw::{Cell, Instance, Point, Rect};
+use layout21::utils::Ptr;
+use pdkprims::tech::sky130;
+use pdkprims::PdkLib;
+use std::collections::HashMap;
+use vlsir::circuit::{Instance as SchematicInstance, Module};
+use vlsir::reference::To;
+use vlsir::{QualifiedName, Reference};
+
+use crate::config::sram::{default_netlist_formats, ControlMode, SramConfig, SramParams};
+use crate::layout::draw_rect;
+use crate::layout::lvs::{check_lvs, DeviceRule, LvsRules, Short, ViaRule};
+use crate::layout::sram::draw_sram;
+use crate::plan::extract::ExtractionResult;
+use crate::plan::generate_plan;
+use crate::schematic::conns::{port_inout, sig_conn, signal};
+use crate::schematic::local_reference;
+use crate::schematic::sram::sram;
+use crate::verification::netgen::parse_report;
+use crate::Result;
+
+/// Checks the SRAM layout `cell` against the schematic of `params`
+/// using the built-in LVS checker and the SKY130 rule table.
+pub(crate) fn assert_sram_lvs_clean(
+    lib: &PdkLib,
+    cell: &Ptr<Cell>,
+    params: &SramParams,
+) -> Result<()> {
+    let report = check_lvs(&lib.pdk, cell, &sram(params), &LvsRules::sky130()?)?;
+    assert!(report.passed(), "{report}");
+    Ok(())
+}
+
+#[test]
+fn test_parse_netgen_report() -> Result<()> {
+    let matched = "Subcircuit summary:\nCircuit 1: sram |Circuit 2: sram\n\nFinal result: Circuits match uniquely.\n";
+    assert!(parse_report(matched)?);
+
+    let property_errors = "Property errors were found.\n\nFinal result: Circuits match uniquely.\n";
+    assert!(!parse_report(property_errors)?);
+
+    let mismatched = "Netlists do not match.\n\nFinal result: Netlists do not match.\n";
+    assert!(!parse_report(mismatched)?);
+
+    assert!(parse_report("Netgen exited early\n").is_err());
+    Ok(())
+}
+
+const BUF: &str = "sramgen_native_lvs_buf";
+
+fn rect(x0: isize, y0: isize, x1: isize, y1: isize) -> Rect {
+    Rect::new(Point::new(x0, y0), Point::new(x1, y1))
+}
+
+/// Draws a buffer with input `A` and output `X` on m1, and a well tap `VPB` on nwell.
+fn draw_buf(lib: &mut PdkLib) -> Ptr<Cell> {
+    let m1 = lib.pdk.get_layerkey("m1").unwrap();
+    let nwell = lib.pdk.get_layerkey("nwell").unwrap();
+    let mut cell = Cell::empty(BUF);
+    cell.add_pin("A", m1, rect(0, 0, 200, 200));
+    cell.add_pin("X", m1, rect(800, 0, 1_000, 200));
+    cell.add_pin("VPB", nwell, rect(0, 0, 1_000, 1_000));
+    let ptr = Ptr::new(cell);
+    lib.lib.cells.push(ptr.clone());
+    ptr
+}
+
+/// Draws two buffers in series between the ports `din` on m1 and `dout` on m2,
+/// optionally leaving out the wire between them and shorting `din` to `dout`.
+fn draw_bufs(lib: &mut PdkLib, name: &str, wire: bool, short: bool) -> Ptr<Cell> {
+    let buf = draw_buf(lib);
+    let m1 = lib.pdk.get_layerkey("m1").unwrap();
+    let m2 = lib.pdk.get_layerkey("m2").unwrap();
+    let via = lib.pdk.get_layerkey("via").unwrap();
+
+    let mut cell = Cell::empty(name);
+    for (i, x) in [0, 2_000].into_iter().enumerate() {
+        cell.layout_mut().add_inst(Instance {
+            inst_name: format!("b{i}"),
+            cell: buf.clone(),
+            loc: Point::new(x, 0),
+            reflect_vert: false,
+            angle: None,
+        });
+    }
+    if wire {
+        cell.layout_mut()
+            .add(draw_rect(rect(800, 0, 2_200, 200), m1));
+    }
+    cell.layout_mut()
+        .add(draw_rect(rect(2_850, 50, 2_950, 150), via));
+    cell.add_pin("din", m1, rect(0, 0, 200, 200));
+    cell.add_pin("dout", m2, rect(2_800, 0, 3_000, 200));
+    if short {
+        cell.layout_mut()
+            .add(draw_rect(rect(50, 50, 150, 150), via));
+        cell.layout_mut().add(draw_rect(rect(0, 0, 3_000, 200), m2));
+    }
+
+    let ptr = Ptr::new(cell);
+    lib.lib.cells.push(ptr.clone());
+    ptr
+}
+
+fn buf_instance(name: &str, a: &str, x: &str) -> SchematicInstance {
+    let mut connections = HashMap::new();
+    connections.insert("A".to_string(), sig_conn(&signal(a)));
+    connections.insert("X".to_string(), sig_conn(&signal(x)));
+    connections.insert("VPB".to_string(), sig_conn(&signal(a)));
+    SchematicInstance {
+        name: name.to_string(),
+        module: Some(Reference {
+            to: Some(To::External(QualifiedName {
+                domain: "test".to_string(),
+                name: BUF.to_string(),
+            })),
+        }),
+        parameters: HashMap::new(),
+        connections,
+    }
+}
+
+/// The schematic of two buffers in series, the second within a wrapper module.
+fn bufs_schematic(name: &str) -> Vec<Module> {
+    let wrapper = Module {
+        name: format!("{name}_wrapper"),
+        ports: vec![port_inout(&signal("a")), port_inout(&signal("x"))],
+        signals: vec![],
+        instances: vec![buf_instance("b", "a", "x")],
+        parameters: vec![],
+    };
+
+    let mut connections = HashMap::new();
+    connections.insert("a".to_string(), sig_conn(&signal("mid")));
+    connections.insert("x".to_string(), sig_conn(&signal("dout")));
+    let top = Module {
+        name: name.to_string(),
+        ports: vec![port_inout(&signal("din")), port_inout(&signal("dout"))],
+        signals: vec![signal("mid")],
+        instances: vec![
+            buf_instance("b0", "din", "mid"),
+            SchematicInstance {
+                name: "b1".to_string(),
+                module: local_reference(&wrapper.name),
+                parameters: HashMap::new(),
+                connections,
+            },
+        ],
+        parameters: vec![],
+    };
+
+    vec![wrapper, top]
+}
+
+fn buf_rules() -> LvsRules {
+    LvsRules {
+        vias: vec![ViaRule {
+            layer: "via".to_string(),
+            connects: vec!["m1".to_string(), "m2".to_string()],
+        }],
+        devices: vec![DeviceRule {
+            cell: BUF.to_string(),
+            module: BUF.to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_native_lvs() -> Result<()> {
+    let name = "sramgen_native_lvs";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_bufs(&mut lib, name, true, false);
+    let report = check_lvs(&lib.pdk, &cell, &bufs_schematic(name), &buf_rules())?;
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.devices[0].layout, 2);
+    assert!(report.to_string().contains("PASSED"));
+    Ok(())
+}
+
+#[test]
+fn test_native_lvs_open() -> Result<()> {
+    let name = "sramgen_native_lvs_open";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_bufs(&mut lib, name, false, false);
+    let report = check_lvs(&lib.pdk, &cell, &bufs_schematic(name), &buf_rules())?;
+    assert!(!report.passed());
+    assert!(report.shorts.is_empty() && report.net_mismatches.is_empty());
+
+    // The net between the buffers is split into its two pins.
+    let a = format!("{BUF}.a");
+    let x = format!("{BUF}.x");
+    let unmatched = report
+        .unmatched_nets
+        .iter()
+        .map(|n| {
+            (
+                n.pins.keys().cloned().collect::<Vec<_>>(),
+                n.layout,
+                n.schematic,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        unmatched,
+        vec![
+            (vec![a.clone()], 1, 0),
+            (vec![a, x.clone()], 0, 1),
+            (vec![x], 1, 0),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_native_lvs_short() -> Result<()> {
+    let name = "sramgen_native_lvs_short";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_bufs(&mut lib, name, true, true);
+    let report = check_lvs(&lib.pdk, &cell, &bufs_schematic(name), &buf_rules())?;
+    assert!(!report.passed());
+    assert_eq!(
+        report.shorts,
+        vec![Short {
+            nets: vec!["din".to_string(), "dout".to_string()]
+        }]
+    );
+    assert!(report.to_string().contains("1 shorts:\n    din, dout"));
+    Ok(())
+}
+
+#[test]
+fn test_native_lvs_pins() -> Result<()> {
+    let name = "sramgen_native_lvs_pins";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_bufs(&mut lib, name, true, false);
+    let mut modules = bufs_schematic(name);
+    let top = modules.last_mut().unwrap();
+    top.ports.push(port_inout(&signal("en")));
+    top.instances[0]
+        .connections
+        .insert("EN".to_string(), sig_conn(&signal("en")));
+
+    let report = check_lvs(&lib.pdk, &cell, &modules, &buf_rules())?;
+    assert!(!report.passed());
+    let missing = report
+        .pin_mismatches
+        .iter()
+        .map(|p| (p.module.as_str(), p.missing.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        missing,
+        vec![
+            (BUF, vec!["en".to_string()]),
+            (name, vec!["en".to_string()])
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_native_lvs_unknown_external() -> Result<()> {
+    let name = "sramgen_native_lvs_unknown_external";
+    let mut lib = sky130::pdk_lib(name)?;
+    let cell = draw_bufs(&mut lib, name, true, false);
+    let modules = bufs_schematic(name);
+
+    // The buffers can be neither compared nor silently dropped.
+    let mut rules = buf_rules();
+    rules.devices.clear();
+    let err = check_lvs(&lib.pdk, &cell, &modules, &rules)
+        .err()
+        .expect("schematic instances of external modules without a rule should be rejected");
+    assert!(err.to_string().contains("has no LVS device rule"));
+
+    rules.ignore.push(BUF.to_string());
+    let report = check_lvs(&lib.pdk, &cell, &modules, &rules)?;
+    assert!(report.devices.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_sky130_lvs_rules() -> Result<()> {
+    let rules = LvsRules::sky130()?;
+    assert!(!rules.vias.is_empty());
+    let bitcell = rules
+        .device("sky130_fd_bd_sram__sram_sp_cell_opt1")
+        .unwrap();
+    assert_eq!(bitcell.module, "sram_sp_cell");
+    assert_eq!(bitcell.pin_name("vpwr".to_string()), "vdd");
+    assert_eq!(bitcell.pin_name("bl".to_string()), "bl");
+    for control in ["sram22_control_logic", "sramgen_control_replica_v1"] {
+        assert!(rules.device(control).unwrap().skips("m2_block"));
+    }
+    assert!(rules.ignore.iter().any(|m| m == "sky130_fd_pr__nfet_01v8"));
+    Ok(())
+}
+
+#[test]
+fn test_sram_native_lvs() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &SramConfig {
+            num_words: 64,
+            data_width: 8,
+            mux_ratio: 4,
+            write_size: 4,
+            control: ControlMode::ReplicaV1,
+            netlist_formats: default_netlist_formats(),
+            tech: Default::default(),
+            corners: Vec::new(),
+            pins: Default::default(),
+            lib: Default::default(),
+        },
+    )?;
+    let sram = &plan.sram_params;
+    let mut lib = sky130::pdk_lib(&sram.name)?;
+    let layout = draw_sram(&mut lib, sram)?;
+    assert_sram_lvs_clean(&lib, &layout.cell, sram)
+}
@@ -7,17 +7,36 @@ mod col_inv;
 mod control;
 mod decoder;
 mod dff;
+mod diagnostics;
 mod dout_buffer;
+mod drc;
 mod edge_detector;
 mod gate;
 mod guard_ring;
 mod inv_chain;
 mod latch;
+mod lef;
+mod liberty;
+mod lvs;
 mod mux;
+mod netlist;
+mod ngspice;
+mod patterns;
+mod pins;
+mod power;
 mod precharge;
+mod pvt;
+mod random;
+mod read_margin;
 mod sense_amp;
+mod snm;
 mod sram;
+mod tech;
+mod timing;
 mod tmc;
+mod vcd;
+mod vectors;
+mod waveforms;
 mod wl_driver;
 mod wmask_control;
 
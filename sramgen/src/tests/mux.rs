@@ -3,6 +3,7 @@ use crate::layout::mux::read::*;
 use crate::layout::mux::write::*;
 use crate::paths::out_gds;
 use crate::tech::BITCELL_WIDTH;
+use crate::tests::drc::assert_drc_clean;
 use crate::tests::test_work_dir;
 use crate::Result;
 use pdkprims::tech::sky130;
@@ -30,7 +31,7 @@ fn test_column_read_mux() -> Result<()> {
 fn test_column_read_mux_2_array() -> Result<()> {
     let name = "sramgen_column_read_mux_2_array";
     let mut lib = sky130::pdk_lib(name)?;
-    draw_read_mux_array(
+    let cell = draw_read_mux_array(
         &mut lib,
         &ReadMuxArrayParams {
             name: name.to_string(),
@@ -45,7 +46,8 @@ fn test_column_read_mux_2_array() -> Result<()> {
     )?;
 
     let work_dir = test_work_dir(name);
-    lib.save_gds(out_gds(work_dir, name))?;
+    lib.save_gds(out_gds(&work_dir, name))?;
+    assert_drc_clean(&lib, &cell, &work_dir)?;
 
     Ok(())
 }
@@ -54,7 +56,7 @@ fn test_column_read_mux_2_array() -> Result<()> {
 fn test_column_read_mux_4_array() -> Result<()> {
     let name = "sramgen_column_read_mux_4_array";
     let mut lib = sky130::pdk_lib(name)?;
-    draw_read_mux_array(
+    let cell = draw_read_mux_array(
         &mut lib,
         &ReadMuxArrayParams {
             name: name.to_string(),
@@ -69,7 +71,8 @@ fn test_column_read_mux_4_array() -> Result<()> {
     )?;
 
     let work_dir = test_work_dir(name);
-    lib.save_gds(out_gds(work_dir, name))?;
+    lib.save_gds(out_gds(&work_dir, name))?;
+    assert_drc_clean(&lib, &cell, &work_dir)?;
 
     Ok(())
 }
@@ -78,7 +81,7 @@ fn test_column_read_mux_4_array() -> Result<()> {
 fn test_column_read_mux_8_array() -> Result<()> {
     let name = "sramgen_column_read_mux_8_array";
     let mut lib = sky130::pdk_lib(name)?;
-    draw_read_mux_array(
+    let cell = draw_read_mux_array(
         &mut lib,
         &ReadMuxArrayParams {
             name: name.to_string(),
@@ -93,7 +96,8 @@ fn test_column_read_mux_8_array() -> Result<()> {
     )?;
 
     let work_dir = test_work_dir(name);
-    lib.save_gds(out_gds(work_dir, name))?;
+    lib.save_gds(out_gds(&work_dir, name))?;
+    assert_drc_clean(&lib, &cell, &work_dir)?;
 
     Ok(())
 }
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+use vlsir::circuit::Package;
+
+use crate::config::precharge::*;
+use crate::config::sram::{ControlMode, SramConfig};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::{execute_plan, generate_plan, ExecutePlanParams, TaskKey};
+use crate::schematic::netlist::netlist_package;
+use crate::schematic::precharge::*;
+use crate::schematic::NetlistFormat;
+use crate::tech::all_external_modules;
+use crate::tests::test_work_dir;
+use crate::verification::lvs::LvsTool;
+use crate::verification::netlist::{write_include, write_lib};
+use crate::verification::{SimOptions, SimulatorKind};
+use crate::Result;
+
+fn precharge_pkg() -> Package {
+    let params = PrechargeArrayParams {
+        width: 2,
+        flip_toggle: false,
+        instance_params: PrechargeParams {
+            name: "precharge".to_string(),
+            length: 150,
+            pull_up_width: 1_200,
+            equalizer_width: 1_000,
+        },
+        name: "precharge_array".to_string(),
+    };
+    Package {
+        domain: "sramgen_netlist".to_string(),
+        desc: "Sramgen generated cells".to_string(),
+        modules: precharge_array(&params),
+        ext_modules: all_external_modules(NetlistFormat::Spectre),
+    }
+}
+
+#[test]
+fn test_netlist_buses_msb_first() -> Result<()> {
+    let netlist = netlist_package(&precharge_pkg(), NetlistFormat::NgSpice)?;
+
+    assert!(netlist.contains(".SUBCKT precharge_array\n+ vdd\n+ en_b\n+ bl[1]\n+ bl[0]\n"));
+    assert!(netlist.contains("xprecharge_1\n+ vdd\n+ bl[1]\n+ br[1]\n+ en_b\n+ precharge\n"));
+    assert!(netlist.contains("+ sky130_fd_pr__pfet_01v8\n+ l=0.15\n+ w=1.2\n"));
+    assert!(!netlist.contains("PRELUDE"));
+
+    Ok(())
+}
+
+#[test]
+fn test_netlist_preludes() -> Result<()> {
+    let spectre = netlist_package(&precharge_pkg(), NetlistFormat::Spectre)?;
+    assert!(spectre
+        .contains(".SUBCKT sky130_fd_pr__special_nfet_pass d g s b\n.PARAM w=1.0 l=1.0 mult=1\n"));
+
+    let cdl = netlist_package(&precharge_pkg(), NetlistFormat::Cdl)?;
+    assert!(cdl.contains(".SUBCKT sky130_fd_pr__nfet_01v8 d g s b PARAMS: w=1.0 l=1.0 mult=1\n"));
+    assert!(cdl.contains("*.PININFO vdd:B en_b:I bl[1]:B bl[0]:B br[1]:B br[0]:B\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_netlist_formats_needed_by_tasks() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &SramConfig {
+            num_words: 32,
+            data_width: 8,
+            mux_ratio: 2,
+            write_size: 8,
+            control: ControlMode::ReplicaV1,
+            netlist_formats: vec![NetlistFormat::Spectre],
+            tech: Default::default(),
+            corners: Vec::new(),
+            pins: Default::default(),
+            lib: Default::default(),
+        },
+    )?;
+    let work_dir = test_work_dir("test_netlist_formats_needed_by_tasks");
+
+    let lvs = HashSet::from([TaskKey::RunLvs]);
+    let err = execute_plan(ExecutePlanParams {
+        work_dir: &work_dir,
+        plan: &plan,
+        tasks: &lvs,
+        ctx: None,
+        sim: Default::default(),
+        lef_tool: Default::default(),
+        characterizer: Default::default(),
+        drc_tool: Default::default(),
+        lvs_tool: LvsTool::Netgen,
+        read_margin: Default::default(),
+    })
+    .err()
+    .expect("LVS without a CDL netlist should be rejected");
+    assert!(err.to_string().contains("does not include `Cdl`"));
+
+    let sim = HashSet::from([TaskKey::RunSim]);
+    let err = execute_plan(ExecutePlanParams {
+        work_dir: &work_dir,
+        plan: &plan,
+        tasks: &sim,
+        ctx: None,
+        sim: SimOptions {
+            simulator: SimulatorKind::Ngspice,
+            ..Default::default()
+        },
+        lef_tool: Default::default(),
+        characterizer: Default::default(),
+        drc_tool: Default::default(),
+        lvs_tool: Default::default(),
+        read_margin: Default::default(),
+    })
+    .err()
+    .expect("ngspice simulation without an ngspice netlist should be rejected");
+    assert!(err.to_string().contains("does not include `NgSpice`"));
+
+    Ok(())
+}
+
+#[test]
+fn test_netlist_model_cards_quote_paths() -> Result<()> {
+    let mut out = String::new();
+    write_lib(&mut out, r"C:\pdk models\sky130.lib", "tt")?;
+    write_include(&mut out, "models/café.spice")?;
+    assert_eq!(
+        out,
+        ".lib \"C:\\pdk models\\sky130.lib\" tt\n.include \"models/café.spice\"\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use approx::assert_relative_eq;
+
+use crate::verification::ngspice::{parse_dc_rawfile, parse_rawfile};
+use crate::Result;
+
+const RAWFILE: &str = "Title: * SRAM22 generated testbench for sram
+Date: Thu Jan  1 00:00:00  2022
+Plotname: Transient Analysis
+Flags: real
+No. Variables: 4
+No. Points: 3
+Variables:
+\t0\ttime\ttime
+\t1\tv(clk)\tvoltage
+\t2\tdout[0]\tvoltage
+\t3\tvvdd#branch\tcurrent
+Values:
+ 0\t0.000000000000000e+00
+\t0.000000000000000e+00
+\t1.800000000000000e+00
+\t-1.000000000000000e-06
+ 1\t1.000000000000000e-09
+\t1.800000000000000e+00
+\t1.800000000000000e+00
+\t-2.000000000000000e-06
+ 2\t2.000000000000000e-09
+\t0.000000000000000e+00
+\t0.000000000000000e+00
+\t-3.000000000000000e-06
+";
+
+#[test]
+fn test_parse_ngspice_rawfile() -> Result<()> {
+    let data = parse_rawfile(RAWFILE)?;
+
+    assert_eq!(data.time.len(), 3);
+    assert_relative_eq!(data.time[2], 2e-9);
+
+    let clk = data.signal("v(clk)").unwrap();
+    assert_relative_eq!(clk[1], 1.8);
+
+    let dout = data.signal("v(dout[0])").unwrap();
+    assert_relative_eq!(dout[2], 0.0);
+
+    let ivdd = data.signal("i(vvdd)").unwrap();
+    assert_relative_eq!(ivdd[0], -1e-6);
+
+    Ok(())
+}
+
+const DC_RAWFILE: &str = "Title: * SRAM22 generated hold SNM testbench
+Date: Thu Jan  1 00:00:00  2022
+Plotname: DC transfer characteristic
+Flags: real
+No. Variables: 3
+No. Points: 2
+Variables:
+\t0\tv-sweep\tvoltage
+\t1\tv(q)\tvoltage
+\t2\tv(qb)\tvoltage
+Values:
+ 0\t0.000000000000000e+00
+\t1.800000000000000e+00
+\t1.790000000000000e+00
+ 1\t1.800000000000000e+00
+\t1.000000000000000e-02
+\t0.000000000000000e+00
+";
+
+#[test]
+fn test_parse_ngspice_dc_rawfile() -> Result<()> {
+    let data = parse_dc_rawfile(DC_RAWFILE)?;
+
+    assert_eq!(data.sweep, vec![0.0, 1.8]);
+    assert_relative_eq!(data.signal("v(q)").unwrap()[1], 1e-2);
+    assert_relative_eq!(data.signal("v(qb)").unwrap()[0], 1.79);
+
+    assert!(parse_rawfile(DC_RAWFILE).is_err());
+    assert!(parse_dc_rawfile(RAWFILE).is_err());
+
+    Ok(())
+}
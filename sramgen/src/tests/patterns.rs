@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use crate::config::sram::{default_netlist_formats, ControlMode, SramConfig};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::generate_plan;
+use crate::tests::test_work_dir;
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::patterns::{generate, Pattern, PatternParams};
+use crate::verification::{run_sram_testbench, Op, SimOptions, Stimulus};
+use crate::Result;
+
+const PARAMS: PatternParams = PatternParams {
+    addr_width: 4,
+    data_width: 8,
+    wmask_width: 2,
+};
+
+/// Returns the set of addresses read by `ops`,
+/// checking that no address is read before it is written.
+fn read_addrs(ops: &[Op]) -> HashSet<BitSignal> {
+    let mut written = HashSet::new();
+    let mut read = HashSet::new();
+    for op in ops {
+        match op {
+            Op::Read { addr } => {
+                assert!(written.contains(addr), "read of uninitialized address");
+                read.insert(addr.clone());
+            }
+            Op::Write { addr, .. } => {
+                written.insert(addr.clone());
+            }
+            Op::WriteMasked { addr, mask, .. } => {
+                assert!(
+                    written.contains(addr),
+                    "masked write to uninitialized address"
+                );
+                assert_eq!(mask.width(), PARAMS.wmask_width);
+            }
+            Op::Idle => {}
+        }
+    }
+    read
+}
+
+#[test]
+fn test_patterns_cover_all_addresses() {
+    for pattern in [
+        Pattern::MarchCMinus,
+        Pattern::MarchB,
+        Pattern::Checkerboard,
+        Pattern::WalkingOnes,
+        Pattern::WalkingZeros,
+        Pattern::AddressDecoder,
+    ] {
+        let ops = generate(pattern, &PARAMS);
+        assert_eq!(
+            read_addrs(&ops).len(),
+            PARAMS.num_words(),
+            "{pattern} does not read every address"
+        );
+        assert!(
+            ops.iter().any(|op| matches!(op, Op::WriteMasked { .. })),
+            "{pattern} does not exercise the write mask"
+        );
+    }
+}
+
+#[test]
+fn test_march_lengths() {
+    let params = PatternParams {
+        wmask_width: 1,
+        ..PARAMS
+    };
+    let n = params.num_words();
+    assert_eq!(generate(Pattern::MarchCMinus, &params).len(), 10 * n);
+    assert_eq!(generate(Pattern::MarchB, &params).len(), 17 * n);
+}
+
+#[test]
+fn test_walking_covers_all_bits() {
+    let params = PatternParams {
+        addr_width: 2,
+        data_width: 8,
+        wmask_width: 1,
+    };
+    for value in [true, false] {
+        let pattern = if value {
+            Pattern::WalkingOnes
+        } else {
+            Pattern::WalkingZeros
+        };
+        let mut walked = HashSet::new();
+        for op in generate(pattern, &params) {
+            if let Op::Write { data, .. } = op {
+                assert_eq!(data.bits().filter(|&b| b == value).count(), 1);
+                walked.insert(data.bits().position(|b| b == value).unwrap());
+            }
+        }
+        assert_eq!(walked.len(), params.data_width);
+    }
+}
+
+#[test]
+fn test_pattern_max_ops() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &SramConfig {
+            num_words: 32,
+            data_width: 8,
+            mux_ratio: 2,
+            write_size: 8,
+            control: ControlMode::ReplicaV1,
+            netlist_formats: default_netlist_formats(),
+            tech: Default::default(),
+            corners: Vec::new(),
+            pins: Default::default(),
+            lib: Default::default(),
+        },
+    )?;
+    let name = &plan.sram_params.name;
+
+    // March C- applies 10 operations to each of the 32 words.
+    let err = run_sram_testbench(
+        &plan.sram_params,
+        &plan.tech,
+        &plan.corners,
+        test_work_dir("test_pattern_max_ops"),
+        name,
+        &SimOptions {
+            stimulus: Stimulus::Pattern(Pattern::MarchCMinus),
+            max_ops: Some(100),
+            ..Default::default()
+        },
+    )
+    .err()
+    .expect("stimuli with too many operations should be rejected");
+    assert!(err
+        .to_string()
+        .contains("has 320 operations, more than the maximum of 100"));
+    Ok(())
+}
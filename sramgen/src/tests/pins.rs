@@ -0,0 +1,261 @@
+use pdkprims::tech::sky130;
+
+use crate::config::pins::{BitOrder, PinConfig, PinGroupConfig, PinTracks, Side, TrackConfig};
+use crate::config::sram::{default_netlist_formats, ControlMode, SramConfig};
+use crate::layout::lef::{sram_lef_params, LefMacro, PinUse};
+use crate::layout::sram::draw_sram;
+use crate::paths::{out_gds, out_lef};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::generate_plan;
+use crate::tests::drc::assert_sram_drc_clean;
+use crate::tests::lvs::assert_sram_lvs_clean;
+use crate::tests::test_work_dir;
+use crate::Result;
+
+fn sram_config(pins: PinConfig) -> SramConfig {
+    SramConfig {
+        num_words: 32,
+        data_width: 8,
+        mux_ratio: 2,
+        write_size: 4,
+        control: ControlMode::ReplicaV1,
+        netlist_formats: default_netlist_formats(),
+        tech: Default::default(),
+        corners: Vec::new(),
+        pins,
+        lib: Default::default(),
+    }
+}
+
+fn group(side: Side) -> Option<PinGroupConfig> {
+    Some(PinGroupConfig {
+        side,
+        pitch: None,
+        offset: None,
+        order: BitOrder::LsbFirst,
+    })
+}
+
+#[test]
+fn test_parse_pin_config() -> Result<()> {
+    let config: SramConfig = toml::from_str(
+        r#"
+        num_words = 32
+        data_width = 8
+        mux_ratio = 2
+        write_size = 8
+        control = "ReplicaV1"
+
+        [pins.din]
+        side = "left"
+        pitch = 1200
+        offset = 5000
+
+        [pins.ctrl]
+        side = "top"
+        order = "msb_first"
+
+        [pins.tracks.m3]
+        pitch = 680
+        offset = 340
+        "#,
+    )?;
+
+    assert_eq!(
+        config.pins.din,
+        Some(PinGroupConfig {
+            side: Side::Left,
+            pitch: Some(1_200),
+            offset: Some(5_000),
+            order: BitOrder::LsbFirst,
+        })
+    );
+    assert_eq!(
+        config.pins.ctrl,
+        Some(PinGroupConfig {
+            side: Side::Top,
+            pitch: None,
+            offset: None,
+            order: BitOrder::MsbFirst,
+        })
+    );
+    assert_eq!(config.pins.addr, None);
+    assert_eq!(
+        config.pins.tracks,
+        PinTracks {
+            m2: None,
+            m3: Some(TrackConfig {
+                pitch: 680,
+                offset: 340,
+            }),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sram_pin_placement() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &sram_config(PinConfig {
+            addr: group(Side::Right),
+            din: group(Side::Left),
+            dout: group(Side::Left),
+            wmask: None,
+            ctrl: group(Side::Top),
+            tracks: Default::default(),
+        }),
+    )?;
+    let sram = &plan.sram_params;
+    let name = format!("{}_pins", sram.name);
+
+    let mut lib = sky130::pdk_lib(&name)?;
+    let layout = draw_sram(&mut lib, sram)?;
+    let lef = LefMacro::new(&lib.pdk, &sram_lef_params(&layout.cell))?;
+
+    let on_side = |pin: &str, side: Side| {
+        let pin = lef.pins.iter().find(|p| p.name == pin).unwrap();
+        pin.shapes.values().flatten().any(|r| match side {
+            Side::Left => r.x0 == lef.bbox.x0,
+            Side::Right => r.x1 == lef.bbox.x1,
+            Side::Bottom => r.y0 == lef.bbox.y0,
+            Side::Top => r.y1 == lef.bbox.y1,
+        })
+    };
+    for i in 0..sram.data_width {
+        assert!(on_side(&format!("din[{i}]"), Side::Left));
+        assert!(on_side(&format!("dout[{i}]"), Side::Left));
+    }
+    for i in 0..sram.addr_width {
+        assert!(on_side(&format!("addr[{i}]"), Side::Right));
+    }
+    assert!(on_side("clk", Side::Top));
+    assert!(on_side("we", Side::Top));
+    for i in 0..sram.wmask_width {
+        assert!(on_side(&format!("wmask[{i}]"), Side::Bottom));
+    }
+    assert!(on_side("vdd", Side::Top));
+    assert!(on_side("vss", Side::Top));
+
+    // The routes around the guard ring must not break the layout.
+    let work_dir = test_work_dir(&name);
+    assert_sram_drc_clean(&lib, &layout.cell, &work_dir)?;
+    assert_sram_lvs_clean(&lib, &layout.cell, sram)?;
+
+    lib.save_gds(out_gds(&work_dir, &name))?;
+    lef.save(out_lef(&work_dir, &name))?;
+    Ok(())
+}
+
+#[test]
+fn test_sram_pin_pitch_too_small() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &sram_config(PinConfig {
+            din: Some(PinGroupConfig {
+                side: Side::Left,
+                pitch: Some(100),
+                offset: None,
+                order: BitOrder::LsbFirst,
+            }),
+            ..Default::default()
+        }),
+    )?;
+
+    let mut lib = sky130::pdk_lib(&plan.sram_params.name)?;
+    let err = draw_sram(&mut lib, &plan.sram_params)
+        .err()
+        .expect("pin pitch below the minimum should be rejected");
+    assert!(err.to_string().contains("must be at least"));
+    Ok(())
+}
+
+/// The routing tracks of met2 and met3 used by OpenROAD for SKY130.
+const SKY130_TRACKS: PinTracks = PinTracks {
+    m2: Some(TrackConfig {
+        pitch: 460,
+        offset: 230,
+    }),
+    m3: Some(TrackConfig {
+        pitch: 680,
+        offset: 340,
+    }),
+};
+
+#[test]
+fn test_sram_pins_on_track() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &sram_config(PinConfig {
+            addr: group(Side::Right),
+            din: group(Side::Left),
+            dout: group(Side::Left),
+            wmask: None,
+            ctrl: group(Side::Top),
+            tracks: SKY130_TRACKS,
+        }),
+    )?;
+    let sram = &plan.sram_params;
+    let name = format!("{}_tracks", sram.name);
+
+    let mut lib = sky130::pdk_lib(&name)?;
+    let layout = draw_sram(&mut lib, sram)?;
+    let lef = LefMacro::new(&lib.pdk, &sram_lef_params(&layout.cell))?;
+
+    // LEF coordinates are relative to the lower left corner of the macro.
+    let signals = lef
+        .pins
+        .iter()
+        .filter(|pin| matches!(pin.use_, PinUse::Signal | PinUse::Clock));
+    for pin in signals {
+        for (layer, rects) in pin.shapes.iter() {
+            for r in rects.iter() {
+                let (center, pitch, offset) = match layer.as_str() {
+                    "met2" => ((r.y0 + r.y1) / 2 - lef.bbox.y0, 460, 230),
+                    "met3" => ((r.x0 + r.x1) / 2 - lef.bbox.x0, 680, 340),
+                    _ => panic!("unexpected layer {layer} for pin {}", pin.name),
+                };
+                assert_eq!(
+                    (center - offset) % pitch,
+                    0,
+                    "pin {} is off-track on {layer}",
+                    pin.name
+                );
+            }
+        }
+    }
+
+    let work_dir = test_work_dir(&name);
+    assert_sram_drc_clean(&lib, &layout.cell, &work_dir)?;
+    assert_sram_lvs_clean(&lib, &layout.cell, sram)?;
+
+    lib.save_gds(out_gds(&work_dir, &name))?;
+    lef.save(out_lef(&work_dir, &name))?;
+    Ok(())
+}
+
+#[test]
+fn test_sram_pin_pitch_off_track() -> Result<()> {
+    let plan = generate_plan(
+        ExtractionResult {},
+        &sram_config(PinConfig {
+            din: Some(PinGroupConfig {
+                side: Side::Left,
+                pitch: Some(1_000),
+                offset: None,
+                order: BitOrder::LsbFirst,
+            }),
+            tracks: SKY130_TRACKS,
+            ..Default::default()
+        }),
+    )?;
+
+    let mut lib = sky130::pdk_lib(&plan.sram_params.name)?;
+    let err = draw_sram(&mut lib, &plan.sram_params)
+        .err()
+        .expect("pin pitch that is not a multiple of the track pitch should be rejected");
+    assert!(err
+        .to_string()
+        .contains("not a multiple of the m2 track pitch"));
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use approx::assert_relative_eq;
+
+use crate::verification::power::integrate;
+
+#[test]
+fn test_integrate() {
+    let time = [0.0, 1.0, 2.0, 4.0];
+    let values = [0.0, 2.0, 2.0, 0.0];
+
+    assert_relative_eq!(integrate(&time, &values, 0.0, 4.0), 5.0);
+    assert_relative_eq!(integrate(&time, &values, 0.5, 3.0), 0.75 + 2.0 + 1.5);
+    assert_relative_eq!(integrate(&time, &values, 5.0, 6.0), 0.0);
+}
@@ -4,8 +4,9 @@ use crate::config::precharge::*;
 use crate::layout::precharge::*;
 use crate::paths::{out_bin, out_gds};
 use crate::schematic::precharge::*;
-use crate::schematic::{generate_netlist, save_bin};
+use crate::schematic::{generate_netlist, save_bin, NetlistFormat};
 use crate::tech::all_external_modules;
+use crate::tests::drc::assert_drc_clean;
 use crate::tests::test_work_dir;
 use crate::Result;
 use pdkprims::tech::sky130;
@@ -20,7 +21,7 @@ fn test_precharge() -> Result<()> {
         equalizer_width: 1_000,
     };
     let pc = precharge(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: "sramgen_precharge".to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -33,7 +34,7 @@ fn test_precharge() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
     draw_precharge(&mut lib, &params)?;
@@ -58,7 +59,7 @@ fn test_precharge_array() -> Result<()> {
         name: name.to_string(),
     };
     let modules = precharge_array(&params);
-    let ext_modules = all_external_modules();
+    let ext_modules = all_external_modules(NetlistFormat::Spectre);
     let pkg = Package {
         domain: name.to_string(),
         desc: "Sramgen generated cells".to_string(),
@@ -71,12 +72,13 @@ fn test_precharge_array() -> Result<()> {
     let bin_path = out_bin(&work_dir, name);
     save_bin(&bin_path, name, pkg)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     let mut lib = sky130::pdk_lib(name)?;
-    draw_precharge_array(&mut lib, &params)?;
+    let cell = draw_precharge_array(&mut lib, &params)?;
 
     lib.save_gds(out_gds(&work_dir, name))?;
+    assert_drc_clean(&lib, &cell, &work_dir)?;
 
     Ok(())
 }
@@ -0,0 +1,47 @@
+use anyhow::bail;
+
+use crate::verification::pvt::{run_pvt_sweep, PvtCorner};
+use crate::Result;
+
+fn corner(process: &str, vdd: f64, temp: f64) -> PvtCorner {
+    PvtCorner {
+        process: process.to_string(),
+        vdd,
+        temp,
+    }
+}
+
+#[test]
+fn test_pvt_sweep_matrix() -> Result<()> {
+    let corners = vec![
+        corner("ss", 1.62, -40.0),
+        corner("ss", 1.98, 100.0),
+        corner("ff", 1.62, -40.0),
+        corner("ff", 1.98, 100.0),
+    ];
+    assert_eq!(corners[0].name(), "ss_1p62v_m40c");
+
+    let mut visited = Vec::new();
+    let report = run_pvt_sweep(&corners, |c| {
+        visited.push(c.name());
+        if c.process == "ss" && c.vdd < 1.8 {
+            bail!("read failed");
+        }
+        Ok(())
+    });
+
+    assert_eq!(visited.len(), corners.len());
+    let failures = report.failures().collect::<Vec<_>>();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].corner, corners[0]);
+    assert_eq!(failures[0].error.as_deref(), Some("read failed"));
+
+    assert_eq!(
+        report.matrix(),
+        "   | 1.62 V -40 C | 1.98 V 100 C\n\
+         ss | FAIL         | PASS        \n\
+         ff | PASS         | PASS        \n"
+    );
+
+    Ok(())
+}
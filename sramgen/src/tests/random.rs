@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use crate::verification::coverage::Coverage;
+use crate::verification::patterns::{self, Pattern, PatternParams};
+use crate::verification::random::{generate, OpWeights, RandomParams};
+use crate::verification::Op;
+use crate::Result;
+
+const PARAMS: PatternParams = PatternParams {
+    addr_width: 5,
+    data_width: 8,
+    wmask_width: 4,
+};
+
+#[test]
+fn test_random_ops_are_reproducible() -> Result<()> {
+    let params = RandomParams::builder().seed(1).num_ops(200).build()?;
+    let ops = generate(&params, &PARAMS)?;
+    assert_eq!(ops.len(), 200);
+    assert_eq!(ops, generate(&params, &PARAMS)?);
+
+    let other = RandomParams::builder().seed(2).num_ops(200).build()?;
+    assert_ne!(ops, generate(&other, &PARAMS)?);
+    Ok(())
+}
+
+#[test]
+fn test_random_ops_read_initialized_addresses() -> Result<()> {
+    let params = RandomParams::builder()
+        .seed(3)
+        .num_ops(500)
+        .weights(OpWeights {
+            read: 4.0,
+            write: 1.0,
+            write_masked: 2.0,
+            idle: 1.0,
+        })
+        .build()?;
+
+    let mut written = HashSet::new();
+    let mut kinds = [0; 4];
+    for op in generate(&params, &PARAMS)? {
+        match op {
+            Op::Read { addr } => {
+                assert!(written.contains(&addr));
+                kinds[0] += 1;
+            }
+            Op::Write { addr, .. } => {
+                written.insert(addr);
+                kinds[1] += 1;
+            }
+            Op::WriteMasked { addr, .. } => {
+                assert!(written.contains(&addr));
+                kinds[2] += 1;
+            }
+            Op::Idle => {
+                kinds[3] += 1;
+            }
+        }
+    }
+    assert!(kinds[0] > kinds[2] && kinds[2] > kinds[1]);
+    assert!(kinds[3] > 0);
+    Ok(())
+}
+
+#[test]
+fn test_random_ops_without_wmask() -> Result<()> {
+    let params = RandomParams::builder().seed(4).build()?;
+    let ops = generate(
+        &params,
+        &PatternParams {
+            wmask_width: 1,
+            ..PARAMS
+        },
+    )?;
+    assert!(!ops.iter().any(|op| matches!(op, Op::WriteMasked { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_coverage() {
+    let ops = patterns::generate(Pattern::MarchCMinus, &PARAMS);
+    let coverage = Coverage::from_ops(&ops, PARAMS.num_words(), 4, PARAMS.wmask_width);
+    assert_eq!(coverage.addrs_read.len(), PARAMS.num_words());
+    assert_eq!(coverage.addrs_written.len(), PARAMS.num_words());
+    assert_eq!(coverage.mux_phases_read.len(), 4);
+    assert_eq!(coverage.wmask_lanes.len(), PARAMS.wmask_width);
+    assert_eq!(
+        coverage.reads,
+        5 * PARAMS.num_words() + 2 * PARAMS.num_words()
+    );
+    assert!(coverage
+        .to_string()
+        .contains("Addresses read:             32/32 (100.0%)"));
+}
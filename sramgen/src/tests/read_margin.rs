@@ -0,0 +1,99 @@
+use vlsir::circuit::Package;
+
+use crate::config::sram::{default_netlist_formats, ControlMode, SramConfig};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::generate_plan;
+use crate::schematic::netlist::netlist_package;
+use crate::schematic::NetlistFormat;
+use crate::tech::all_external_modules;
+use crate::tests::test_work_dir;
+use crate::verification::pvt::PvtCorner;
+use crate::verification::read_margin::{
+    normal_cdf, read_column, ReadMarginReport, ReadMarginRun, COLUMN_NAME,
+};
+use crate::Result;
+
+#[test]
+fn test_read_column_netlist() -> Result<()> {
+    let name = "sramgen_read_column";
+    let plan = generate_plan(
+        ExtractionResult {},
+        &SramConfig {
+            num_words: 32,
+            data_width: 32,
+            mux_ratio: 2,
+            write_size: 32,
+            control: ControlMode::ReplicaV1,
+            netlist_formats: default_netlist_formats(),
+            tech: Default::default(),
+            corners: Vec::new(),
+            pins: Default::default(),
+            lib: Default::default(),
+        },
+    )?;
+    let sram = &plan.sram_params;
+
+    let modules = read_column(sram);
+    let column = modules.last().unwrap();
+    assert_eq!(column.name, COLUMN_NAME);
+    assert_eq!(column.instances.len(), 4);
+    // Each bitline pair has a bitcell for every row, plus two dummy rows on either side.
+    let bitcells = modules[0]
+        .instances
+        .iter()
+        .filter(|inst| inst.name.starts_with("bitcell_"))
+        .count();
+    assert_eq!(bitcells, sram.mux_ratio * (sram.rows + 4));
+
+    let format = NetlistFormat::NgSpice;
+    let pkg = Package {
+        domain: name.to_string(),
+        desc: "Sramgen generated cells".to_string(),
+        modules,
+        ext_modules: all_external_modules(format),
+    };
+    let netlist = netlist_package(&pkg, format)?;
+    assert!(netlist.contains("sramgen_sp_sense_amp"));
+    assert!(netlist.contains(&format!(".SUBCKT {COLUMN_NAME}")));
+
+    let work_dir = test_work_dir(name);
+    std::fs::create_dir_all(&work_dir)?;
+    std::fs::write(work_dir.join(format!("{name}.spice")), netlist)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_read_margin_report() -> Result<()> {
+    let corner = PvtCorner {
+        process: "tt".to_string(),
+        vdd: 1.8,
+        temp: 25.0,
+    };
+    let runs = [0.1, 0.12, 0.08, 0.1]
+        .into_iter()
+        .enumerate()
+        .map(|(i, split)| ReadMarginRun {
+            seed: i as u64,
+            split,
+            passed: i != 2,
+        })
+        .collect();
+    let report = ReadMarginReport::new(corner.clone(), 1e-9, runs)?;
+
+    assert!((report.mean_split - 0.1).abs() < 1e-12);
+    assert!((report.std_split - (0.0008f64 / 3.0).sqrt()).abs() < 1e-12);
+    assert_eq!(report.min_split, 0.08);
+    assert_eq!(report.failures, 1);
+    assert_eq!(report.failure_rate, 0.25);
+    assert!(!report.passed());
+    assert_eq!(report.histogram().iter().map(|(_, c)| c).sum::<usize>(), 4);
+
+    assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+    assert!((normal_cdf(-2.0) - 0.02275).abs() < 1e-5);
+    assert!((normal_cdf(3.0) - 0.99865).abs() < 1e-5);
+
+    assert!(ReadMarginReport::new(corner, 1e-9, Vec::new()).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use crate::verification::snm::{half_cell, Butterfly};
+use crate::{Result, LIB_PATH};
+
+/// An inverter with its switching threshold at half of a 1 V supply.
+fn inverter(x: f64) -> f64 {
+    0.5 * (1.0 - (40.0 * (x - 0.5)).tanh())
+}
+
+fn butterfly(q_strength: f64) -> Butterfly {
+    let input = (0..=500).map(|i| i as f64 / 500.0).collect::<Vec<_>>();
+    Butterfly {
+        q: input.iter().map(|&x| q_strength * inverter(x)).collect(),
+        qb: input.iter().map(|&x| inverter(x)).collect(),
+        input,
+    }
+}
+
+#[test]
+fn test_half_cell() -> Result<()> {
+    let netlist = std::fs::read_to_string(
+        PathBuf::from(LIB_PATH).join("sram_sp_cell/sky130_fd_bd_sram__sram_sp_cell.spice"),
+    )?;
+    let half = half_cell(&netlist, "sram_sp_cell", "Q", "QB", "half_qb")?;
+
+    assert!(half.starts_with(".subckt half_qb IN OUT BL BR VDD VSS WL VNB VPB\n"));
+    assert!(half.ends_with(".ends\n"));
+    // The access transistor, pull up, and pull down driving QB.
+    assert_eq!(half.lines().count(), 5);
+    assert!(half.contains("X0 OUT WL BR VNB sky130_fd_pr__special_nfet_pass"));
+    assert!(half.contains("X7 VSS IN OUT VNB sky130_fd_pr__special_nfet_latch"));
+    assert!(!half.contains("QB"));
+
+    assert!(half_cell(&netlist, "sram_sp_cell", "Q", "X", "half").is_err());
+    assert!(half_cell(&netlist, "missing", "Q", "QB", "half").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_butterfly_margins() -> Result<()> {
+    // A symmetric latch has two equal lobes, slightly smaller than half the supply.
+    let hold = butterfly(1.0);
+    let snm = hold.static_noise_margin()?;
+    assert!(snm > 0.4 && snm < 0.5, "unexpected SNM {snm}");
+    // The stored 1 survives, so the write margin is negative.
+    let write = hold.write_margin()?;
+    assert!(
+        (write + snm).abs() < 0.01,
+        "unexpected write margin {write}"
+    );
+
+    // A weak pull up on `q` leaves a single stable state with `q` low.
+    let weak = butterfly(0.3);
+    assert!(weak.static_noise_margin()? <= 0.0);
+    assert!(weak.write_margin()? > 0.0);
+    Ok(())
+}
@@ -17,6 +17,11 @@ macro_rules! generate_sram_test {
                     mux_ratio: $mux_ratio,
                     write_size: $write_size,
                     control: crate::config::sram::ControlMode::Simple,
+                    netlist_formats: crate::config::sram::default_netlist_formats(),
+                    tech: Default::default(),
+                    corners: Vec::new(),
+                    pins: Default::default(),
+                    lib: Default::default(),
                 })
             }
         }
@@ -31,6 +36,11 @@ macro_rules! generate_sram_test {
                     mux_ratio: $mux_ratio,
                     write_size: $write_size,
                     control: crate::config::sram::ControlMode::ReplicaV1,
+                    netlist_formats: crate::config::sram::default_netlist_formats(),
+                    tech: Default::default(),
+                    corners: Vec::new(),
+                    pins: Default::default(),
+                    lib: Default::default(),
                 })
             }
         }
@@ -50,6 +60,12 @@ pub(crate) fn test_sram(config: &SramConfig) -> Result<()> {
         plan: &plan,
         tasks: &HashSet::new(),
         ctx: None,
+        sim: Default::default(),
+        lef_tool: Default::default(),
+        characterizer: Default::default(),
+        drc_tool: Default::default(),
+        lvs_tool: Default::default(),
+        read_margin: Default::default(),
     })?;
 
     Ok(())
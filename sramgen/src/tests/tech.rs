@@ -0,0 +1,119 @@
+use crate::config::tech::{tech_preset_path, ModelLib, SimModels, TechConfig, TechOverrides};
+use crate::verification::drc::DrcWaivers;
+use crate::verification::SimulatorKind;
+use crate::Result;
+
+#[test]
+fn test_tech_presets() -> Result<()> {
+    for preset in ["bwrc", "open_pdks"] {
+        let tech = TechConfig::load(&TechOverrides {
+            preset: Some(preset.to_string()),
+            ..Default::default()
+        })?;
+        assert_eq!(tech.default_corner, "tt");
+        assert!(!tech.models(SimulatorKind::Ngspice).is_empty());
+        assert!(!tech.ngspice.mismatch_libs.is_empty());
+        assert!(tech.magic.drc.is_some() && tech.magic.lvs.is_some());
+        assert!(tech.klayout.drc.is_some());
+        assert!(tech.netgen.lvs.is_some());
+        assert!(tech.native.drc()?.exists());
+        assert!(tech.native.lvs()?.exists());
+        assert!(tech.input_cap.is_some());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_drc_waivers_path() -> Result<()> {
+    let tech = TechConfig::load(&TechOverrides {
+        preset: Some("bwrc".to_string()),
+        ..Default::default()
+    })?;
+    let path = tech.calibre.drc_waivers()?.unwrap();
+    assert!(path.is_absolute());
+    let waivers = DrcWaivers::load(path)?;
+    assert!(!waivers.waivers.is_empty());
+
+    let mut overrides: TechOverrides = toml::from_str(
+        r#"
+        [calibre]
+        drc = "/path/to/drc_rules"
+        drc_waivers = "waivers.toml"
+        "#,
+    )?;
+    overrides.resolve_paths(std::path::Path::new("/path/to/sram"));
+    assert_eq!(
+        overrides.calibre.unwrap().drc_waivers.as_deref(),
+        Some("/path/to/sram/waivers.toml")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rule_deck_paths() -> Result<()> {
+    let mut overrides: TechOverrides = toml::from_str(
+        r#"
+        [magic]
+        drc = "sky130A.magicrc"
+
+        [native]
+        drc = "drc_rules.toml"
+        lvs = "${PDK_ROOT}/lvs_rules.toml"
+        "#,
+    )?;
+    overrides.resolve_paths(std::path::Path::new("/path/to/sram"));
+
+    // Only the rule tables of the built-in checkers are resolved.
+    assert_eq!(
+        overrides.magic.unwrap().drc.as_deref(),
+        Some("sky130A.magicrc")
+    );
+    let native = overrides.native.unwrap();
+    assert_eq!(native.drc.as_deref(), Some("/path/to/sram/drc_rules.toml"));
+    assert_eq!(native.lvs.as_deref(), Some("${PDK_ROOT}/lvs_rules.toml"));
+
+    Ok(())
+}
+
+#[test]
+fn test_tech_overrides() -> Result<()> {
+    let tech = TechConfig::load(&TechOverrides {
+        path: Some(tech_preset_path("bwrc")),
+        default_corner: Some("ss".to_string()),
+        ngspice: Some(SimModels {
+            includes: vec!["models/{corner}.spice".to_string()],
+            libs: vec![ModelLib {
+                path: "models/all.lib".to_string(),
+                section: "{corner}_mm".to_string(),
+            }],
+            ..Default::default()
+        }),
+        input_cap: Some(2e-15),
+        ..Default::default()
+    })?;
+
+    assert_eq!(tech.input_cap, Some(2e-15));
+    let models = tech.models(SimulatorKind::Ngspice);
+    assert_eq!(
+        models.includes(&tech.default_corner)?,
+        vec!["models/ss.spice".to_string()]
+    );
+    assert_eq!(
+        models.libs(&tech.default_corner)?,
+        vec![("models/all.lib".to_string(), "ss_mm".to_string())]
+    );
+
+    let spectre = tech.models(SimulatorKind::Spectre).includes("ff")?;
+    assert!(spectre.iter().any(|path| path.ends_with("/ffcell.cor")));
+    assert!(tech.calibre.drc().is_ok());
+
+    assert!(TechConfig::load(&TechOverrides {
+        preset: Some("open_pdks".to_string()),
+        default_corner: Some("xx".to_string()),
+        ..Default::default()
+    })
+    .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use approx::assert_relative_eq;
+
+use crate::verification::timing::{crossings, settling_time, Edge};
+
+#[test]
+fn test_crossings() {
+    let time = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let values = [0.0, 1.8, 1.8, 0.0, 0.0, 0.9];
+
+    let rising = crossings(&time, &values, 0.9, Edge::Rising, 0.0, 5.0);
+    assert_eq!(rising.len(), 2);
+    assert_relative_eq!(rising[0], 0.5);
+    assert_relative_eq!(rising[1], 5.0);
+
+    let falling = crossings(&time, &values, 0.9, Edge::Falling, 0.0, 5.0);
+    assert_eq!(falling.len(), 1);
+    assert_relative_eq!(falling[0], 2.5);
+
+    let either = crossings(&time, &values, 0.45, Edge::Either, 1.0, 4.0);
+    assert_eq!(either.len(), 1);
+    assert_relative_eq!(either[0], 2.75);
+}
+
+#[test]
+fn test_settling_time() {
+    let time = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+
+    let falling = [1.8, 1.8, 0.9, 0.0, 0.0, 0.0];
+    assert_relative_eq!(
+        settling_time(&time, &falling, 1.8, 0.0, 5.0).unwrap(),
+        2.95,
+        epsilon = 1e-12
+    );
+
+    // An output that holds its value settles at the start of the window,
+    // unless it glitches.
+    let held = [1.8; 6];
+    assert_relative_eq!(settling_time(&time, &held, 1.8, 1.0, 5.0).unwrap(), 1.0);
+    let glitch = [1.8, 1.8, 1.2, 1.8, 1.8, 1.8];
+    assert_relative_eq!(
+        settling_time(&time, &glitch, 1.8, 1.0, 5.0).unwrap(),
+        2.925,
+        epsilon = 1e-12
+    );
+
+    let stuck = [1.8, 1.8, 0.9, 0.9, 0.9, 0.9];
+    assert_eq!(settling_time(&time, &stuck, 1.8, 0.0, 5.0), None);
+}
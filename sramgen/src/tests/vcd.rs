@@ -0,0 +1,56 @@
+use crate::verification::vcd::{generate_vcd, Trace, VcdSignal};
+use crate::Result;
+
+#[test]
+fn test_generate_vcd() -> Result<()> {
+    let time = [0.0, 1e-9, 2e-9, 3e-9];
+    let clk = [0.0, 1.8, 1.8, 0.0];
+    let addr0 = [1.8, 1.8, 0.9, 0.0];
+    let addr1 = [0.0, 0.0, 0.0, 1.8];
+
+    let signals = vec![
+        VcdSignal {
+            name: "clk".to_string(),
+            bits: vec![Trace {
+                time: &time,
+                values: &clk,
+            }],
+        },
+        VcdSignal {
+            name: "addr".to_string(),
+            bits: vec![
+                Trace {
+                    time: &time,
+                    values: &addr0,
+                },
+                Trace {
+                    time: &time,
+                    values: &addr1,
+                },
+            ],
+        },
+    ];
+
+    let vcd = generate_vcd(&signals, 1.8, "sram")?;
+    let expected = "\
+$version sramgen $end
+$timescale 1ps $end
+$scope module sram $end
+$var wire 1 ! clk $end
+$var wire 2 \" addr [1:0] $end
+$upscope $end
+$enddefinitions $end
+#0
+0!
+b01 \"
+#1000
+1!
+#2000
+b0x \"
+#3000
+0!
+b10 \"
+";
+    assert_eq!(vcd, expected);
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use crate::config::sram::{default_netlist_formats, ControlMode, SramConfig};
+use crate::plan::extract::ExtractionResult;
+use crate::plan::{generate_plan, SramPlan};
+use crate::tests::test_work_dir;
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::patterns::PatternParams;
+use crate::verification::vectors::{parse_test_vectors, TestVectors};
+use crate::verification::{
+    simulate_testbench, sram_tb_builder, Op, Stimulus, TestCase, VerificationTask,
+};
+use crate::Result;
+
+const PARAMS: PatternParams = PatternParams {
+    addr_width: 2,
+    data_width: 4,
+    wmask_width: 1,
+};
+
+#[test]
+fn test_test_vectors_json_roundtrip() -> Result<()> {
+    let work_dir = test_work_dir("test_test_vectors_json_roundtrip");
+    std::fs::create_dir_all(&work_dir)?;
+
+    let addr = BitSignal::from_u64(2, 2);
+    let vectors = TestVectors {
+        test_case: TestCase::builder()
+            .clk_period(10e-9)
+            .ops(vec![
+                Op::Write {
+                    addr: addr.clone(),
+                    data: BitSignal::from_u64(0b1010, 4),
+                },
+                Op::Read { addr },
+            ])
+            .build()?,
+        vdd: Some(1.62),
+        tr: None,
+        tf: None,
+        c_load: Some(10e-15),
+    };
+    vectors.validate(&PARAMS)?;
+
+    let path = work_dir.join("vectors.json");
+    vectors.save(&path)?;
+    assert_eq!(parse_test_vectors(&path)?, vectors);
+    Ok(())
+}
+
+#[test]
+fn test_test_vectors_toml() -> Result<()> {
+    let work_dir = test_work_dir("test_test_vectors_toml");
+    std::fs::create_dir_all(&work_dir)?;
+
+    let path = work_dir.join("vectors.toml");
+    std::fs::write(
+        &path,
+        r#"
+clk_period = 20e-9
+tr = 100e-12
+tf = 100e-12
+
+[[ops]]
+[ops.Write]
+addr = { bits = [true, false] }
+data = { bits = [true, true, false, false] }
+
+[[ops]]
+[ops.Read]
+addr = { bits = [true, false] }
+"#,
+    )?;
+
+    let vectors = parse_test_vectors(&path)?;
+    assert_eq!(vectors.test_case.ops.len(), 2);
+    assert_eq!(vectors.tr, Some(100e-12));
+    assert_eq!(vectors.vdd, None);
+    vectors.validate(&PARAMS)?;
+
+    assert!(vectors
+        .validate(&PatternParams {
+            addr_width: 3,
+            ..PARAMS
+        })
+        .is_err());
+    Ok(())
+}
+
+fn sram_plan() -> Result<SramPlan> {
+    generate_plan(
+        ExtractionResult {},
+        &SramConfig {
+            num_words: 16,
+            data_width: 4,
+            mux_ratio: 2,
+            write_size: 4,
+            control: ControlMode::ReplicaV1,
+            netlist_formats: default_netlist_formats(),
+            tech: Default::default(),
+            corners: Vec::new(),
+            pins: Default::default(),
+            lib: Default::default(),
+        },
+    )
+}
+
+#[test]
+fn test_test_vectors_clock_period() -> Result<()> {
+    let plan = sram_plan()?;
+    let sram = &plan.sram_params;
+
+    let addr = BitSignal::from_u64(5, sram.addr_width);
+    let vectors = TestVectors {
+        test_case: TestCase::builder()
+            .clk_period(10e-9)
+            .ops(vec![
+                Op::Write {
+                    addr: addr.clone(),
+                    data: BitSignal::from_u64(0b0110, sram.data_width),
+                },
+                Op::Read { addr },
+            ])
+            .build()?,
+        vdd: None,
+        tr: None,
+        tf: None,
+        c_load: None,
+    };
+
+    let tb = sram_tb_builder(
+        sram,
+        test_work_dir("test_test_vectors_clock_period"),
+        &sram.name,
+        VerificationTask::NgspiceSim,
+        &Stimulus::File(vectors),
+    )?
+    .build()?;
+
+    // The idle time at the end of the testbench scales with the applied clock period.
+    assert_eq!(tb.test_case.clk_period, 10e-9);
+    assert_eq!(tb.idle_time, 4.0 * 10e-9);
+    Ok(())
+}
+
+#[test]
+fn test_test_vectors_edges_too_close() -> Result<()> {
+    let plan = sram_plan()?;
+    let sram = &plan.sram_params;
+    let work_dir = test_work_dir("test_test_vectors_edges_too_close");
+    std::fs::create_dir_all(&work_dir)?;
+
+    const OPS: &str = r#"
+[[ops]]
+[ops.Write]
+addr = { bits = [true, false, false, false] }
+data = { bits = [true, true, false, false] }
+
+[[ops]]
+[ops.Read]
+addr = { bits = [true, false, false, false] }
+"#;
+    // The address of operation 1 is applied 10 ps after that of operation 0.
+    const CLOSE_SETUPS: &str = r#"
+[[setup_overrides]]
+op = 0
+port = "Addr"
+setup = 10e-12
+
+[[setup_overrides]]
+op = 1
+port = "Addr"
+setup = 10e-9
+"#;
+
+    // Each case has an edge that starts before the previous 100 ps edge finishes.
+    for (name, duty_cycle, setups) in [
+        ("setup", 0.5, CLOSE_SETUPS),
+        ("high", 0.005, ""),
+        ("low", 0.995, ""),
+    ] {
+        let path = work_dir.join(format!("{name}.toml"));
+        std::fs::write(
+            &path,
+            format!(
+                "clk_period = 10e-9\nduty_cycle = {duty_cycle}\ntr = 100e-12\ntf = 100e-12\n{setups}{OPS}"
+            ),
+        )?;
+        let tb = sram_tb_builder(
+            sram,
+            work_dir.join(name),
+            &sram.name,
+            VerificationTask::NgspiceSim,
+            &Stimulus::File(parse_test_vectors(&path)?),
+        )?
+        .build()?;
+
+        let err = simulate_testbench(&tb)
+            .err()
+            .expect("edges closer than the transition time should be rejected");
+        assert!(err.to_string().contains("must be longer than"), "{err}");
+    }
+    Ok(())
+}
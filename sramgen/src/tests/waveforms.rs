@@ -0,0 +1,117 @@
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::waveform::Waveform;
+use crate::verification::{
+    generate_waveforms, Op, PortClass, PortOrder, SetupOverride, SimulatorKind, TbParams, TestCase,
+    TestCaseBuilder,
+};
+use crate::Result;
+
+const PERIOD: f64 = 1e-9;
+const VDD: f64 = 1.8;
+
+fn tb(test_case: TestCase) -> Result<TbParams> {
+    Ok(TbParams::builder()
+        .test_case(test_case)
+        .sram_name("sram")
+        .tr(10e-12)
+        .tf(10e-12)
+        .vdd(VDD)
+        .c_load(5e-15)
+        .data_width(2)
+        .addr_width(2)
+        .wmask_width(1)
+        .ports(vec![(PortClass::Clock, PortOrder::MsbFirst)])
+        .clk_port("clk")
+        .write_enable_port("we")
+        .addr_port("addr")
+        .data_in_port("din")
+        .data_out_port("dout")
+        .pwr_port("vdd")
+        .gnd_port("vss")
+        .work_dir("build/test_waveforms")
+        .simulator(SimulatorKind::Ngspice)
+        .build()?)
+}
+
+/// The value of `w` at the last point at or before time `t` (in clock periods).
+fn value_at(w: &Waveform, t: f64) -> f64 {
+    w.values()
+        .take_while(|&(tw, _)| tw <= t * PERIOD * (1.0 + 1e-9))
+        .last()
+        .unwrap()
+        .1
+}
+
+fn test_case() -> TestCaseBuilder {
+    let mut builder = TestCase::builder();
+    builder.clk_period(PERIOD).ops(vec![
+        Op::Write {
+            addr: BitSignal::from_u64(1, 2),
+            data: BitSignal::from_u64(0b11, 2),
+        },
+        Op::Idle,
+        Op::Read {
+            addr: BitSignal::from_u64(2, 2),
+        },
+    ]);
+    builder
+}
+
+#[test]
+fn test_waveforms_idle_and_setup_overrides() -> Result<()> {
+    let tb = tb(test_case()
+        .duty_cycle(0.25)
+        .setup_overrides(vec![SetupOverride {
+            op: 2,
+            port: PortClass::Addr,
+            setup: 0.25 * PERIOD,
+        }])
+        .build()?)?;
+    tb.test_case.validate()?;
+    let waveforms = generate_waveforms(&tb);
+
+    // The clock is high for the first quarter of each period.
+    assert_eq!(value_at(&waveforms.clk, 0.25), VDD);
+    assert_eq!(value_at(&waveforms.clk, 0.5), 0.0);
+    assert_eq!(value_at(&waveforms.clk, 1.25), VDD);
+    assert_eq!(value_at(&waveforms.clk, 1.5), 0.0);
+
+    // Write enable is high for the write and low for the idle cycle.
+    assert_eq!(value_at(&waveforms.we, 1.0), VDD);
+    assert_eq!(value_at(&waveforms.we, 2.0), 0.0);
+
+    // The idle cycle holds the write address until the read address
+    // is applied a quarter period before the edge that captures it.
+    assert_eq!(value_at(&waveforms.addr[0], 2.75), VDD);
+    assert_eq!(value_at(&waveforms.addr[1], 2.75), 0.0);
+    assert_eq!(value_at(&waveforms.addr[0], 3.0), 0.0);
+    assert_eq!(value_at(&waveforms.addr[1], 3.0), VDD);
+
+    // Data inputs hold their values through the idle and read cycles.
+    assert_eq!(value_at(&waveforms.din[0], 3.0), VDD);
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_test_cases() -> Result<()> {
+    assert!(test_case().duty_cycle(1.0).build()?.validate().is_err());
+    assert!(test_case().duty_cycle(0.0).build()?.validate().is_err());
+
+    let setup = |op: usize, port: PortClass, setup: f64| -> Result<TestCase> {
+        Ok(test_case()
+            .setup_overrides(vec![SetupOverride { op, port, setup }])
+            .build()?)
+    };
+    assert!(setup(3, PortClass::Addr, 0.5 * PERIOD)?.validate().is_err());
+    assert!(setup(0, PortClass::Clock, 0.5 * PERIOD)?
+        .validate()
+        .is_err());
+    assert!(setup(0, PortClass::DataIn, 2.0 * PERIOD)?
+        .validate()
+        .is_err());
+    assert!(setup(0, PortClass::DataIn, 0.0)?.validate().is_err());
+    setup(0, PortClass::DataIn, 0.5 * PERIOD)?.validate()?;
+
+    Ok(())
+}
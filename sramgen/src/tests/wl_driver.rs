@@ -2,7 +2,7 @@ use crate::config::gate::Size;
 use crate::config::wl_driver::*;
 use crate::paths::out_bin;
 use crate::schematic::wl_driver::*;
-use crate::schematic::{generate_netlist, save_modules};
+use crate::schematic::{generate_netlist, save_modules, NetlistFormat};
 use crate::tests::test_work_dir;
 
 #[test]
@@ -28,9 +28,9 @@ fn test_netlist_wordline_driver_array() -> Result<(), Box<dyn std::error::Error>
     let work_dir = test_work_dir(name);
 
     let bin_path = out_bin(&work_dir, name);
-    save_modules(&bin_path, name, modules)?;
+    save_modules(&bin_path, name, modules, NetlistFormat::Spectre)?;
 
-    generate_netlist(&bin_path, &work_dir)?;
+    generate_netlist(&bin_path, &work_dir, NetlistFormat::Spectre)?;
 
     Ok(())
 }
@@ -39,6 +39,15 @@ impl BitSignal {
         Self { bits }
     }
 
+    /// Interprets the bits as an unsigned integer, with bit 0 as the LSB.
+    pub fn to_u64(&self) -> u64 {
+        assert!(self.width() <= 64);
+        self.bits
+            .iter()
+            .rev()
+            .fold(0, |value, &bit| (value << 1) | bit as u64)
+    }
+
     #[inline]
     pub fn ones(width: usize) -> Self {
         Self {
@@ -1,28 +1,27 @@
+use crate::config::tech::RuleDecks;
 use crate::paths::out_gds;
+use crate::verification::drc::{DrcViolation, DRC_DIR};
+use crate::verification::lvs::LVS_DIR;
 use crate::verification::{source_files, VerificationTask};
 use crate::Result;
+#[cfg(feature = "pex")]
 use anyhow::bail;
 use calibre::drc::{run_drc, DrcParams};
 use calibre::lvs::{run_lvs, LvsParams, LvsStatus};
 #[cfg(feature = "pex")]
 use calibre::pex::{run_pex, PexParams};
-use calibre::RuleCheck;
 use std::path::{Path, PathBuf};
 
-const SKY130_DRC_RULES_PATH: &str = "/tools/B/rahulkumar/sky130/priv/drc/sram_drc_rules";
-const SKY130_LVS_RULES_PATH: &str =
-    "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/LVS/Calibre/lvs_s8_opts";
-
-#[cfg(feature = "pex")]
-const SKY130_PEX_RULES_PATH: &str =
-    "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/PEX/xRC/xrcControlFile_s8";
-
-fn test_check_filter(check: &RuleCheck) -> bool {
-    check.name.starts_with("r_") && check.name != "r_1252_metblk.6"
-}
-
-pub fn run_sram_drc(work_dir: impl AsRef<Path>, name: &str) -> Result<()> {
-    let drc_work_dir = PathBuf::from(work_dir.as_ref()).join("drc");
+/// Runs Calibre DRC, returning the violated rule checks.
+///
+/// Calibre does not report the locations of violations,
+/// so they can only be waived by rule.
+pub fn run_sram_drc(
+    work_dir: impl AsRef<Path>,
+    name: &str,
+    rules: &RuleDecks,
+) -> Result<Vec<DrcViolation>> {
+    let drc_work_dir = PathBuf::from(work_dir.as_ref()).join(DRC_DIR);
 
     let layout_path = out_gds(&work_dir, name);
 
@@ -30,46 +29,41 @@ pub fn run_sram_drc(work_dir: impl AsRef<Path>, name: &str) -> Result<()> {
         cell_name: name,
         work_dir: &drc_work_dir,
         layout_path: &layout_path,
-        drc_rules_path: &PathBuf::from(SKY130_DRC_RULES_PATH),
+        drc_rules_path: &rules.drc()?,
     })?;
 
-    if data
+    Ok(data
         .rule_checks
         .into_iter()
-        .filter(test_check_filter)
-        .count()
-        > 0
-    {
-        bail!("Found DRC errors");
-    }
-
-    Ok(())
+        .map(|check| DrcViolation {
+            rule: check.name,
+            description: String::new(),
+            cell: None,
+            bbox: None,
+        })
+        .collect())
 }
 
 pub fn run_sram_lvs(
     work_dir: impl AsRef<Path>,
     name: &str,
     control_mode: crate::config::sram::ControlMode,
-) -> Result<()> {
-    let lvs_work_dir = PathBuf::from(work_dir.as_ref()).join("lvs");
+    rules: &RuleDecks,
+) -> Result<bool> {
+    let lvs_work_dir = PathBuf::from(work_dir.as_ref()).join(LVS_DIR);
 
     let layout_path = out_gds(&work_dir, name);
 
-    if run_lvs(&LvsParams {
+    Ok(run_lvs(&LvsParams {
         work_dir: &lvs_work_dir,
         layout_path: &layout_path,
         layout_cell_name: name,
         source_paths: &source_files(&work_dir, name, VerificationTask::Lvs, control_mode),
         source_cell_name: name,
-        lvs_rules_path: &PathBuf::from(SKY130_LVS_RULES_PATH),
+        lvs_rules_path: &rules.lvs()?,
     })?
     .status
-        != LvsStatus::Correct
-    {
-        bail!("LVS failed");
-    }
-
-    Ok(())
+        == LvsStatus::Correct)
 }
 
 #[cfg(feature = "pex")]
@@ -78,6 +72,7 @@ pub fn run_sram_pex(
     pex_netlist_path: impl AsRef<Path>,
     name: &str,
     control_mode: crate::config::sram::ControlMode,
+    rules: &RuleDecks,
 ) -> Result<()> {
     let pex_work_dir = PathBuf::from(work_dir.as_ref()).join("pex");
     let pex_netlist_path = pex_netlist_path.as_ref();
@@ -90,7 +85,7 @@ pub fn run_sram_pex(
         layout_cell_name: name,
         source_paths: &source_files(&work_dir, name, VerificationTask::Pex, control_mode),
         source_cell_name: name,
-        pex_rules_path: &PathBuf::from(SKY130_PEX_RULES_PATH),
+        pex_rules_path: &rules.pex()?,
         pex_netlist_path,
     })?
     .status
@@ -0,0 +1,151 @@
+//! Coverage of SRAM test cases.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::path::Path;
+
+use crate::verification::Op;
+use crate::Result;
+
+/// Tracks which parts of the SRAM a list of operations exercises.
+///
+/// The low `log2(mux_ratio)` address bits select the column mux phase;
+/// the remaining bits select the row.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Coverage {
+    pub num_words: usize,
+    pub mux_ratio: usize,
+    pub wmask_width: usize,
+    pub reads: usize,
+    pub writes: usize,
+    pub masked_writes: usize,
+    #[serde(default)]
+    pub idles: usize,
+    pub addrs_read: BTreeSet<usize>,
+    pub addrs_written: BTreeSet<usize>,
+    pub mux_phases_read: BTreeSet<usize>,
+    pub mux_phases_written: BTreeSet<usize>,
+    /// Write mask lanes enabled by at least one masked write.
+    pub wmask_lanes: BTreeSet<usize>,
+}
+
+impl Coverage {
+    pub fn new(num_words: usize, mux_ratio: usize, wmask_width: usize) -> Self {
+        Self {
+            num_words,
+            mux_ratio,
+            wmask_width,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_ops<'a>(
+        ops: impl IntoIterator<Item = &'a Op>,
+        num_words: usize,
+        mux_ratio: usize,
+        wmask_width: usize,
+    ) -> Self {
+        let mut coverage = Self::new(num_words, mux_ratio, wmask_width);
+        for op in ops {
+            coverage.record(op);
+        }
+        coverage
+    }
+
+    pub fn record(&mut self, op: &Op) {
+        match op {
+            Op::Read { addr } => {
+                let addr = addr.to_u64() as usize;
+                self.reads += 1;
+                self.addrs_read.insert(addr);
+                self.mux_phases_read.insert(addr % self.mux_ratio);
+            }
+            Op::Write { addr, .. } => {
+                let addr = addr.to_u64() as usize;
+                self.writes += 1;
+                self.addrs_written.insert(addr);
+                self.mux_phases_written.insert(addr % self.mux_ratio);
+            }
+            Op::WriteMasked { addr, mask, .. } => {
+                let addr = addr.to_u64() as usize;
+                self.masked_writes += 1;
+                self.addrs_written.insert(addr);
+                self.mux_phases_written.insert(addr % self.mux_ratio);
+                self.wmask_lanes
+                    .extend(mask.bits().enumerate().filter(|(_, b)| *b).map(|(i, _)| i));
+            }
+            Op::Idle => {
+                self.idles += 1;
+            }
+        }
+    }
+
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(
+            dir.join("coverage.json"),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        std::fs::write(dir.join("coverage.txt"), self.to_string())?;
+        Ok(())
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn line(
+            f: &mut std::fmt::Formatter<'_>,
+            name: &str,
+            hit: usize,
+            total: usize,
+        ) -> std::fmt::Result {
+            let pct = if total == 0 {
+                100.0
+            } else {
+                100.0 * hit as f64 / total as f64
+            };
+            writeln!(f, "{name:28}{hit}/{total} ({pct:.1}%)")
+        }
+
+        writeln!(
+            f,
+            "{:28}{} ({} reads, {} writes, {} masked writes, {} idle cycles)",
+            "Operations:",
+            self.reads + self.writes + self.masked_writes + self.idles,
+            self.reads,
+            self.writes,
+            self.masked_writes,
+            self.idles
+        )?;
+        line(
+            f,
+            "Addresses written:",
+            self.addrs_written.len(),
+            self.num_words,
+        )?;
+        line(f, "Addresses read:", self.addrs_read.len(), self.num_words)?;
+        line(
+            f,
+            "Column mux phases written:",
+            self.mux_phases_written.len(),
+            self.mux_ratio,
+        )?;
+        line(
+            f,
+            "Column mux phases read:",
+            self.mux_phases_read.len(),
+            self.mux_ratio,
+        )?;
+        if self.wmask_width > 1 {
+            line(
+                f,
+                "Write mask lanes:",
+                self.wmask_lanes.len(),
+                self.wmask_width,
+            )?;
+        }
+        Ok(())
+    }
+}
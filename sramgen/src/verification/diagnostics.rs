@@ -0,0 +1,314 @@
+//! Diagnostics for failed reads, based on probes of internal SRAM nets.
+
+use anyhow::anyhow;
+use psf_ascii::parser::transient::TransientData;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::bus_bit;
+use crate::verification::netlist::dut_node;
+use crate::verification::timing::{crossings, Edge, DELAY_THRESHOLD};
+use crate::verification::{Op, TbParams};
+use crate::Result;
+
+/// Fraction of VDD by which the bitlines must differ to be considered split.
+pub const SPLIT_THRESHOLD: f64 = 0.05;
+
+/// Describes the internal nets of the SRAM that can be probed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct InternalNets {
+    /// Width of the `wl` bus.
+    pub rows: usize,
+    /// Width of the `bl` and `br` buses.
+    pub cols: usize,
+    /// Width of the `write_driver_en` bus.
+    pub mux_ratio: usize,
+    /// Whether the SRAM has a replica bitline, `rbl`.
+    pub replica: bool,
+}
+
+impl InternalNets {
+    /// The scalar nets probed in addition to the `wl`, `bl`, `br`,
+    /// and `write_driver_en` buses.
+    pub fn signals(&self) -> Vec<&'static str> {
+        let mut signals = vec!["sense_amp_en", "pc_b", "wl_en"];
+        if self.replica {
+            signals.push("rbl");
+        }
+        signals
+    }
+}
+
+/// Activity during the cycle in which a write executed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteDiagnostic {
+    /// Index of the write in the test case.
+    pub op: usize,
+    /// Wordlines that rose above 50% of VDD.
+    pub wordlines: Vec<usize>,
+    /// Bits of `write_driver_en` that rose above 50% of VDD.
+    pub write_drivers: Vec<usize>,
+}
+
+/// Activity during the cycle in which a failed read executed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadDiagnostic {
+    /// Index of the read in the test case.
+    pub op: usize,
+    /// The data output bit that was read incorrectly.
+    pub bit: usize,
+    /// The row selected by the read address.
+    pub expected_row: usize,
+    /// Wordlines that rose above 50% of VDD.
+    pub wordlines: Vec<usize>,
+    /// Time at which `wl_en` rose.
+    pub t_wl_en: Option<f64>,
+    /// Time at which `rbl` fell, if the SRAM has a replica bitline.
+    pub t_rbl: Option<f64>,
+    /// Time at which `sense_amp_en` rose.
+    pub t_sense_amp_en: Option<f64>,
+    /// The bitline column of the failing bit with the largest differential.
+    pub column: usize,
+    /// Time at which the bitline differential of `column` first exceeded [`SPLIT_THRESHOLD`].
+    pub t_bitline_split: Option<f64>,
+    /// Differential `bl - br` of `column` when `sense_amp_en` rose,
+    /// or at the end of the cycle if it did not rise.
+    pub split: f64,
+    /// Activity during the last write to the read address, if any.
+    pub last_write: Option<WriteDiagnostic>,
+}
+
+struct Probes<'a> {
+    data: &'a TransientData,
+    tb: &'a TbParams,
+}
+
+impl<'a> Probes<'a> {
+    fn signal(&self, net: &str) -> Result<&'a [f64]> {
+        let name = format!("v({})", dut_node(self.tb.simulator, net));
+        self.data
+            .signal(&name)
+            .map(|s| &s[..])
+            .ok_or_else(|| anyhow!("Unable to find signal {}", name))
+    }
+
+    fn first_crossing(
+        &self,
+        net: &str,
+        edge: Edge,
+        t_start: f64,
+        t_end: f64,
+    ) -> Result<Option<f64>> {
+        Ok(crossings(
+            &self.data.time,
+            self.signal(net)?,
+            DELAY_THRESHOLD * self.tb.vdd,
+            edge,
+            t_start,
+            t_end,
+        )
+        .first()
+        .copied())
+    }
+
+    /// The bits of `bus` that rose above 50% of VDD in `[t_start, t_end]`.
+    fn active(&self, bus: &str, width: usize, t_start: f64, t_end: f64) -> Result<Vec<usize>> {
+        let mut active = Vec::new();
+        for i in 0..width {
+            let values = self.signal(&bus_bit(bus, i))?;
+            let high =
+                self.data.time.iter().zip(values).any(|(&t, &x)| {
+                    t >= t_start && t <= t_end && x > DELAY_THRESHOLD * self.tb.vdd
+                });
+            if high {
+                active.push(i);
+            }
+        }
+        Ok(active)
+    }
+
+    fn value_at(&self, net: &str, t: f64) -> Result<f64> {
+        let idx = self
+            .data
+            .idx_before_time(t)
+            .ok_or_else(|| anyhow!("Time {} was out of simulation range", t))?;
+        Ok(self.signal(net)?[idx])
+    }
+}
+
+/// Diagnoses an incorrect read of data output `bit` by the `op`-th operation of the testbench.
+///
+/// Requires the testbench to have probed the nets described by [`TbParams::internal_nets`].
+pub fn diagnose_read(
+    data: &TransientData,
+    tb: &TbParams,
+    op: usize,
+    bit: usize,
+) -> Result<ReadDiagnostic> {
+    let nets = tb
+        .internal_nets
+        .ok_or_else(|| anyhow!("Internal nets were not probed"))?;
+    let probes = Probes { data, tb };
+
+    let addr = match &tb.test_case.ops[op] {
+        Op::Read { addr } => addr,
+        _ => return Err(anyhow!("Operation {} is not a read", op)),
+    };
+    let addr_value = addr.to_u64() as usize;
+    let mux_ratio = nets.mux_ratio.max(1);
+    let expected_row = addr_value / mux_ratio;
+
+    // The `i`-th operation executes in cycle `i + 1`.
+    let period = tb.test_case.clk_period;
+    let cycle = |i: usize| ((i + 1) as f64 * period, (i + 2) as f64 * period);
+    let (t_start, t_end) = cycle(op);
+
+    let wordlines = probes.active("wl", nets.rows, t_start, t_end)?;
+    let t_wl_en = probes.first_crossing("wl_en", Edge::Rising, t_start, t_end)?;
+    let t_rbl = if nets.replica {
+        probes.first_crossing("rbl", Edge::Falling, t_start, t_end)?
+    } else {
+        None
+    };
+    let t_sense_amp_en = probes.first_crossing("sense_amp_en", Edge::Rising, t_start, t_end)?;
+    let t_sense = t_sense_amp_en.unwrap_or(t_end);
+
+    // Each data bit is multiplexed from `mux_ratio` adjacent columns.
+    // The addressed column is the one whose bitlines split the most.
+    let mut column = bit * mux_ratio;
+    let mut split = 0.0;
+    for col in bit * mux_ratio..((bit + 1) * mux_ratio).min(nets.cols) {
+        let diff = probes.value_at(&bus_bit("bl", col), t_sense)?
+            - probes.value_at(&bus_bit("br", col), t_sense)?;
+        if diff.abs() > split.abs() || col == bit * mux_ratio {
+            column = col;
+            split = diff;
+        }
+    }
+
+    let bl = probes.signal(&bus_bit("bl", column))?;
+    let br = probes.signal(&bus_bit("br", column))?;
+    let diff = bl
+        .iter()
+        .zip(br)
+        .map(|(l, r)| (l - r).abs())
+        .collect::<Vec<_>>();
+    let t_bitline_split = crossings(
+        &data.time,
+        &diff,
+        SPLIT_THRESHOLD * tb.vdd,
+        Edge::Rising,
+        t_start,
+        t_end,
+    )
+    .first()
+    .copied();
+
+    let last_write = tb.test_case.ops[..op]
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, op)| match op {
+            Op::Write { addr: a, .. } | Op::WriteMasked { addr: a, .. } => a == addr,
+            Op::Read { .. } | Op::Idle => false,
+        })
+        .map(|(i, _)| -> Result<WriteDiagnostic> {
+            let (t_start, t_end) = cycle(i);
+            Ok(WriteDiagnostic {
+                op: i,
+                wordlines: probes.active("wl", nets.rows, t_start, t_end)?,
+                write_drivers: probes.active("write_driver_en", nets.mux_ratio, t_start, t_end)?,
+            })
+        })
+        .transpose()?;
+
+    Ok(ReadDiagnostic {
+        op,
+        bit,
+        expected_row,
+        wordlines,
+        t_wl_en,
+        t_rbl,
+        t_sense_amp_en,
+        column,
+        t_bitline_split,
+        split,
+        last_write,
+    })
+}
+
+fn fmt_bits(bus: &str, bits: &[usize]) -> String {
+    if bits.is_empty() {
+        "none".to_string()
+    } else {
+        bits.iter()
+            .map(|&i| bus_bit(bus, i))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn fmt_time(t: f64) -> String {
+    format!("{:.3} ns", t * 1e9)
+}
+
+impl Display for ReadDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Diagnostics for read {} (bit {}):", self.op, self.bit)?;
+        writeln!(
+            f,
+            "  Wordlines fired: {} (expected row {})",
+            fmt_bits("wl", &self.wordlines),
+            self.expected_row
+        )?;
+        match self.t_wl_en {
+            Some(t) => writeln!(f, "  wl_en rose at {}", fmt_time(t))?,
+            None => writeln!(f, "  wl_en did not rise")?,
+        }
+        if let Some(t) = self.t_rbl {
+            writeln!(f, "  rbl fell at {}", fmt_time(t))?;
+        }
+        match self.t_bitline_split {
+            Some(t) => writeln!(
+                f,
+                "  Bitlines of column {} split by {:.0}% of VDD at {}",
+                self.column,
+                SPLIT_THRESHOLD * 100.0,
+                fmt_time(t)
+            )?,
+            None => writeln!(f, "  Bitlines of column {} did not split", self.column)?,
+        }
+        match (self.t_sense_amp_en, self.t_bitline_split) {
+            (Some(t_sae), Some(t_split)) if t_sae >= t_split => writeln!(
+                f,
+                "  sense_amp_en rose at {}, {} after the bitline split",
+                fmt_time(t_sae),
+                fmt_time(t_sae - t_split)
+            )?,
+            (Some(t_sae), Some(t_split)) => writeln!(
+                f,
+                "  sense_amp_en rose at {}, {} before the bitline split",
+                fmt_time(t_sae),
+                fmt_time(t_split - t_sae)
+            )?,
+            (Some(t_sae), None) => writeln!(f, "  sense_amp_en rose at {}", fmt_time(t_sae))?,
+            (None, _) => writeln!(f, "  sense_amp_en did not rise")?,
+        }
+        writeln!(
+            f,
+            "  Bitline differential (bl - br) when sensed: {:.1} mV",
+            self.split * 1e3
+        )?;
+        match &self.last_write {
+            Some(write) => writeln!(
+                f,
+                "  Last write to this address (operation {}): wordlines fired: {}; write drivers enabled: {}",
+                write.op,
+                fmt_bits("wl", &write.wordlines),
+                fmt_bits("write_driver_en", &write.write_drivers)
+            )?,
+            None => writeln!(f, "  No write to this address was found")?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+//! Design rule checking (DRC) of generated layouts.
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::path::Path;
+
+use crate::config::tech::{RuleDecks, TechConfig};
+use crate::layout::drc::{check_gds, DrcRules};
+use crate::paths::out_gds;
+use crate::verification::{klayout, magic};
+use crate::Result;
+
+/// The directory within the SRAM's work directory in which DRC is run.
+pub const DRC_DIR: &str = "drc";
+
+/// Maximum number of violations of each rule listed in a DRC summary.
+const MAX_LISTED_VIOLATIONS: usize = 10;
+
+/// The tool used to run DRC.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+pub enum DrcTool {
+    Calibre,
+    Magic,
+    Klayout,
+    /// The built-in design rule checker, which checks a subset of the design rules.
+    Native,
+}
+
+impl Default for DrcTool {
+    /// Calibre if support for it is enabled; Magic otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "calibre") {
+            Self::Calibre
+        } else {
+            Self::Magic
+        }
+    }
+}
+
+impl Display for DrcTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Calibre => write!(f, "calibre"),
+            Self::Magic => write!(f, "magic"),
+            Self::Klayout => write!(f, "klayout"),
+            Self::Native => write!(f, "native"),
+        }
+    }
+}
+
+/// An axis-aligned rectangle, in microns.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bbox {
+    pub x0: f64,
+    pub y0: f64,
+    pub x1: f64,
+    pub y1: f64,
+}
+
+impl Bbox {
+    /// The smallest rectangle containing all of `points`, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        points.into_iter().fold(None, |bbox, (x, y)| {
+            Some(match bbox {
+                None => Self {
+                    x0: x,
+                    y0: y,
+                    x1: x,
+                    y1: y,
+                },
+                Some(b) => Self {
+                    x0: b.x0.min(x),
+                    y0: b.y0.min(y),
+                    x1: b.x1.max(x),
+                    y1: b.y1.max(y),
+                },
+            })
+        })
+    }
+
+    /// Whether `other` lies entirely within this rectangle.
+    pub fn contains(&self, other: &Bbox) -> bool {
+        self.x0 <= other.x0 && self.y0 <= other.y0 && other.x1 <= self.x1 && other.y1 <= self.y1
+    }
+}
+
+impl Display for Bbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({:.3}, {:.3}) - ({:.3}, {:.3})",
+            self.x0, self.y0, self.x1, self.y1
+        )
+    }
+}
+
+/// A single DRC violation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrcViolation {
+    /// The name of the violated rule.
+    pub rule: String,
+    /// A description of the violated rule, if the tool provides one.
+    pub description: String,
+    /// The cell in which the violation was found, if the tool reports one.
+    ///
+    /// Violations without a cell were found in the top level cell.
+    pub cell: Option<String>,
+    /// The location of the violation within its cell, if the tool reports one.
+    pub bbox: Option<Bbox>,
+}
+
+/// Waives the violations of a rule, optionally only within a cell or region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrcWaiver {
+    /// The name of the waived rule.
+    ///
+    /// May contain the wildcards `*`, matching any sequence of characters,
+    /// and `?`, matching any single character.
+    pub rule: String,
+    /// Rules matched by `rule` that are not waived, which may also contain wildcards.
+    #[serde(default)]
+    pub except: Vec<String>,
+    /// Only waive violations in this cell.
+    pub cell: Option<String>,
+    /// Only waive violations lying entirely within this region of the cell.
+    ///
+    /// Violations whose location is unknown are not waived.
+    pub bbox: Option<Bbox>,
+    /// Why the violations are waived.
+    pub reason: Option<String>,
+}
+
+/// A DRC waiver file.
+///
+/// Waiver files are TOML files listing waivers as `[[waiver]]` tables.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrcWaivers {
+    #[serde(default, rename = "waiver")]
+    pub waivers: Vec<DrcWaiver>,
+}
+
+impl DrcWaiver {
+    /// Whether this waiver applies to `violation`, found while checking `top_cell`.
+    pub fn waives(&self, violation: &DrcViolation, top_cell: &str) -> bool {
+        if !glob_match(&self.rule, &violation.rule)
+            || self
+                .except
+                .iter()
+                .any(|pattern| glob_match(pattern, &violation.rule))
+        {
+            return false;
+        }
+        if let Some(ref cell) = self.cell {
+            if cell != violation.cell.as_deref().unwrap_or(top_cell) {
+                return false;
+            }
+        }
+        match (&self.bbox, &violation.bbox) {
+            (None, _) => true,
+            (Some(region), Some(bbox)) => region.contains(bbox),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl DrcWaivers {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading DRC waiver file {path:?}"))?;
+        let waivers = toml::from_str(&contents)
+            .with_context(|| format!("Error parsing DRC waiver file {path:?}"))?;
+        Ok(waivers)
+    }
+
+    /// Loads the waiver file of `rules`, if it has one.
+    pub fn from_rule_decks(rules: &RuleDecks) -> Result<Self> {
+        match rules.drc_waivers()? {
+            Some(path) => Self::load(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Whether any waiver applies to `violation`, found while checking `top_cell`.
+    pub fn waives(&self, violation: &DrcViolation, top_cell: &str) -> bool {
+        self.waivers.iter().any(|w| w.waives(violation, top_cell))
+    }
+}
+
+/// Whether `s` matches `pattern`, in which `*` matches any sequence of characters
+/// and `?` matches any single character.
+fn glob_match(pattern: &str, s: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let s = s.chars().collect::<Vec<_>>();
+    let (mut p, mut i) = (0, 0);
+    // The position of the last `*` in the pattern, and the position in `s` it was matched at.
+    let mut star: Option<(usize, usize)> = None;
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, i));
+            p += 1;
+        } else if let Some((sp, si)) = star {
+            // Let the last `*` match one more character.
+            star = Some((sp, si + 1));
+            p = sp + 1;
+            i = si + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrcResult {
+    pub tool: DrcTool,
+    /// The name of the checked cell.
+    pub cell: String,
+    /// Violations that were not waived.
+    pub violations: Vec<DrcViolation>,
+    /// Violations that were waived.
+    #[serde(default)]
+    pub waived: Vec<DrcViolation>,
+}
+
+impl DrcResult {
+    /// Separates the `violations` found in `cell` that are waived by `waivers`
+    /// from those that are not.
+    pub fn new(
+        tool: DrcTool,
+        cell: impl Into<String>,
+        violations: Vec<DrcViolation>,
+        waivers: &DrcWaivers,
+    ) -> Self {
+        let cell = cell.into();
+        let (waived, violations): (Vec<_>, Vec<_>) = violations
+            .into_iter()
+            .partition(|v| waivers.waives(v, &cell));
+        Self {
+            tool,
+            cell,
+            violations,
+            waived,
+        }
+    }
+
+    /// Whether every violation was waived.
+    #[inline]
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Saves the result to `dir` as `drc.json` and `drc.txt`,
+    /// and the violations that were not waived as the KLayout report database `drc.lyrdb`.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("drc.json"), serde_json::to_string_pretty(self)?)?;
+        std::fs::write(dir.join("drc.txt"), self.to_string())?;
+        klayout::write_lyrdb(self, &mut File::create(dir.join("drc.lyrdb"))?)?;
+        Ok(())
+    }
+}
+
+impl Display for DrcResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.passed() { "PASSED" } else { "FAILED" };
+        writeln!(f, "DRC of {} using {}: {}", self.cell, self.tool, status)?;
+        if !self.violations.is_empty() {
+            writeln!(f)?;
+            write_violations(f, "violations", &self.violations, &self.cell)?;
+        }
+        if !self.waived.is_empty() {
+            writeln!(f)?;
+            write_violations(f, "waived violations", &self.waived, &self.cell)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists `violations`, grouped by rule, along with their locations.
+fn write_violations(
+    f: &mut std::fmt::Formatter<'_>,
+    kind: &str,
+    violations: &[DrcViolation],
+    top_cell: &str,
+) -> std::fmt::Result {
+    let mut rules: BTreeMap<&str, Vec<&DrcViolation>> = BTreeMap::new();
+    for v in violations.iter() {
+        rules.entry(&v.rule).or_default().push(v);
+    }
+    writeln!(f, "{} {kind} of {} rules:", violations.len(), rules.len())?;
+    for (rule, violations) in rules {
+        write!(f, "\n{rule}: {} violations", violations.len())?;
+        if violations[0].description.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, " ({})", violations[0].description)?;
+        }
+        for v in violations.iter().take(MAX_LISTED_VIOLATIONS) {
+            if let Some(bbox) = v.bbox {
+                writeln!(
+                    f,
+                    "    {} in {}",
+                    bbox,
+                    v.cell.as_deref().unwrap_or(top_cell)
+                )?;
+            }
+        }
+        if violations.len() > MAX_LISTED_VIOLATIONS {
+            writeln!(
+                f,
+                "    ... and {} more",
+                violations.len() - MAX_LISTED_VIOLATIONS
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs DRC on the GDS layout of the SRAM named `name` using `tool`.
+///
+/// Violations waived by the tool's waiver file in the tech configuration are reported,
+/// but do not fail the check.
+/// The tool runs in `work_dir/drc`, where the result is saved as `drc.json`, `drc.txt`,
+/// and `drc.lyrdb`.
+/// Returns an error if any violations that were not waived are found.
+pub fn run_sram_drc(
+    work_dir: impl AsRef<Path>,
+    name: &str,
+    tool: DrcTool,
+    tech: &TechConfig,
+) -> Result<DrcResult> {
+    let work_dir = work_dir.as_ref();
+    let drc_work_dir = work_dir.join(DRC_DIR);
+    let layout_path = out_gds(work_dir, name);
+
+    // Load the waivers before running the tool, so that a bad waiver file fails fast.
+    let waivers = DrcWaivers::from_rule_decks(rule_decks(tech, tool))?;
+
+    let violations = match tool {
+        #[cfg(feature = "calibre")]
+        DrcTool::Calibre => {
+            crate::verification::calibre::run_sram_drc(work_dir, name, &tech.calibre)?
+        }
+        #[cfg(not(feature = "calibre"))]
+        DrcTool::Calibre => {
+            bail!("Calibre support is not enabled; rebuild with the `calibre` feature")
+        }
+        DrcTool::Magic => magic::run_drc(&magic::MagicDrcParams {
+            work_dir: &drc_work_dir,
+            layout_path: &layout_path,
+            cell_name: name,
+            rcfile: &tech.magic.drc()?,
+        })?,
+        DrcTool::Klayout => klayout::run_drc(&klayout::KlayoutDrcParams {
+            work_dir: &drc_work_dir,
+            layout_path: &layout_path,
+            cell_name: name,
+            drc_rules_path: &tech.klayout.drc()?,
+        })?,
+        DrcTool::Native => {
+            let rules = DrcRules::load(tech.native.drc()?)?;
+            check_gds(&layout_path, name, &rules)?.violations
+        }
+    };
+
+    let result = DrcResult::new(tool, name, violations, &waivers);
+    result.save(&drc_work_dir)?;
+
+    if !result.passed() {
+        bail!(
+            "Found {} DRC violations ({} waived); see {:?}",
+            result.violations.len(),
+            result.waived.len(),
+            drc_work_dir.join("drc.txt")
+        );
+    }
+
+    Ok(result)
+}
+
+/// The rule decks used by `tool`.
+fn rule_decks(tech: &TechConfig, tool: DrcTool) -> &RuleDecks {
+    match tool {
+        DrcTool::Calibre => &tech.calibre,
+        DrcTool::Magic => &tech.magic,
+        DrcTool::Klayout => &tech.klayout,
+        DrcTool::Native => &tech.native,
+    }
+}
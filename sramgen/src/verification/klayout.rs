@@ -0,0 +1,230 @@
+//! DRC using the open source KLayout layout viewer.
+
+use anyhow::{bail, Context};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use crate::verification::drc::{Bbox, DrcResult, DrcViolation};
+use crate::Result;
+
+pub struct KlayoutDrcParams<'a> {
+    pub work_dir: &'a Path,
+    pub layout_path: &'a Path,
+    pub cell_name: &'a str,
+    /// The KLayout DRC script, such as `sky130A_mr.drc`.
+    pub drc_rules_path: &'a Path,
+}
+
+/// Runs a KLayout DRC script in batch mode on a GDS layout, returning the violations found.
+///
+/// The script receives the layout as `$input`, the top cell as `$topcell`,
+/// and the path of the report database it should write as `$report`.
+pub fn run_drc(params: &KlayoutDrcParams) -> Result<Vec<DrcViolation>> {
+    std::fs::create_dir_all(params.work_dir)?;
+    let report_path = params.work_dir.join("klayout_drc.lyrdb");
+    let out_file = File::create(params.work_dir.join("klayout.out"))?;
+    let err_file = File::create(params.work_dir.join("klayout.err"))?;
+
+    let status = Command::new("klayout")
+        .arg("-b")
+        .arg("-r")
+        .arg(params.drc_rules_path)
+        .arg("-rd")
+        .arg(format!("input={}", params.layout_path.display()))
+        .arg("-rd")
+        .arg(format!("topcell={}", params.cell_name))
+        .arg("-rd")
+        .arg(format!("report={}", report_path.display()))
+        .stdout(out_file)
+        .stderr(err_file)
+        .current_dir(params.work_dir)
+        .status()
+        .with_context(|| "Failed to start klayout")?;
+
+    if !status.success() {
+        bail!("klayout exited unsuccessfully");
+    }
+
+    let report = std::fs::read_to_string(&report_path)
+        .with_context(|| format!("Error reading KLayout report database {report_path:?}"))?;
+    parse_lyrdb(&report)
+}
+
+/// Parses the violations in a KLayout report database (`.lyrdb`).
+///
+/// The location of each violation is the bounding box of the shapes it lists,
+/// in the coordinates of the cell in which it was found.
+pub fn parse_lyrdb(xml: &str) -> Result<Vec<DrcViolation>> {
+    // Categories contain (usually empty) lists of subcategories,
+    // so look for them directly rather than within the top level category list.
+    // Items refer to their category by name using elements also named `category`.
+    let header = xml.split("<items>").next().unwrap_or_default();
+    let categories = elements(header, "category")
+        .into_iter()
+        .map(|c| {
+            let name = text(c, "name").unwrap_or_default();
+            let description = text(c, "description").unwrap_or_default();
+            (name, description)
+        })
+        .collect::<Vec<_>>();
+
+    let items = elements(xml, "items")
+        .first()
+        .map(|items| elements(items, "item"))
+        .unwrap_or_default();
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let rule = text(item, "category")
+                .unwrap_or_default()
+                .trim_matches('\'')
+                .to_string();
+            let description = categories
+                .iter()
+                .find(|(name, _)| *name == rule)
+                .map(|(_, description)| description.clone())
+                .unwrap_or_default();
+            let bbox = Bbox::from_points(
+                elements(item, "value")
+                    .into_iter()
+                    .flat_map(|value| shape_points(&unescape(value))),
+            );
+            DrcViolation {
+                rule,
+                description,
+                cell: text(item, "cell"),
+                bbox,
+            }
+        })
+        .collect())
+}
+
+/// Writes the violations in `result` that were not waived as a KLayout report database,
+/// which can be browsed alongside the layout in KLayout's marker browser.
+pub fn write_lyrdb(result: &DrcResult, w: &mut impl Write) -> Result<()> {
+    let mut rules: BTreeMap<&str, &str> = BTreeMap::new();
+    let mut cells = vec![result.cell.as_str()];
+    for v in result.violations.iter() {
+        rules.entry(&v.rule).or_insert(&v.description);
+        if let Some(ref cell) = v.cell {
+            if !cells.contains(&cell.as_str()) {
+                cells.push(cell);
+            }
+        }
+    }
+
+    writeln!(w, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+    writeln!(w, "<report-database>")?;
+    writeln!(
+        w,
+        " <description>DRC of {} using {}</description>",
+        escape(&result.cell),
+        result.tool
+    )?;
+    writeln!(w, " <generator>sramgen</generator>")?;
+    writeln!(w, " <top-cell>{}</top-cell>", escape(&result.cell))?;
+    writeln!(w, " <categories>")?;
+    for (rule, description) in rules {
+        writeln!(w, "  <category>")?;
+        writeln!(w, "   <name>{}</name>", escape(rule))?;
+        writeln!(w, "   <description>{}</description>", escape(description))?;
+        writeln!(w, "  </category>")?;
+    }
+    writeln!(w, " </categories>")?;
+    writeln!(w, " <cells>")?;
+    for cell in cells {
+        writeln!(w, "  <cell>")?;
+        writeln!(w, "   <name>{}</name>", escape(cell))?;
+        writeln!(w, "  </cell>")?;
+    }
+    writeln!(w, " </cells>")?;
+    writeln!(w, " <items>")?;
+    for v in result.violations.iter() {
+        writeln!(w, "  <item>")?;
+        writeln!(w, "   <category>'{}'</category>", escape(&v.rule))?;
+        writeln!(
+            w,
+            "   <cell>{}</cell>",
+            escape(v.cell.as_deref().unwrap_or(&result.cell))
+        )?;
+        writeln!(w, "   <visited>false</visited>")?;
+        writeln!(w, "   <multiplicity>1</multiplicity>")?;
+        writeln!(w, "   <values>")?;
+        if let Some(b) = v.bbox {
+            writeln!(
+                w,
+                "    <value>box: ({},{};{},{})</value>",
+                b.x0, b.y0, b.x1, b.y1
+            )?;
+        }
+        writeln!(w, "   </values>")?;
+        writeln!(w, "  </item>")?;
+    }
+    writeln!(w, " </items>")?;
+    writeln!(w, "</report-database>")?;
+    Ok(())
+}
+
+/// The contents of every top level `<tag>` element in `xml`.
+///
+/// Elements of the same name nested within each other are not supported.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let body = &rest[start + open.len()..];
+        match body.find(&close) {
+            Some(end) => {
+                out.push(&body[..end]);
+                rest = &body[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    out
+}
+
+/// The unescaped text of the first `<tag>` element in `xml`.
+fn text(xml: &str, tag: &str) -> Option<String> {
+    elements(xml, tag).first().map(|s| unescape(s.trim()))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The points of a shape in a report database value,
+/// such as `polygon: (0,0;0,1;1,1;1,0)` or `edge-pair: (0,0;0,1)|(1,0;1,1)`.
+///
+/// Values that are not shapes, such as `float: 1.5`, have no points.
+fn shape_points(value: &str) -> Vec<(f64, f64)> {
+    let shape = match value.split_once(':') {
+        Some((_, shape)) => shape,
+        None => value,
+    };
+    shape
+        .split(|c| matches!(c, '(' | ')' | ';' | '|' | '/'))
+        .filter_map(|point| {
+            let (x, y) = point.trim().split_once(',')?;
+            Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+        })
+        .collect()
+}
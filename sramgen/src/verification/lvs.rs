@@ -0,0 +1,166 @@
+//! Layout versus schematic (LVS) checking of generated layouts.
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use vlsir::circuit::Module;
+
+use crate::config::sram::ControlMode;
+use crate::config::tech::TechConfig;
+use crate::layout::lvs::{check_gds, LvsRules};
+use crate::paths::out_gds;
+use crate::verification::{magic, netgen, source_files, VerificationTask};
+use crate::Result;
+
+/// The directory within the SRAM's work directory in which LVS is run.
+pub const LVS_DIR: &str = "lvs";
+
+/// The tool used to run LVS.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+pub enum LvsTool {
+    Calibre,
+    /// Extract the layout using Magic, then compare it to the schematic using Netgen.
+    Netgen,
+    /// The built-in checker, which compares the connections of bitcells, sense amplifiers,
+    /// and other black box devices, but not those of transistors.
+    Native,
+}
+
+impl Default for LvsTool {
+    /// Calibre if support for it is enabled; Netgen otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "calibre") {
+            Self::Calibre
+        } else {
+            Self::Netgen
+        }
+    }
+}
+
+impl Display for LvsTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Calibre => write!(f, "calibre"),
+            Self::Netgen => write!(f, "netgen"),
+            Self::Native => write!(f, "native"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LvsResult {
+    pub tool: LvsTool,
+    /// The name of the checked cell.
+    pub cell: String,
+    /// Whether the layout matches the schematic.
+    pub matched: bool,
+    /// The report written by the tool, if any.
+    pub report_path: Option<PathBuf>,
+}
+
+impl LvsResult {
+    #[inline]
+    pub fn passed(&self) -> bool {
+        self.matched
+    }
+
+    /// Saves the result to `dir` as `lvs.json`.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("lvs.json"), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl Display for LvsResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.passed() { "PASSED" } else { "FAILED" };
+        write!(f, "LVS of {} using {}: {}", self.cell, self.tool, status)?;
+        if let Some(ref path) = self.report_path {
+            write!(f, " (see {:?})", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs LVS on the GDS layout of the SRAM named `name` using `tool`,
+/// comparing it against the CDL netlist of the SRAM.
+///
+/// The native checker compares the layout against the schematic `modules` directly.
+/// The tool runs in `work_dir/lvs`, where the result is saved as `lvs.json`.
+/// Returns an error if the layout does not match the schematic.
+pub fn run_sram_lvs(
+    work_dir: impl AsRef<Path>,
+    name: &str,
+    control_mode: ControlMode,
+    modules: &[Module],
+    tool: LvsTool,
+    tech: &TechConfig,
+) -> Result<LvsResult> {
+    let work_dir = work_dir.as_ref();
+    let lvs_work_dir = work_dir.join(LVS_DIR);
+    let layout_path = out_gds(work_dir, name);
+
+    let (matched, report_path) = match tool {
+        #[cfg(feature = "calibre")]
+        LvsTool::Calibre => (
+            crate::verification::calibre::run_sram_lvs(
+                work_dir,
+                name,
+                control_mode,
+                &tech.calibre,
+            )?,
+            None,
+        ),
+        #[cfg(not(feature = "calibre"))]
+        LvsTool::Calibre => {
+            bail!("Calibre support is not enabled; rebuild with the `calibre` feature")
+        }
+        LvsTool::Netgen => {
+            let layout_netlist_path = magic::extract_spice(&magic::MagicExtractParams {
+                work_dir: &lvs_work_dir,
+                layout_path: &layout_path,
+                cell_name: name,
+                rcfile: &tech.magic.lvs()?,
+            })?;
+            let report_path = lvs_work_dir.join("netgen.rpt");
+            let matched = netgen::run_lvs(&netgen::NetgenLvsParams {
+                work_dir: &lvs_work_dir,
+                layout_netlist_path: &layout_netlist_path,
+                source_paths: &source_files(work_dir, name, VerificationTask::Lvs, control_mode),
+                cell_name: name,
+                setup_path: &tech.netgen.lvs()?,
+                report_path: &report_path,
+            })?;
+            (matched, Some(report_path))
+        }
+        LvsTool::Native => {
+            let rules = LvsRules::load(tech.native.lvs()?)?;
+            let report = check_gds(&layout_path, modules, name, &rules)?;
+            std::fs::create_dir_all(&lvs_work_dir)?;
+            let report_path = lvs_work_dir.join("native_lvs.rpt");
+            std::fs::write(&report_path, report.to_string())?;
+            std::fs::write(
+                lvs_work_dir.join("native_lvs.json"),
+                serde_json::to_string_pretty(&report)?,
+            )?;
+            (report.passed(), Some(report_path))
+        }
+    };
+
+    let result = LvsResult {
+        tool,
+        cell: name.to_string(),
+        matched,
+        report_path,
+    };
+    result.save(&lvs_work_dir)?;
+
+    if !result.passed() {
+        bail!("{}", result);
+    }
+
+    Ok(result)
+}
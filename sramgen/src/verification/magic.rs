@@ -0,0 +1,177 @@
+//! DRC and netlist extraction using the open source Magic layout tool.
+
+use anyhow::{anyhow, bail, Context};
+use serde::Serialize;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tera::Context as TeraContext;
+
+use crate::verification::drc::{Bbox, DrcViolation};
+use crate::{Result, TEMPLATES};
+
+pub struct MagicDrcParams<'a> {
+    pub work_dir: &'a Path,
+    pub layout_path: &'a Path,
+    pub cell_name: &'a str,
+    /// The Magic startup file that loads the technology, such as `sky130A.magicrc`.
+    pub rcfile: &'a Path,
+}
+
+pub struct MagicExtractParams<'a> {
+    pub work_dir: &'a Path,
+    pub layout_path: &'a Path,
+    pub cell_name: &'a str,
+    /// The Magic startup file that loads the technology, such as `sky130A.magicrc`.
+    pub rcfile: &'a Path,
+}
+
+/// Values interpolated into the DRC script, each escaped with [`tcl_escape`].
+#[derive(Debug, Clone, Serialize)]
+struct DrcScriptContext {
+    layout_path: String,
+    cell_name: String,
+    report_path: String,
+}
+
+/// Values interpolated into the extraction script, each escaped with [`tcl_escape`].
+#[derive(Debug, Clone, Serialize)]
+struct ExtractScriptContext {
+    layout_path: String,
+    cell_name: String,
+    extract_dir: String,
+    netlist_path: String,
+}
+
+/// Runs Magic DRC on a GDS layout, returning the violations found.
+pub fn run_drc(params: &MagicDrcParams) -> Result<Vec<DrcViolation>> {
+    std::fs::create_dir_all(params.work_dir)?;
+    let report_path = params.work_dir.join("magic_drc.rpt");
+    let script_path = params.work_dir.join("magic_drc.tcl");
+
+    let ctx = TeraContext::from_serialize(DrcScriptContext {
+        layout_path: tcl_escape_path(params.layout_path),
+        cell_name: tcl_escape(params.cell_name),
+        report_path: tcl_escape_path(&report_path),
+    })?;
+    TEMPLATES.render_to("magic_drc.tcl", &ctx, File::create(&script_path)?)?;
+
+    run_magic(params.work_dir, params.rcfile, &script_path)?;
+
+    let report = std::fs::read_to_string(&report_path)
+        .with_context(|| format!("Error reading Magic DRC report {report_path:?}"))?;
+    parse_drc_report(&report)
+}
+
+/// Extracts a SPICE netlist from a GDS layout for LVS, returning the path to the netlist.
+pub fn extract_spice(params: &MagicExtractParams) -> Result<PathBuf> {
+    let extract_dir = params.work_dir.join("ext");
+    std::fs::create_dir_all(&extract_dir)?;
+    let netlist_path = params
+        .work_dir
+        .join(format!("{}.ext.spice", params.cell_name));
+    let script_path = params.work_dir.join("magic_extract.tcl");
+
+    let ctx = TeraContext::from_serialize(ExtractScriptContext {
+        layout_path: tcl_escape_path(params.layout_path),
+        cell_name: tcl_escape(params.cell_name),
+        extract_dir: tcl_escape_path(&extract_dir),
+        netlist_path: tcl_escape_path(&netlist_path),
+    })?;
+    TEMPLATES.render_to("magic_extract.tcl", &ctx, File::create(&script_path)?)?;
+
+    run_magic(params.work_dir, params.rcfile, &script_path)?;
+
+    if !netlist_path.exists() {
+        bail!("Magic did not produce an extracted netlist at {netlist_path:?}");
+    }
+    Ok(netlist_path)
+}
+
+/// Escapes `word` so that Tcl reads it as a single word, without substitutions.
+///
+/// Spaces and characters with special meaning to Tcl are preceded by a backslash.
+pub fn tcl_escape(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    for c in word.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ' ' | '\\' | '$' | '[' | ']' | '{' | '}' | '"' | ';' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn tcl_escape_path(path: &Path) -> String {
+    tcl_escape(&path.to_string_lossy())
+}
+
+fn run_magic(work_dir: &Path, rcfile: &Path, script_path: &Path) -> Result<()> {
+    let out_file = File::create(work_dir.join("magic.out"))?;
+    let err_file = File::create(work_dir.join("magic.err"))?;
+
+    let status = Command::new("magic")
+        .arg("-dnull")
+        .arg("-noconsole")
+        .arg("-rcfile")
+        .arg(rcfile)
+        .arg(script_path)
+        .stdout(out_file)
+        .stderr(err_file)
+        .current_dir(work_dir)
+        .status()
+        .with_context(|| "Failed to start magic")?;
+
+    if !status.success() {
+        bail!("magic exited unsuccessfully");
+    }
+    Ok(())
+}
+
+/// Parses the DRC report written by the `magic_drc.tcl` template.
+///
+/// Each line lists one violation as tab-separated `llx lly urx ury rule` values.
+/// Magic describes rules in words, usually followed by the rule name in parentheses,
+/// as in `Metal1 spacing < 0.14um (met1.2)`.
+pub fn parse_drc_report(report: &str) -> Result<Vec<DrcViolation>> {
+    report
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            let mut coord = || -> Result<f64> {
+                Ok(fields
+                    .next()
+                    .ok_or_else(|| anyhow!("Malformed Magic DRC report line: {}", line))?
+                    .trim()
+                    .parse()?)
+            };
+            let bbox = Bbox {
+                x0: coord()?,
+                y0: coord()?,
+                x1: coord()?,
+                y1: coord()?,
+            };
+            let description = fields
+                .next()
+                .ok_or_else(|| anyhow!("Malformed Magic DRC report line: {}", line))?
+                .trim();
+            let rule = description
+                .rsplit_once('(')
+                .and_then(|(_, rest)| rest.strip_suffix(')'))
+                .unwrap_or(description);
+            Ok(DrcViolation {
+                rule: rule.to_string(),
+                description: description.to_string(),
+                cell: None,
+                bbox: Some(bbox),
+            })
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, bail};
@@ -8,21 +9,53 @@ use psf_ascii::parser::transient::TransientData;
 use serde::{Deserialize, Serialize};
 use waveform::Waveform;
 
-use crate::config::sram::ControlMode;
+use crate::config::sram::{ControlMode, SramParams};
+use crate::config::tech::TechConfig;
+use crate::paths::out_spice;
+use crate::schematic::NetlistFormat;
 use crate::verification::utils::push_bus;
 use crate::{Result, LIB_PATH};
 
+use self::coverage::Coverage;
+use self::diagnostics::{diagnose_read, InternalNets};
 use self::netlist::{generate_netlist, write_netlist, TbNetlistParams};
-use self::spectre::{run_spectre, SpectreParams};
-use self::utils::to_bit;
+use self::ngspice::Ngspice;
+use self::patterns::{Pattern, PatternParams};
+use self::power::{measure_power, supply_current_signal};
+use self::pvt::{run_pvt_sweep, PvtCorner, NOMINAL_TEMP, NOMINAL_VDD};
+use self::random::RandomParams;
+use self::spectre::Spectre;
+use self::timing::measure_timing;
+use self::utils::to_logic;
+use self::vcd::{save_sim_vcd, save_stimulus_vcd, SIM_VCD_FILE, STIMULUS_VCD_FILE};
+use self::vectors::{TestVectors, TEST_VECTORS_FILE};
+use self::xyce::Xyce;
 
 pub mod bit_signal;
 #[cfg(feature = "calibre")]
 pub mod calibre;
+pub mod coverage;
+pub mod diagnostics;
+pub mod drc;
+pub mod klayout;
+pub mod lvs;
+pub mod magic;
+pub mod netgen;
 pub mod netlist;
+pub mod ngspice;
+pub mod patterns;
+pub mod power;
+pub mod pvt;
+pub mod random;
+pub mod read_margin;
+pub mod snm;
 pub mod spectre;
+pub mod timing;
 pub mod utils;
+pub mod vcd;
+pub mod vectors;
 pub mod waveform;
+pub mod xyce;
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Op {
@@ -38,6 +71,11 @@ pub enum Op {
         data: BitSignal,
         mask: BitSignal,
     },
+    /// A cycle in which the SRAM is not accessed.
+    ///
+    /// Write enable is held low and all other inputs hold their previous values.
+    /// The SRAM has no chip select, so the data output is not checked.
+    Idle,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -58,11 +96,85 @@ pub enum PortClass {
     WriteEnable,
 }
 
-#[derive(Debug, Clone, Builder, Serialize, Deserialize)]
+/// The operations used to exercise the SRAM during functional verification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Stimulus {
+    Pattern(Pattern),
+    Random(RandomParams),
+    /// Test vectors loaded from a file.
+    ///
+    /// Conditions specified by the test vectors override the testbench defaults.
+    File(TestVectors),
+}
+
+impl Default for Stimulus {
+    fn default() -> Self {
+        Self::Pattern(Pattern::default())
+    }
+}
+
+impl Stimulus {
+    pub fn ops(&self, params: &PatternParams) -> Result<Vec<Op>> {
+        match self {
+            Self::Pattern(pattern) => Ok(patterns::generate(*pattern, params)),
+            Self::Random(random) => random::generate(random, params),
+            Self::File(vectors) => {
+                vectors.validate(params)?;
+                Ok(vectors.test_case.ops.clone())
+            }
+        }
+    }
+}
+
+/// Options for the functional verification of an SRAM.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimOptions {
+    /// Simulator used to run testbenches.
+    pub simulator: SimulatorKind,
+    /// Operations applied to the SRAM.
+    pub stimulus: Stimulus,
+    /// Whether to probe internal nets of the SRAM to diagnose failed reads.
+    pub probe_internal: bool,
+    /// The maximum number of operations to simulate.
+    ///
+    /// Test patterns grow with the number of words, so stimuli with more operations
+    /// are rejected before simulating rather than running for hours.
+    pub max_ops: Option<usize>,
+}
+
+/// Overrides the setup time of one input for a single operation.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetupOverride {
+    /// Index of the operation in the test case.
+    pub op: usize,
+    /// The input whose setup time is overridden.
+    ///
+    /// Must be one of [`PortClass::Addr`], [`PortClass::DataIn`],
+    /// [`PortClass::WriteEnable`], or [`PortClass::WriteMask`].
+    pub port: PortClass,
+    /// Time from the input's transition to the clock edge that captures it.
+    pub setup: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Builder, Serialize, Deserialize)]
 pub struct TestCase {
     pub clk_period: f64,
+    /// Fraction of each clock period for which the clock is high.
+    #[builder(default = "0.5")]
+    #[serde(default = "default_duty_cycle")]
+    pub duty_cycle: f64,
     #[builder(default, setter(into))]
     pub ops: Vec<Op>,
+    /// Per-operation setup times.
+    ///
+    /// Take precedence over [`TbParams::setup_times`].
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub setup_overrides: Vec<SetupOverride>,
+}
+
+fn default_duty_cycle() -> f64 {
+    0.5
 }
 
 impl TestCase {
@@ -70,6 +182,50 @@ impl TestCase {
     pub fn builder() -> TestCaseBuilder {
         TestCaseBuilder::default()
     }
+
+    /// The setup time of `port` for the `op`-th operation, if overridden.
+    pub fn setup_time(&self, op: usize, port: PortClass) -> Option<f64> {
+        self.setup_overrides
+            .iter()
+            .rev()
+            .find(|o| o.op == op && o.port == port)
+            .map(|o| o.setup)
+    }
+
+    /// Checks that the clock duty cycle and setup time overrides are valid.
+    pub fn validate(&self) -> Result<()> {
+        if self.duty_cycle <= 0.0 || self.duty_cycle >= 1.0 {
+            bail!(
+                "Clock duty cycle must be strictly between 0 and 1; got {}",
+                self.duty_cycle
+            );
+        }
+        for o in self.setup_overrides.iter() {
+            if o.op >= self.ops.len() {
+                bail!(
+                    "Setup time override for operation {} is out of range; the test case has {} operations",
+                    o.op,
+                    self.ops.len()
+                );
+            }
+            if !matches!(
+                o.port,
+                PortClass::Addr | PortClass::DataIn | PortClass::WriteEnable | PortClass::WriteMask
+            ) {
+                bail!("Cannot override the setup time of {:?}", o.port);
+            }
+            if o.setup <= 0.0 || o.setup > self.clk_period {
+                bail!(
+                    "Setup time {} for {:?} of operation {} must be positive and at most the clock period ({})",
+                    o.setup,
+                    o.port,
+                    o.op,
+                    self.clk_period
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
@@ -85,8 +241,26 @@ pub struct TbParams {
     pub tf: f64,
     /// Supply voltage.
     pub vdd: f64,
+    /// Temperature, in degrees Celsius.
+    #[builder(default = "NOMINAL_TEMP")]
+    pub temp: f64,
     /// Capacitance on output pins.
     pub c_load: f64,
+    /// Time from each input's transitions to the clock edge that captures them.
+    ///
+    /// Inputs that are not listed transition a full clock period before the
+    /// capturing clock edge, at the same time as the preceding clock edge.
+    /// Setup times must be positive and less than twice the clock period.
+    /// Setup times longer than the clock period apply each operation's inputs
+    /// before the clock edge that captures the previous operation,
+    /// which tests negative hold times.
+    #[builder(default, setter(into))]
+    pub setup_times: HashMap<PortClass, f64>,
+    /// Time for which all inputs are held static after the last operation.
+    ///
+    /// Used to measure leakage power.
+    #[builder(default)]
+    pub idle_time: f64,
 
     /// Number of data bits.
     pub data_width: usize,
@@ -125,6 +299,17 @@ pub struct TbParams {
     /// Name of the write mask bus.
     #[builder(default, setter(strip_option, into))]
     pub wmask_port: Option<String>,
+    /// Width of the SRAM's internal `bl` and `br` buses.
+    ///
+    /// If specified, the bitlines are probed to measure write completion time.
+    #[builder(default, setter(strip_option))]
+    pub bitline_width: Option<usize>,
+    /// Internal nets of the SRAM to probe.
+    ///
+    /// If specified, failed reads are reported with diagnostics
+    /// derived from the internal nets.
+    #[builder(default, setter(strip_option))]
+    pub internal_nets: Option<InternalNets>,
 
     /// Working directory for the simulator and generated files.
     #[builder(setter(into))]
@@ -138,6 +323,16 @@ pub struct TbParams {
     /// specified in [`TbParams::source_paths`].
     #[builder(default, setter(into))]
     pub includes: Vec<String>,
+    /// Model libraries to load, as `(path, section)` pairs.
+    ///
+    /// Each entry is emitted as a `.lib path section` card.
+    #[builder(default, setter(into))]
+    pub libs: Vec<(String, String)>,
+    /// The simulator used to run the testbench.
+    ///
+    /// Also determines the dialect of the generated testbench netlist.
+    #[builder(default)]
+    pub simulator: SimulatorKind,
 }
 
 impl TbParams {
@@ -169,6 +364,65 @@ impl TbParams {
     pub fn builder() -> TbParamsBuilder {
         TbParamsBuilder::default()
     }
+
+    /// The time at which the `i`-th operation's value of `port_class` is applied.
+    ///
+    /// The value of the previous operation is held until then.
+    pub fn apply_time(&self, i: usize, port_class: PortClass) -> f64 {
+        let period = self.test_case.clk_period;
+        let setup = self
+            .test_case
+            .setup_time(i, port_class)
+            .unwrap_or_else(|| self.setup_times.get(&port_class).copied().unwrap_or(period));
+        (i + 1) as f64 * period - setup
+    }
+
+    /// Checks that every clock and input edge finishes before the next edge
+    /// of the same signal begins.
+    pub fn check_edge_timing(&self) -> Result<()> {
+        let period = self.test_case.clk_period;
+        let t_high = self.test_case.duty_cycle * period;
+        if t_high <= self.tr {
+            bail!(
+                "Clock high time {} must be longer than the rise time ({})",
+                t_high,
+                self.tr
+            );
+        }
+        if period - t_high <= self.tf {
+            bail!(
+                "Clock low time {} must be longer than the fall time ({})",
+                period - t_high,
+                self.tf
+            );
+        }
+
+        let t_edge = self.tr.max(self.tf);
+        for port_class in [
+            PortClass::Addr,
+            PortClass::DataIn,
+            PortClass::WriteEnable,
+            PortClass::WriteMask,
+        ] {
+            for i in 0..self.test_case.ops.len() {
+                // Inputs applied before the simulation starts are applied at time zero.
+                let t_start = self.apply_time(i, port_class).max(0.0);
+                let t_hold = self.apply_time(i + 1, port_class) - t_start;
+                if t_hold <= t_edge {
+                    bail!(
+                        "{:?} of operation {} is held for {}, which must be longer than the input transition time ({}); check the setup times of operations {} and {}",
+                        port_class,
+                        i,
+                        t_hold,
+                        t_edge,
+                        i,
+                        i + 1
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl PortClass {
@@ -189,46 +443,331 @@ impl PortClass {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TbWaveforms {
     /// One [`Waveform`] per address bit.
-    addr: Vec<Waveform>,
+    pub(crate) addr: Vec<Waveform>,
 
     /// One [`Waveform`] per data bit.
-    din: Vec<Waveform>,
+    pub(crate) din: Vec<Waveform>,
 
     /// Clock.
-    clk: Waveform,
+    pub(crate) clk: Waveform,
 
     /// Write enable.
-    we: Waveform,
+    pub(crate) we: Waveform,
 
     /// One [`Waveform`] per write mask bit.
     ///
     /// Empty if no write mask is enabled.
-    wmask: Vec<Waveform>,
+    pub(crate) wmask: Vec<Waveform>,
 }
 
-pub fn run_testbench(params: &TbParams) -> Result<()> {
-    let waveforms = generate_waveforms(params);
-    let netlist = generate_netlist(TbNetlistParams {
-        tb: params,
-        waveforms: &waveforms,
-    })?;
+/// A circuit simulator capable of running SRAM testbenches.
+pub trait Simulator {
+    /// Which simulator this is.
+    fn kind(&self) -> SimulatorKind;
+
+    /// Writes the testbench netlist to `path`.
+    fn write_netlist(&self, path: &Path, params: TbNetlistParams) -> Result<()> {
+        let netlist = generate_netlist(self.kind(), params)?;
+        write_netlist(path, &netlist)
+    }
 
+    /// Runs the testbench netlist at `netlist_path`, returning the simulated waveforms.
+    fn run(&self, work_dir: &Path, netlist_path: &Path) -> Result<TransientData>;
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+pub enum SimulatorKind {
+    Spectre,
+    Ngspice,
+    Xyce,
+}
+
+impl SimulatorKind {
+    pub fn simulator(&self) -> Box<dyn Simulator> {
+        match *self {
+            Self::Spectre => Box::new(Spectre),
+            Self::Ngspice => Box::new(Ngspice),
+            Self::Xyce => Box::new(Xyce),
+        }
+    }
+
+    /// The kind of SRAM netlist this simulator should be given.
+    pub fn verification_task(&self) -> VerificationTask {
+        match *self {
+            Self::Spectre => VerificationTask::SpectreSim,
+            Self::Ngspice | Self::Xyce => VerificationTask::NgspiceSim,
+        }
+    }
+}
+
+impl Default for SimulatorKind {
+    /// Spectre if support for it is enabled; ngspice otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "spectre") {
+            Self::Spectre
+        } else {
+            Self::Ngspice
+        }
+    }
+}
+
+impl Display for SimulatorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Spectre => write!(f, "spectre"),
+            Self::Ngspice => write!(f, "ngspice"),
+            Self::Xyce => write!(f, "xyce"),
+        }
+    }
+}
+
+/// Simulates the testbench described by `params` without checking the results.
+pub fn simulate_testbench(params: &TbParams) -> Result<TransientData> {
+    let period = params.test_case.clk_period;
+    for (port_class, setup) in params.setup_times.iter() {
+        if *setup <= 0.0 || *setup >= 2.0 * period {
+            bail!(
+                "Setup time {} for {:?} must be positive and less than twice the clock period ({})",
+                setup,
+                port_class,
+                period
+            );
+        }
+    }
+    params.test_case.validate()?;
+    params.check_edge_timing()?;
+
+    // Save the test vectors so that the simulation can be reproduced.
+    std::fs::create_dir_all(&params.work_dir)?;
+    TestVectors::from_tb(params).save(params.work_dir.join(TEST_VECTORS_FILE))?;
+
+    let simulator = params.simulator.simulator();
+
+    let waveforms = generate_waveforms(params);
+    save_stimulus_vcd(&waveforms, params, params.work_dir.join(STIMULUS_VCD_FILE))?;
     let netlist_path = params
         .work_dir
         .join(format!("test_{}_sim.sp", params.sram_name));
-    write_netlist(&netlist_path, &netlist)?;
+    simulator.write_netlist(
+        &netlist_path,
+        TbNetlistParams {
+            tb: params,
+            waveforms: &waveforms,
+        },
+    )?;
 
-    let data = run_spectre(&SpectreParams {
-        work_dir: params.work_dir.clone(),
-        spice_path: netlist_path,
-    })?;
+    simulator.run(&params.work_dir, &netlist_path)
+}
+
+pub fn run_testbench(params: &TbParams) -> Result<()> {
+    let data = simulate_testbench(params)?;
+    save_sim_vcd(&data, params, params.work_dir.join(SIM_VCD_FILE))?;
 
+    // Check the results first, so that a measurement that fails on a broken design
+    // does not hide the functional failure.
     verify_simulation(&data, params)?;
 
+    let timing = measure_timing(&data, params)?;
+    timing.save(params.work_dir.join("timing.json"))?;
+
+    // Not every simulator saves the supply current.
+    if data.signal(&supply_current_signal()).is_some() {
+        let power = measure_power(&data, params)?;
+        power.save(params.work_dir.join("power.json"))?;
+    } else {
+        log::warn!(
+            "{} did not save the supply current {}; skipping power measurement",
+            params.simulator,
+            supply_current_signal()
+        );
+    }
+
+    Ok(())
+}
+
+/// The addresses, column mux phases, and write mask lanes of the SRAM
+/// exercised by `stimulus`.
+pub fn sram_coverage(params: &SramParams, stimulus: &Stimulus) -> Result<Coverage> {
+    Ok(Coverage::from_ops(
+        &stimulus.ops(&params.into())?,
+        params.num_words,
+        params.mux_ratio,
+        params.wmask_width,
+    ))
+}
+
+/// Simulates the SRAM at each of the given corners.
+///
+/// Each corner is simulated in its own directory within `work_dir/sim`.
+/// A pass/fail matrix is saved alongside them.
+pub fn run_sram_testbench(
+    params: &SramParams,
+    tech: &TechConfig,
+    corners: &[PvtCorner],
+    work_dir: impl AsRef<Path>,
+    name: &str,
+    opts: &SimOptions,
+) -> Result<()> {
+    let SimOptions {
+        simulator,
+        stimulus,
+        probe_internal,
+        max_ops,
+    } = opts;
+    if let Some(max_ops) = *max_ops {
+        let num_ops = stimulus.ops(&params.into())?.len();
+        if num_ops > max_ops {
+            bail!("The stimulus has {num_ops} operations, more than the maximum of {max_ops}");
+        }
+    }
+    let simulator = *simulator;
+    let models = tech.models(simulator);
+    if models.is_empty() {
+        bail!("No {simulator} models specified in the tech configuration");
+    }
+    let sim_dir = work_dir.as_ref().join("sim");
+
+    sram_coverage(params, stimulus)?.save(&sim_dir)?;
+
+    // A supply voltage specified by test vectors applies at every corner.
+    // Corners that then differ only in supply voltage are simulated once.
+    let mut unique_corners: Vec<PvtCorner> = Vec::with_capacity(corners.len());
+    for corner in corners {
+        let corner = match stimulus {
+            Stimulus::File(TestVectors { vdd: Some(vdd), .. }) => PvtCorner {
+                vdd: *vdd,
+                ..corner.clone()
+            },
+            _ => corner.clone(),
+        };
+        if !unique_corners.contains(&corner) {
+            unique_corners.push(corner);
+        }
+    }
+    let corners = unique_corners;
+
+    let report = run_pvt_sweep(&corners, |corner| {
+        tech.check_corner(&corner.process)?;
+
+        let mut tb = sram_tb_builder(
+            params,
+            &work_dir,
+            name,
+            simulator.verification_task(),
+            stimulus,
+        )?;
+        if *probe_internal {
+            tb.internal_nets(InternalNets {
+                rows: params.rows,
+                cols: params.cols,
+                mux_ratio: params.mux_ratio,
+                replica: params.control == ControlMode::ReplicaV1,
+            });
+        }
+        tb.simulator(simulator)
+            .vdd(corner.vdd)
+            .temp(corner.temp)
+            .work_dir(sim_dir.join(corner.name()))
+            .includes(models.includes(&corner.process)?)
+            .libs(models.libs(&corner.process)?);
+
+        let tb = tb.build()?;
+
+        run_testbench(&tb)
+    });
+    report.save(&sim_dir)?;
+
+    let failures = report
+        .failures()
+        .map(|r| r.corner.to_string())
+        .collect::<Vec<_>>();
+    if !failures.is_empty() {
+        bail!(
+            "Error simulating testbench using {}: failed at {} of {} corners ({})\n{}",
+            simulator,
+            failures.len(),
+            report.results.len(),
+            failures.join(", "),
+            report.matrix()
+        );
+    }
+
     Ok(())
 }
 
-fn generate_waveforms(params: &TbParams) -> TbWaveforms {
+/// Creates a [`TbParamsBuilder`] for a functional testbench of the SRAM described by `params`,
+/// exercising it with the given `stimulus`.
+///
+/// Simulator-specific options, such as model files and the simulator itself,
+/// are left for the caller to set.
+pub fn sram_tb_builder(
+    params: &SramParams,
+    work_dir: impl AsRef<Path>,
+    name: &str,
+    task: VerificationTask,
+    stimulus: &Stimulus,
+) -> Result<TbParamsBuilder> {
+    let &SramParams {
+        wmask_width,
+        data_width,
+        addr_width,
+        ..
+    } = params;
+
+    // Test vectors may set their own clock period.
+    let clk_period = match stimulus {
+        Stimulus::File(vectors) => vectors.test_case.clk_period,
+        _ => 20e-9,
+    };
+    let test_case = TestCase::builder()
+        .clk_period(clk_period)
+        .ops(stimulus.ops(&params.into())?)
+        .build()?;
+
+    let mut ports = vec![
+        (PortClass::Power, PortOrder::MsbFirst),
+        (PortClass::Ground, PortOrder::MsbFirst),
+        (PortClass::Clock, PortOrder::MsbFirst),
+        (PortClass::DataIn, PortOrder::MsbFirst),
+        (PortClass::DataOut, PortOrder::MsbFirst),
+        (PortClass::WriteEnable, PortOrder::MsbFirst),
+        (PortClass::Addr, PortOrder::MsbFirst),
+    ];
+    if wmask_width > 1 {
+        ports.push((PortClass::WriteMask, PortOrder::MsbFirst));
+    }
+    let mut tb = TbParams::builder();
+    tb.test_case(test_case)
+        .sram_name(name)
+        .tr(50e-12)
+        .tf(50e-12)
+        .vdd(NOMINAL_VDD)
+        .c_load(5e-15)
+        .idle_time(4.0 * clk_period)
+        .data_width(data_width)
+        .addr_width(addr_width)
+        .wmask_width(wmask_width)
+        .ports(ports)
+        .clk_port("clk")
+        .write_enable_port("we")
+        .addr_port("addr")
+        .data_in_port("din")
+        .data_out_port("dout")
+        .pwr_port("vdd")
+        .gnd_port("vss")
+        .wmask_port("wmask")
+        .bitline_width(params.cols)
+        .work_dir(std::path::PathBuf::from(work_dir.as_ref()).join("sim"))
+        .source_paths(source_files(&work_dir, name, task, params.control));
+
+    if let Stimulus::File(vectors) = stimulus {
+        vectors.apply(&mut tb);
+    }
+
+    Ok(tb)
+}
+
+pub(crate) fn generate_waveforms(params: &TbParams) -> TbWaveforms {
     let mut addr = vec![Waveform::with_initial_value(0f64); params.addr_width];
     let mut din = vec![Waveform::with_initial_value(0f64); params.data_width];
     let wmask_bits = if params.wmask_width > 1 {
@@ -240,42 +779,71 @@ fn generate_waveforms(params: &TbParams) -> TbWaveforms {
     let mut clk = Waveform::with_initial_value(0f64);
     let mut we = Waveform::with_initial_value(0f64);
 
-    let period = params.test_case.clk_period;
+    let test_case = &params.test_case;
+    let period = test_case.clk_period;
+    let t_high = test_case.duty_cycle * period;
     let vdd = params.vdd;
     let tr = params.tr;
     let tf = params.tf;
 
+    // Hold the initial values of delayed inputs through the start of the first cycle.
+    // Inputs with setup times longer than the clock period are applied immediately.
+    for (waveforms, port_class) in [
+        (&mut addr, PortClass::Addr),
+        (&mut din, PortClass::DataIn),
+        (&mut wmask, PortClass::WriteMask),
+    ] {
+        let t_apply = params.apply_time(0, port_class);
+        if t_apply > 0.0 {
+            for w in waveforms.iter_mut() {
+                w.push(t_apply, 0f64);
+            }
+        }
+    }
+    let we_apply = params.apply_time(0, PortClass::WriteEnable);
+    if we_apply > 0.0 {
+        we.push(we_apply, 0f64);
+    }
+
     let mut t = 0f64;
-    let mut t_end;
 
     let wmask_all = BitSignal::ones(params.wmask_width);
 
-    for op in params.test_case.ops.iter() {
-        t_end = t + period;
+    for (i, op) in test_case.ops.iter().enumerate() {
+        // Each input is held until the inputs of the next operation are applied.
+        let until = |port_class: PortClass| params.apply_time(i + 1, port_class);
+
         // Toggle the clock
-        clk.push_high(t + (period / 2.0), vdd, tr);
+        clk.push_high(t + t_high, vdd, tr);
         clk.push_low(t + period, vdd, tf);
 
         match op {
             Op::Read { addr: addrv } => {
                 // Set write enable low
-                we.push_low(t_end, vdd, tf);
+                we.push_low(until(PortClass::WriteEnable), vdd, tf);
 
                 assert_eq!(addrv.width(), params.addr_width);
-                push_bus(&mut addr, addrv, t_end, vdd, tr, tf);
+                push_bus(&mut addr, addrv, until(PortClass::Addr), vdd, tr, tf);
             }
             Op::Write { addr: addrv, data } => {
                 // Set write enable high
-                we.push_high(t_end, vdd, tr);
+                we.push_high(until(PortClass::WriteEnable), vdd, tr);
 
                 assert_eq!(addrv.width(), params.addr_width);
-                push_bus(&mut addr, addrv, t_end, vdd, tr, tf);
+                push_bus(&mut addr, addrv, until(PortClass::Addr), vdd, tr, tf);
 
                 assert_eq!(data.width(), params.data_width);
-                push_bus(&mut din, data, t_end, vdd, tr, tf);
+                push_bus(&mut din, data, until(PortClass::DataIn), vdd, tr, tf);
 
                 if params.wmask_width > 1 {
-                    push_bus(&mut wmask, &wmask_all, t_end, vdd, tr, tf);
+                    push_bus(
+                        &mut wmask,
+                        &wmask_all,
+                        until(PortClass::WriteMask),
+                        vdd,
+                        tr,
+                        tf,
+                    );
                 }
             }
 
@@ -285,34 +853,53 @@ fn generate_waveforms(params: &TbParams) -> TbWaveforms {
                 mask,
             } => {
                 // Set write enable high
-                we.push_high(t_end, vdd, tr);
+                we.push_high(until(PortClass::WriteEnable), vdd, tr);
 
                 assert_eq!(addrv.width(), params.addr_width);
-                push_bus(&mut addr, addrv, t_end, vdd, tr, tf);
+                push_bus(&mut addr, addrv, until(PortClass::Addr), vdd, tr, tf);
 
                 assert_eq!(data.width(), params.data_width);
-                push_bus(&mut din, data, t_end, vdd, tr, tf);
+                push_bus(&mut din, data, until(PortClass::DataIn), vdd, tr, tf);
 
                 assert!(params.wmask_width > 1);
                 assert_eq!(mask.width(), params.wmask_width);
-                push_bus(&mut wmask, mask, t_end, vdd, tr, tf);
+                push_bus(&mut wmask, mask, until(PortClass::WriteMask), vdd, tr, tf);
+            }
+
+            Op::Idle => {
+                // Set write enable low and hold all other inputs
+                we.push_low(until(PortClass::WriteEnable), vdd, tf);
+                for (waveforms, port_class) in [
+                    (&mut addr, PortClass::Addr),
+                    (&mut din, PortClass::DataIn),
+                    (&mut wmask, PortClass::WriteMask),
+                ] {
+                    for w in waveforms.iter_mut() {
+                        w.push(until(port_class), w.last_x().unwrap());
+                    }
+                }
             }
         }
 
         t += period;
     }
 
-    t_end = t + period;
+    let t_end = t + period;
     let t_final = t + 2.0 * period;
 
     // One more clock cycle
-    clk.push_high(t + period / 2.0, vdd, tr);
+    clk.push_high(t + t_high, vdd, tr);
     clk.push_low(t_end, vdd, tf);
 
     // Turn off write enable
     we.push_low(t_final, vdd, tf);
     clk.push_high(t_final, vdd, tr);
 
+    // Hold the clock high while idle
+    if params.idle_time > 0.0 {
+        clk.push(t_final + params.idle_time, vdd);
+    }
+
     TbWaveforms {
         addr,
         din,
@@ -331,21 +918,24 @@ pub enum VerificationTask {
     Pex,
 }
 
+impl VerificationTask {
+    /// The format of the SRAM netlist used by this task.
+    pub fn netlist_format(&self) -> NetlistFormat {
+        match *self {
+            Self::SpectreSim => NetlistFormat::Spectre,
+            Self::NgspiceSim => NetlistFormat::NgSpice,
+            _ => NetlistFormat::Cdl,
+        }
+    }
+}
+
 pub fn source_files(
     work_dir: impl AsRef<Path>,
     sram_name: &str,
     task: VerificationTask,
     control_mode: ControlMode,
 ) -> Vec<PathBuf> {
-    let source_path_main = match task {
-        VerificationTask::SpectreSim => {
-            PathBuf::from(work_dir.as_ref()).join(format!("{}.spectre.spice", sram_name))
-        }
-        VerificationTask::NgspiceSim => {
-            PathBuf::from(work_dir.as_ref()).join(format!("{}.ngspice.spice", sram_name))
-        }
-        _ => PathBuf::from(work_dir.as_ref()).join(format!("{}.spice", sram_name)),
-    };
+    let source_path_main = out_spice(&work_dir, sram_name, task.netlist_format());
     let source_path_dff = PathBuf::from(LIB_PATH).join("openram_dff/openram_dff.spice");
     let source_path_sp_cell = match task {
         VerificationTask::SpiceSim
@@ -387,7 +977,7 @@ pub fn source_files(
     ]
 }
 
-fn verify_simulation(data: &TransientData, tb: &TbParams) -> Result<()> {
+pub fn verify_simulation(data: &TransientData, tb: &TbParams) -> Result<()> {
     let mut state = HashMap::new();
     let data_bits_per_wmask = tb.data_width / tb.wmask_width;
 
@@ -396,7 +986,7 @@ fn verify_simulation(data: &TransientData, tb: &TbParams) -> Result<()> {
     // since nothing happens on the first cycle of our testbench.
     let mut cycle = 1;
 
-    for op in tb.test_case.ops.iter() {
+    for (op_idx, op) in tb.test_case.ops.iter().enumerate() {
         cycle += 1;
         match op {
             Op::Read { addr } => {
@@ -417,14 +1007,30 @@ fn verify_simulation(data: &TransientData, tb: &TbParams) -> Result<()> {
                         .ok_or_else(|| {
                             anyhow!("Index {} was out of range for signal {}", idx, &name)
                         })?;
-                    let rx_bit = to_bit(*rx_bit, tb.vdd)?;
                     let ex_bit = expected.bit(i);
-                    if rx_bit != ex_bit {
-                        bail!(
-                            "Expected bit {} to be {}; got {} at clock cycle {} (time {}, index {})",
-                            i, ex_bit, rx_bit, cycle-1, t, idx
-                        );
+                    // A bit stuck between the rails is reported as a mismatch, like a wrong value,
+                    // so that it is diagnosed in the same way.
+                    let rx_bit = match to_logic(*rx_bit, tb.vdd) {
+                        Some(bit) if bit == ex_bit => continue,
+                        Some(bit) => bit.to_string(),
+                        None => format!("{} V, which is not close to either rail", rx_bit),
+                    };
+                    let mut msg = format!(
+                        "Expected bit {} to be {}; got {} at clock cycle {} (time {}, index {})",
+                        i,
+                        ex_bit,
+                        rx_bit,
+                        cycle - 1,
+                        t,
+                        idx
+                    );
+                    if tb.internal_nets.is_some() {
+                        match diagnose_read(data, tb, op_idx, i) {
+                            Ok(diagnostic) => msg.push_str(&format!("\n{diagnostic}")),
+                            Err(e) => msg.push_str(&format!("\nUnable to diagnose failure: {e}")),
+                        }
                     }
+                    bail!(msg);
                 }
             }
             Op::Write { addr, data } => {
@@ -443,6 +1049,7 @@ fn verify_simulation(data: &TransientData, tb: &TbParams) -> Result<()> {
                     }
                 }
             }
+            Op::Idle => {}
         }
     }
     Ok(())
@@ -0,0 +1,86 @@
+//! LVS using the open source Netgen netlist comparison tool.
+
+use anyhow::{bail, Context};
+use std::fmt::Write;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::verification::netlist::write_include;
+use crate::Result;
+
+pub struct NetgenLvsParams<'a> {
+    pub work_dir: &'a Path,
+    /// The netlist extracted from the layout.
+    pub layout_netlist_path: &'a Path,
+    /// The schematic netlists.
+    pub source_paths: &'a [PathBuf],
+    /// The name of the top level cell in both the layout and the schematic.
+    pub cell_name: &'a str,
+    /// The Netgen setup file, such as `sky130A_setup.tcl`.
+    pub setup_path: &'a Path,
+    pub report_path: &'a Path,
+}
+
+/// Compares a netlist extracted from a layout against the schematic netlists,
+/// returning whether they match.
+pub fn run_lvs(params: &NetgenLvsParams) -> Result<bool> {
+    std::fs::create_dir_all(params.work_dir)?;
+
+    // Netgen reads a single file per circuit, so gather the schematic netlists into one.
+    let source_path = params.work_dir.join("source.spice");
+    let mut source = String::new();
+    writeln!(&mut source, "* Schematic netlists of {}", params.cell_name)?;
+    for path in params.source_paths {
+        write_include(&mut source, path)?;
+    }
+    std::fs::write(&source_path, source)?;
+
+    let out_file = File::create(params.work_dir.join("netgen.out"))?;
+    let err_file = File::create(params.work_dir.join("netgen.err"))?;
+
+    let status = Command::new("netgen")
+        .arg("-batch")
+        .arg("lvs")
+        .arg(format!(
+            "{} {}",
+            params.layout_netlist_path.display(),
+            params.cell_name
+        ))
+        .arg(format!("{} {}", source_path.display(), params.cell_name))
+        .arg(params.setup_path)
+        .arg(params.report_path)
+        .stdout(out_file)
+        .stderr(err_file)
+        .current_dir(params.work_dir)
+        .status()
+        .with_context(|| "Failed to start netgen")?;
+
+    if !status.success() {
+        bail!("netgen exited unsuccessfully");
+    }
+
+    let report = std::fs::read_to_string(params.report_path)
+        .with_context(|| format!("Error reading Netgen report {:?}", params.report_path))?;
+    parse_report(&report)
+}
+
+/// Determines whether the netlists compared in a Netgen LVS report match.
+///
+/// Netlists that match only up to property errors, such as mismatched device sizes,
+/// do not match.
+pub fn parse_report(report: &str) -> Result<bool> {
+    let result = report
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("Final result:"))
+        .map(str::trim);
+    match result {
+        Some(result) if result.starts_with("Circuits match") => {
+            Ok(!report.contains("Property errors were found"))
+        }
+        // Eg. `Netlists do not match` or `Top level cell failed pin matching`.
+        Some(_) => Ok(false),
+        None => bail!("Netgen LVS report does not contain a final result"),
+    }
+}
@@ -1,19 +1,26 @@
 use anyhow::anyhow;
 
+use super::power::VDD_SOURCE;
 use super::waveform::Waveform;
 use super::{TbParams, TbWaveforms};
-use crate::verification::PortOrder;
+use crate::verification::{PortOrder, SimulatorKind};
 use crate::Result;
 use std::fmt::Write;
 use std::path::Path;
 
+/// Name of the SRAM instance in generated testbenches.
+pub const DUT_INSTANCE: &str = "xdut0";
+
 #[derive(Debug, Clone)]
 pub struct TbNetlistParams<'a> {
     pub tb: &'a TbParams,
     pub waveforms: &'a TbWaveforms,
 }
 
-pub fn generate_netlist(params: TbNetlistParams) -> crate::Result<String> {
+pub fn generate_netlist(
+    simulator: SimulatorKind,
+    params: TbNetlistParams,
+) -> crate::Result<String> {
     let TbNetlistParams { tb, waveforms } = params;
     let mut out = String::new();
 
@@ -31,14 +38,22 @@ pub fn generate_netlist(params: TbNetlistParams) -> crate::Result<String> {
         tb.sram_name
     )?;
     writeln!(&mut out, ".param t_end={}", clk.last_t().unwrap())?;
-    writeln!(&mut out, ".tran 1.00e-12 't_end'")?;
+    match simulator {
+        SimulatorKind::Spectre => writeln!(&mut out, ".tran 1.00e-12 't_end'")?,
+        SimulatorKind::Ngspice | SimulatorKind::Xyce => {
+            writeln!(&mut out, ".tran 1.00e-12 {{t_end}}")?
+        }
+    }
     write_spacer(&mut out)?;
 
+    for (path, section) in tb.libs.iter() {
+        write_lib(&mut out, path, section)?;
+    }
     for include in tb.includes.iter() {
-        writeln!(&mut out, ".include {}", include)?;
+        write_include(&mut out, include)?;
     }
     for include in tb.source_paths.iter() {
-        writeln!(&mut out, ".include {:?}", include)?;
+        write_include(&mut out, include)?;
     }
     write_spacer(&mut out)?;
 
@@ -46,14 +61,20 @@ pub fn generate_netlist(params: TbNetlistParams) -> crate::Result<String> {
 
     let gnd_net = &tb.gnd_port;
 
-    writeln!(&mut out, "Vvdd {} {} {}", tb.pwr_port, gnd_net, tb.vdd)?;
+    writeln!(
+        &mut out,
+        "{} {} {} {}",
+        VDD_SOURCE, tb.pwr_port, gnd_net, tb.vdd
+    )?;
     writeln!(&mut out, "Vvss {} 0 0", gnd_net)?;
     write_spacer(&mut out)?;
-    writeln!(&mut out, ".option parhier=local redefinedparams=ignore")?;
-    writeln!(&mut out, "simulator lang=spectre")?;
-    writeln!(&mut out, "altos_op1 options global_param_override=ignore")?;
-    writeln!(&mut out, "simulator lang=spice")?;
-    write_spacer(&mut out)?;
+    if simulator == SimulatorKind::Spectre {
+        writeln!(&mut out, ".option parhier=local redefinedparams=ignore")?;
+        writeln!(&mut out, "simulator lang=spectre")?;
+        writeln!(&mut out, "altos_op1 options global_param_override=ignore")?;
+        writeln!(&mut out, "simulator lang=spice")?;
+        write_spacer(&mut out)?;
+    }
 
     write_pwl(&mut out, &tb.clk_port, gnd_net, clk)?;
     write_pwl(&mut out, &tb.write_enable_port, gnd_net, we)?;
@@ -81,11 +102,12 @@ pub fn generate_netlist(params: TbNetlistParams) -> crate::Result<String> {
         tb.c_load,
     )?;
     write_spacer(&mut out)?;
-    write_probe(&mut out, &tb.clk_port)?;
-    write_probe(&mut out, &tb.write_enable_port)?;
+    write_probe(&mut out, simulator, &tb.clk_port)?;
+    write_probe(&mut out, simulator, &tb.write_enable_port)?;
     if !wmask.is_empty() {
         write_probes(
             &mut out,
+            simulator,
             tb.wmask_port.as_ref().ok_or_else(|| {
                 anyhow!(
                     "Attempting to use write mask waveforms but no write mask port was specified."
@@ -94,12 +116,36 @@ pub fn generate_netlist(params: TbNetlistParams) -> crate::Result<String> {
             tb.wmask_width,
         )?;
     }
-    write_probes(&mut out, &tb.data_out_port, tb.data_width)?;
-    write_probes(&mut out, &tb.data_in_port, tb.data_width)?;
-    write_probes(&mut out, &tb.addr_port, tb.addr_width)?;
+    write_probes(&mut out, simulator, &tb.data_out_port, tb.data_width)?;
+    write_probes(&mut out, simulator, &tb.data_in_port, tb.data_width)?;
+    write_probes(&mut out, simulator, &tb.addr_port, tb.addr_width)?;
+    write_current_probe(&mut out, simulator, VDD_SOURCE)?;
+    if let Some(width) = tb.bitline_width {
+        write_probes(&mut out, simulator, &dut_node(simulator, "bl"), width)?;
+        write_probes(&mut out, simulator, &dut_node(simulator, "br"), width)?;
+    }
+    if let Some(nets) = tb.internal_nets {
+        write_probes(&mut out, simulator, &dut_node(simulator, "wl"), nets.rows)?;
+        write_probes(
+            &mut out,
+            simulator,
+            &dut_node(simulator, "write_driver_en"),
+            nets.mux_ratio,
+        )?;
+        if tb.bitline_width.is_none() {
+            write_probes(&mut out, simulator, &dut_node(simulator, "bl"), nets.cols)?;
+            write_probes(&mut out, simulator, &dut_node(simulator, "br"), nets.cols)?;
+        }
+        for net in nets.signals() {
+            write_probe(&mut out, simulator, &dut_node(simulator, net))?;
+        }
+    }
 
     write_spacer(&mut out)?;
-    writeln!(&mut out, ".temp 25")?;
+    match simulator {
+        SimulatorKind::Spectre | SimulatorKind::Ngspice => writeln!(&mut out, ".temp {}", tb.temp)?,
+        SimulatorKind::Xyce => writeln!(&mut out, ".options device temp={}", tb.temp)?,
+    }
     writeln!(&mut out, ".end")?;
     write_spacer(&mut out)?;
 
@@ -118,7 +164,12 @@ fn write_pwl_bus(
     Ok(())
 }
 
-fn write_pwl(out: &mut String, net: &str, gnd_net: &str, waveform: &Waveform) -> Result<()> {
+pub(crate) fn write_pwl(
+    out: &mut String,
+    net: &str,
+    gnd_net: &str,
+    waveform: &Waveform,
+) -> Result<()> {
     writeln!(out, "V{net} {net} {gnd_net} pwl(")?;
     for (t, x) in waveform.values() {
         writeln!(out, "+ {t} {x}")?;
@@ -128,7 +179,7 @@ fn write_pwl(out: &mut String, net: &str, gnd_net: &str, waveform: &Waveform) ->
 }
 
 fn write_dut(out: &mut String, tb: &TbParams) -> Result<()> {
-    writeln!(out, "XDUT0 ")?;
+    writeln!(out, "{DUT_INSTANCE}")?;
     for (port_class, order) in tb.ports.iter().copied() {
         let port_name = tb.port_name(port_class);
         if port_class.is_bus() {
@@ -154,6 +205,14 @@ fn write_dut(out: &mut String, tb: &TbParams) -> Result<()> {
     Ok(())
 }
 
+/// The name under which `simulator` reports the net `net` inside the SRAM instance.
+pub fn dut_node(simulator: SimulatorKind, net: &str) -> String {
+    match simulator {
+        SimulatorKind::Spectre | SimulatorKind::Ngspice => format!("{DUT_INSTANCE}.{net}"),
+        SimulatorKind::Xyce => format!("{DUT_INSTANCE}:{net}"),
+    }
+}
+
 fn write_cap_loads(
     out: &mut String,
     port: &str,
@@ -168,17 +227,61 @@ fn write_cap_loads(
     Ok(())
 }
 
-fn write_probes(out: &mut String, port: &str, width: usize) -> Result<()> {
-    writeln!(out, "* PROBES FOR {port}")?;
-    for i in 0..width {
-        writeln!(out, ".probe v({port}[{i}])")?;
+/// The control card used to save a signal.
+///
+/// Spectre (in SPICE mode) uses `.probe`; ngspice only writes vectors
+/// listed in `.save` cards to the rawfile. Xyce saves every node
+/// to the rawfile, so it needs no probes.
+fn probe_card(simulator: SimulatorKind) -> Option<&'static str> {
+    match simulator {
+        SimulatorKind::Spectre => Some(".probe"),
+        SimulatorKind::Ngspice => Some(".save"),
+        SimulatorKind::Xyce => None,
     }
+}
+
+fn write_probes(
+    out: &mut String,
+    simulator: SimulatorKind,
+    port: &str,
+    width: usize,
+) -> Result<()> {
+    if let Some(card) = probe_card(simulator) {
+        writeln!(out, "* PROBES FOR {port}")?;
+        for i in 0..width {
+            writeln!(out, "{card} v({port}[{i}])")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_probe(out: &mut String, simulator: SimulatorKind, net: &str) -> Result<()> {
+    if let Some(card) = probe_card(simulator) {
+        writeln!(out, "* PROBE FOR {net}")?;
+        writeln!(out, "{card} v({net})")?;
+    }
+    Ok(())
+}
+
+fn write_current_probe(out: &mut String, simulator: SimulatorKind, source: &str) -> Result<()> {
+    if let Some(card) = probe_card(simulator) {
+        writeln!(out, "* PROBE FOR CURRENT THROUGH {source}")?;
+        writeln!(out, "{card} i({source})")?;
+    }
+    Ok(())
+}
+
+/// Writes a `.lib` card loading `section` of the model library at `path`.
+///
+/// The path is quoted as is, rather than escaped like a Rust string.
+pub(crate) fn write_lib(out: &mut String, path: impl AsRef<Path>, section: &str) -> Result<()> {
+    writeln!(out, ".lib \"{}\" {section}", path.as_ref().display())?;
     Ok(())
 }
 
-fn write_probe(out: &mut String, net: &str) -> Result<()> {
-    writeln!(out, "* PROBE FOR {net}")?;
-    writeln!(out, ".probe v({net})")?;
+/// Writes an `.include` card for the netlist at `path`, quoted as in [`write_lib`].
+pub(crate) fn write_include(out: &mut String, path: impl AsRef<Path>) -> Result<()> {
+    writeln!(out, ".include \"{}\"", path.as_ref().display())?;
     Ok(())
 }
 
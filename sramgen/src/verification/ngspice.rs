@@ -0,0 +1,211 @@
+use anyhow::{anyhow, bail, Context};
+use psf_ascii::parser::transient::TransientData;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::verification::{Simulator, SimulatorKind};
+use crate::Result;
+
+/// The open source ngspice simulator.
+pub struct Ngspice;
+
+impl Simulator for Ngspice {
+    fn kind(&self) -> SimulatorKind {
+        SimulatorKind::Ngspice
+    }
+
+    fn run(&self, work_dir: &Path, netlist_path: &Path) -> Result<TransientData> {
+        run_ngspice(&NgspiceParams {
+            work_dir: work_dir.to_path_buf(),
+            spice_path: netlist_path.to_path_buf(),
+        })
+    }
+}
+
+pub struct NgspiceParams {
+    pub work_dir: PathBuf,
+    pub spice_path: PathBuf,
+}
+
+pub struct NgspiceGeneratedPaths {
+    pub raw_output_path: PathBuf,
+    pub log_path: PathBuf,
+    pub stdout_path: PathBuf,
+    pub stderr_path: PathBuf,
+}
+
+pub fn run_ngspice(params: &NgspiceParams) -> Result<TransientData> {
+    parse_rawfile(&run_ngspice_raw(params)?)
+}
+
+/// Runs a netlist containing a DC sweep, returning the swept values.
+pub fn run_ngspice_dc(params: &NgspiceParams) -> Result<DcData> {
+    parse_dc_rawfile(&run_ngspice_raw(params)?)
+}
+
+/// Runs ngspice, returning the contents of the ASCII rawfile it produced.
+fn run_ngspice_raw(params: &NgspiceParams) -> Result<String> {
+    let paths = generate_paths(params);
+
+    let out_file = std::fs::File::create(&paths.stdout_path)?;
+    let err_file = std::fs::File::create(&paths.stderr_path)?;
+
+    let status = Command::new("ngspice")
+        .arg("-b")
+        .arg("-r")
+        .arg(&paths.raw_output_path)
+        .arg("-o")
+        .arg(&paths.log_path)
+        .arg(&params.spice_path)
+        // Ask ngspice for an ASCII rawfile rather than the default binary format
+        .env("SPICE_ASCIIRAWFILE", "1")
+        .stdout(out_file)
+        .stderr(err_file)
+        .current_dir(&params.work_dir)
+        .status()
+        .with_context(|| "Failed to start ngspice")?;
+
+    if !status.success() {
+        bail!("ngspice exited unsuccessfully");
+    }
+
+    Ok(std::fs::read_to_string(&paths.raw_output_path)?)
+}
+
+fn generate_paths(params: &NgspiceParams) -> NgspiceGeneratedPaths {
+    NgspiceGeneratedPaths {
+        raw_output_path: params.work_dir.join("ngspice.raw"),
+        log_path: params.work_dir.join("ngspice.log"),
+        stdout_path: params.work_dir.join("ngspice.out"),
+        stderr_path: params.work_dir.join("ngspice.err"),
+    }
+}
+
+/// The results of a DC sweep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcData {
+    /// The values of the swept source.
+    pub sweep: Vec<f64>,
+    pub signals: HashMap<String, Vec<f64>>,
+}
+
+impl DcData {
+    #[inline]
+    pub fn signal(&self, name: &str) -> Option<&Vec<f64>> {
+        self.signals.get(name)
+    }
+}
+
+/// Parses the transient analysis from an ASCII ngspice rawfile.
+///
+/// Signal names are normalized to match those produced by Spectre:
+/// node voltages are named `v(node)` and branch currents `i(source)`.
+pub fn parse_rawfile(raw: &str) -> Result<TransientData> {
+    let (plotname, mut signals) = parse_plot(raw)?;
+    if !plotname.starts_with("Transient") {
+        bail!(
+            "Expected a transient analysis rawfile; found plot `{}`",
+            plotname
+        );
+    }
+    let time = signals
+        .remove("time")
+        .ok_or_else(|| anyhow!("Rawfile variable list is malformed"))?;
+    Ok(TransientData { signals, time })
+}
+
+/// Parses the DC sweep from an ASCII ngspice rawfile.
+///
+/// Signal names are normalized as in [`parse_rawfile`].
+pub fn parse_dc_rawfile(raw: &str) -> Result<DcData> {
+    let (plotname, mut signals) = parse_plot(raw)?;
+    if !plotname.starts_with("DC") {
+        bail!("Expected a DC sweep rawfile; found plot `{}`", plotname);
+    }
+    let sweep = signals
+        .remove(SWEEP_NAME)
+        .ok_or_else(|| anyhow!("Rawfile variable list is malformed"))?;
+    Ok(DcData { sweep, signals })
+}
+
+/// The name given to the independent variable of a DC sweep.
+const SWEEP_NAME: &str = "v(v-sweep)";
+
+/// Parses the name and signals of the first plot in an ASCII ngspice rawfile.
+///
+/// The independent variable is named `time` for transient analyses
+/// and [`SWEEP_NAME`] for DC sweeps.
+fn parse_plot(raw: &str) -> Result<(String, HashMap<String, Vec<f64>>)> {
+    let mut lines = raw.lines();
+
+    let mut plotname = String::new();
+    let mut num_vars = None;
+    let mut num_points = None;
+    let mut names = Vec::new();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if let Some(value) = line.strip_prefix("Plotname:") {
+            plotname = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("Flags:") {
+            if value.contains("complex") {
+                bail!("Complex rawfile data is not supported");
+            }
+        } else if let Some(value) = line.strip_prefix("No. Variables:") {
+            num_vars = Some(value.trim().parse::<usize>()?);
+        } else if let Some(value) = line.strip_prefix("No. Points:") {
+            num_points = Some(value.trim().parse::<usize>()?);
+        } else if line.starts_with("Variables:") {
+            let num_vars =
+                num_vars.ok_or_else(|| anyhow!("Rawfile variables listed before their count"))?;
+            for _ in 0..num_vars {
+                let var = lines
+                    .next()
+                    .ok_or_else(|| anyhow!("Unexpected end of rawfile in variable list"))?;
+                let mut tokens = var.split_whitespace().skip(1);
+                let name = tokens
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing variable name in rawfile: {}", var))?;
+                let kind = tokens.next().unwrap_or("");
+                names.push(normalize_name(name, kind));
+            }
+        } else if line.starts_with("Values:") {
+            break;
+        }
+    }
+
+    let num_vars = num_vars.ok_or_else(|| anyhow!("Rawfile is missing variable count"))?;
+    let num_points = num_points.ok_or_else(|| anyhow!("Rawfile is missing point count"))?;
+    if names.len() != num_vars || num_vars == 0 {
+        bail!("Rawfile variable list is malformed");
+    }
+
+    let mut values = vec![Vec::with_capacity(num_points); num_vars];
+    let mut tokens = lines.flat_map(|line| line.split_whitespace());
+    for point in 0..num_points {
+        // Each point starts with its index, followed by one value per variable.
+        tokens
+            .next()
+            .ok_or_else(|| anyhow!("Unexpected end of rawfile at point {}", point))?;
+        for signal in values.iter_mut() {
+            let value = tokens
+                .next()
+                .ok_or_else(|| anyhow!("Unexpected end of rawfile at point {}", point))?;
+            signal.push(value.trim_end_matches(',').parse::<f64>()?);
+        }
+    }
+
+    Ok((plotname, names.into_iter().zip(values).collect()))
+}
+
+fn normalize_name(name: &str, kind: &str) -> String {
+    let name = name.to_lowercase();
+    if let Some(source) = name.strip_suffix("#branch") {
+        format!("i({source})")
+    } else if kind == "voltage" && !name.starts_with("v(") {
+        format!("v({name})")
+    } else {
+        name
+    }
+}
@@ -0,0 +1,371 @@
+//! Memory test patterns.
+//!
+//! Each pattern initializes every address it reads,
+//! as required by [`verify_simulation`](super::verify_simulation).
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::config::sram::SramParams;
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::{Op, TestCase};
+use crate::Result;
+
+/// An alternating 64-bit sequence 0b010101...01
+const BIT_PATTERN1: u64 = 0x5555555555555555u64;
+
+/// An alternating 64-bit sequence 0b101010...10
+const BIT_PATTERN2: u64 = 0xAAAAAAAAAAAAAAAAu64;
+
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, clap::ValueEnum,
+)]
+pub enum Pattern {
+    /// Alternating data patterns written to and read from the first 16 addresses.
+    #[default]
+    Basic,
+    /// March C- (10N operations).
+    MarchCMinus,
+    /// March B (17N operations).
+    MarchB,
+    /// Alternating data in alternating addresses, followed by its inverse.
+    Checkerboard,
+    /// A single 1 walked through the data bits of successive addresses.
+    WalkingOnes,
+    /// A single 0 walked through the data bits of successive addresses.
+    WalkingZeros,
+    /// Writes each address's own value to it, then its complement,
+    /// reading back in the opposite address order.
+    AddressDecoder,
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Basic => write!(f, "basic"),
+            Self::MarchCMinus => write!(f, "march-c-minus"),
+            Self::MarchB => write!(f, "march-b"),
+            Self::Checkerboard => write!(f, "checkerboard"),
+            Self::WalkingOnes => write!(f, "walking-ones"),
+            Self::WalkingZeros => write!(f, "walking-zeros"),
+            Self::AddressDecoder => write!(f, "address-decoder"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PatternParams {
+    pub addr_width: usize,
+    pub data_width: usize,
+    pub wmask_width: usize,
+}
+
+impl PatternParams {
+    #[inline]
+    pub fn num_words(&self) -> usize {
+        1 << self.addr_width
+    }
+
+    fn addr(&self, a: usize) -> BitSignal {
+        BitSignal::from_u64(a as u64, self.addr_width)
+    }
+}
+
+impl From<&SramParams> for PatternParams {
+    fn from(params: &SramParams) -> Self {
+        Self {
+            addr_width: params.addr_width,
+            data_width: params.data_width,
+            wmask_width: params.wmask_width,
+        }
+    }
+}
+
+/// The order in which a March element visits addresses.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AddressOrder {
+    Up,
+    Down,
+}
+
+/// An operation on a single address within a March element.
+///
+/// `0` and `1` refer to an all-zeros and an all-ones data word, respectively.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum MarchOp {
+    R0,
+    R1,
+    W0,
+    W1,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct MarchElement {
+    pub order: AddressOrder,
+    pub ops: Vec<MarchOp>,
+}
+
+impl MarchElement {
+    pub fn new(order: AddressOrder, ops: impl Into<Vec<MarchOp>>) -> Self {
+        Self {
+            order,
+            ops: ops.into(),
+        }
+    }
+}
+
+pub fn march_c_minus() -> Vec<MarchElement> {
+    use AddressOrder::*;
+    use MarchOp::*;
+    vec![
+        MarchElement::new(Up, [W0]),
+        MarchElement::new(Up, [R0, W1]),
+        MarchElement::new(Up, [R1, W0]),
+        MarchElement::new(Down, [R0, W1]),
+        MarchElement::new(Down, [R1, W0]),
+        MarchElement::new(Up, [R0]),
+    ]
+}
+
+pub fn march_b() -> Vec<MarchElement> {
+    use AddressOrder::*;
+    use MarchOp::*;
+    vec![
+        MarchElement::new(Up, [W0]),
+        MarchElement::new(Up, [R0, W1, R1, W0, R0, W1]),
+        MarchElement::new(Up, [R1, W0, W1]),
+        MarchElement::new(Down, [R1, W0, W1, W0]),
+        MarchElement::new(Down, [R0, W1, W0]),
+    ]
+}
+
+/// Expands a March algorithm into a list of operations.
+pub fn march(elements: &[MarchElement], params: &PatternParams) -> Vec<Op> {
+    let zeros = BitSignal::zeros(params.data_width);
+    let ones = BitSignal::ones(params.data_width);
+
+    let mut ops = Vec::new();
+    for element in elements {
+        let addrs: Box<dyn Iterator<Item = usize>> = match element.order {
+            AddressOrder::Up => Box::new(0..params.num_words()),
+            AddressOrder::Down => Box::new((0..params.num_words()).rev()),
+        };
+        for a in addrs {
+            let addr = params.addr(a);
+            for op in element.ops.iter() {
+                ops.push(match op {
+                    MarchOp::R0 | MarchOp::R1 => Op::Read { addr: addr.clone() },
+                    MarchOp::W0 => Op::Write {
+                        addr: addr.clone(),
+                        data: zeros.clone(),
+                    },
+                    MarchOp::W1 => Op::Write {
+                        addr: addr.clone(),
+                        data: ones.clone(),
+                    },
+                });
+            }
+        }
+    }
+    ops
+}
+
+pub fn basic(params: &PatternParams) -> Vec<Op> {
+    let &PatternParams {
+        addr_width,
+        data_width,
+        wmask_width,
+    } = params;
+
+    let addr1 = BitSignal::zeros(addr_width);
+    let addr2 = BitSignal::ones(addr_width);
+
+    let mut ops = vec![
+        Op::Write {
+            addr: addr1.clone(),
+            data: BitSignal::from_u64(BIT_PATTERN1, data_width),
+        },
+        Op::Write {
+            addr: addr2.clone(),
+            data: BitSignal::from_u64(BIT_PATTERN2, data_width),
+        },
+        Op::Read {
+            addr: addr1.clone(),
+        },
+        Op::Read { addr: addr2 },
+        Op::Read { addr: addr1 },
+    ];
+
+    for i in 0..16 {
+        let bits = (i % 2) * BIT_PATTERN2 + (1 - (i % 2)) * BIT_PATTERN1 + i + 1;
+        ops.push(Op::Write {
+            addr: BitSignal::from_u64(i, addr_width),
+            data: BitSignal::from_u64(bits, data_width),
+        });
+    }
+    for i in 0..16 {
+        ops.push(Op::Read {
+            addr: BitSignal::from_u64(i, addr_width),
+        });
+    }
+
+    if wmask_width > 1 {
+        for i in 0..16 {
+            let bits = (1 - (i % 2)) * BIT_PATTERN2 + (i % 2) * BIT_PATTERN1 + i + 1;
+            ops.push(Op::WriteMasked {
+                addr: BitSignal::from_u64(i, addr_width),
+                data: BitSignal::from_u64(bits, data_width),
+                mask: BitSignal::from_u64(BIT_PATTERN1, wmask_width),
+            });
+        }
+        for i in 0..16 {
+            ops.push(Op::Read {
+                addr: BitSignal::from_u64(i, addr_width),
+            });
+        }
+    }
+
+    ops
+}
+
+pub fn checkerboard(params: &PatternParams) -> Vec<Op> {
+    let pattern1 = BitSignal::from_u64(BIT_PATTERN1, params.data_width);
+    let pattern2 = BitSignal::from_u64(BIT_PATTERN2, params.data_width);
+
+    let mut ops = Vec::new();
+    for (even, odd) in [(&pattern1, &pattern2), (&pattern2, &pattern1)] {
+        for a in 0..params.num_words() {
+            ops.push(Op::Write {
+                addr: params.addr(a),
+                data: if a % 2 == 0 { even } else { odd }.clone(),
+            });
+        }
+        for a in 0..params.num_words() {
+            ops.push(Op::Read {
+                addr: params.addr(a),
+            });
+        }
+    }
+    ops
+}
+
+/// Walks a single `value` bit through a background of `!value` bits.
+///
+/// Address `a` is written with the bit `a % data_width` set to `value`.
+/// If there are fewer words than data bits, the walk wraps around the
+/// address space so that every data bit is exercised.
+pub fn walking(params: &PatternParams, value: bool) -> Vec<Op> {
+    let num_words = params.num_words();
+    let data = |j: usize| {
+        let mut data = if value {
+            BitSignal::zeros(params.data_width)
+        } else {
+            BitSignal::ones(params.data_width)
+        };
+        data.assign_bit(j % params.data_width, value);
+        data
+    };
+
+    let mut ops = Vec::new();
+    for a in 0..num_words {
+        ops.push(Op::Write {
+            addr: params.addr(a),
+            data: data(a),
+        });
+    }
+    for a in 0..num_words {
+        ops.push(Op::Read {
+            addr: params.addr(a),
+        });
+    }
+    for j in num_words..params.data_width {
+        let addr = params.addr(j % num_words);
+        ops.push(Op::Write {
+            addr: addr.clone(),
+            data: data(j),
+        });
+        ops.push(Op::Read { addr });
+    }
+    ops
+}
+
+/// Writes each address's own value to it, reads back in descending order,
+/// then repeats with the complement of each address's value.
+///
+/// Addresses wider than the data word are truncated to the data width.
+pub fn address_decoder(params: &PatternParams) -> Vec<Op> {
+    let value = |a: usize, invert: bool| {
+        let mut data = BitSignal::zeros(params.data_width);
+        for i in 0..params.data_width.min(params.addr_width) {
+            data.assign_bit(i, ((a >> i) & 1 == 1) != invert);
+        }
+        data
+    };
+
+    let mut ops = Vec::new();
+    for invert in [false, true] {
+        for a in 0..params.num_words() {
+            ops.push(Op::Write {
+                addr: params.addr(a),
+                data: value(a, invert),
+            });
+        }
+        for a in (0..params.num_words()).rev() {
+            ops.push(Op::Read {
+                addr: params.addr(a),
+            });
+        }
+    }
+    ops
+}
+
+/// Exercises every write mask lane at every address.
+///
+/// Assumes every address has already been written.
+fn masked_writes(params: &PatternParams) -> Vec<Op> {
+    let mut ops = Vec::new();
+    for (data, mask) in [
+        (BitSignal::ones(params.data_width), BIT_PATTERN1),
+        (BitSignal::zeros(params.data_width), BIT_PATTERN2),
+    ] {
+        let mask = BitSignal::from_u64(mask, params.wmask_width);
+        for a in 0..params.num_words() {
+            ops.push(Op::WriteMasked {
+                addr: params.addr(a),
+                data: data.clone(),
+                mask: mask.clone(),
+            });
+            ops.push(Op::Read {
+                addr: params.addr(a),
+            });
+        }
+    }
+    ops
+}
+
+/// Generates the operations of the given pattern.
+///
+/// Patterns other than [`Pattern::Basic`] finish by exercising each write mask lane
+/// if the SRAM has more than one.
+pub fn generate(pattern: Pattern, params: &PatternParams) -> Vec<Op> {
+    let mut ops = match pattern {
+        Pattern::Basic => return basic(params),
+        Pattern::MarchCMinus => march(&march_c_minus(), params),
+        Pattern::MarchB => march(&march_b(), params),
+        Pattern::Checkerboard => checkerboard(params),
+        Pattern::WalkingOnes => walking(params, true),
+        Pattern::WalkingZeros => walking(params, false),
+        Pattern::AddressDecoder => address_decoder(params),
+    };
+    if params.wmask_width > 1 {
+        ops.extend(masked_writes(params));
+    }
+    ops
+}
+
+pub fn test_case(pattern: Pattern, params: &PatternParams, clk_period: f64) -> Result<TestCase> {
+    Ok(TestCase::builder()
+        .clk_period(clk_period)
+        .ops(generate(pattern, params))
+        .build()?)
+}
@@ -0,0 +1,168 @@
+//! Energy and leakage measurements from the supply current.
+
+use anyhow::anyhow;
+use psf_ascii::parser::transient::TransientData;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::verification::{Op, TbParams};
+use crate::Result;
+
+/// Name of the voltage source supplying the SRAM in generated testbenches.
+pub const VDD_SOURCE: &str = "vvdd";
+
+/// The name of the signal holding the current through [`VDD_SOURCE`].
+pub fn supply_current_signal() -> String {
+    format!("i({VDD_SOURCE})")
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum OpKind {
+    Read,
+    Write,
+    WriteMasked,
+    Idle,
+}
+
+impl From<&Op> for OpKind {
+    fn from(op: &Op) -> Self {
+        match op {
+            Op::Read { .. } => Self::Read,
+            Op::Write { .. } => Self::Write,
+            Op::WriteMasked { .. } => Self::WriteMasked,
+            Op::Idle => Self::Idle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpEnergy {
+    /// Index of the operation in the test case.
+    pub op: usize,
+    pub kind: OpKind,
+    /// Energy drawn from the supply during the cycle in which the operation executes.
+    pub energy: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerReport {
+    pub vdd: f64,
+    pub energies: Vec<OpEnergy>,
+    /// Average energy per read.
+    pub read_energy: Option<f64>,
+    /// Average energy per unmasked write.
+    pub write_energy: Option<f64>,
+    /// Average energy per masked write.
+    pub write_masked_energy: Option<f64>,
+    /// Average energy per idle cycle.
+    #[serde(default)]
+    pub idle_energy: Option<f64>,
+    /// Average power drawn while the SRAM is idle.
+    ///
+    /// `None` if the testbench has no idle time.
+    pub leakage_power: Option<f64>,
+}
+
+impl PowerReport {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn average_energy(&self, kind: OpKind) -> Option<f64> {
+        let energies = self
+            .energies
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| e.energy)
+            .collect::<Vec<_>>();
+        if energies.is_empty() {
+            None
+        } else {
+            Some(energies.iter().sum::<f64>() / energies.len() as f64)
+        }
+    }
+}
+
+/// Integrates the piecewise linear signal `values` over `[t_start, t_end]`.
+pub fn integrate(time: &[f64], values: &[f64], t_start: f64, t_end: f64) -> f64 {
+    let interp = |i: usize, t: f64| {
+        let (t0, t1) = (time[i - 1], time[i]);
+        let (x0, x1) = (values[i - 1], values[i]);
+        if t1 == t0 {
+            x0
+        } else {
+            x0 + (x1 - x0) * (t - t0) / (t1 - t0)
+        }
+    };
+
+    let mut total = 0.0;
+    for i in 1..time.len().min(values.len()) {
+        let a = time[i - 1].max(t_start);
+        let b = time[i].min(t_end);
+        if b > a {
+            total += (interp(i, a) + interp(i, b)) / 2.0 * (b - a);
+        }
+    }
+    total
+}
+
+/// Measures the energy of each operation in the testbench,
+/// and the leakage power during the idle time at the end of the testbench.
+///
+/// The `i`-th operation executes in cycle `i + 1`, after the clock edge that captures it.
+pub fn measure_power(data: &TransientData, tb: &TbParams) -> Result<PowerReport> {
+    let name = supply_current_signal();
+    let current = data
+        .signal(&name)
+        .ok_or_else(|| anyhow!("Unable to find signal {}", name))?;
+    // The supply current flows into the positive terminal of the source,
+    // so the power delivered to the SRAM is `-vdd * i`.
+    let power = current.iter().map(|i| -tb.vdd * i).collect::<Vec<_>>();
+
+    let time = &data.time;
+    let period = tb.test_case.clk_period;
+    let num_ops = tb.test_case.ops.len();
+
+    let energies = tb
+        .test_case
+        .ops
+        .iter()
+        .enumerate()
+        .map(|(i, op)| {
+            let t_start = (i + 1) as f64 * period;
+            OpEnergy {
+                op: i,
+                kind: op.into(),
+                energy: integrate(time, &power, t_start, t_start + period),
+            }
+        })
+        .collect();
+
+    // Measure leakage over the second half of the idle time,
+    // once switching currents have settled.
+    let leakage_power = if tb.idle_time > 0.0 {
+        let t_idle = (num_ops + 2) as f64 * period;
+        let t_start = t_idle + tb.idle_time / 2.0;
+        let t_end = t_idle + tb.idle_time;
+        Some(integrate(time, &power, t_start, t_end) / (t_end - t_start))
+    } else {
+        None
+    };
+
+    let mut report = PowerReport {
+        vdd: tb.vdd,
+        energies,
+        read_energy: None,
+        write_energy: None,
+        write_masked_energy: None,
+        idle_energy: None,
+        leakage_power,
+    };
+    report.read_energy = report.average_energy(OpKind::Read);
+    report.write_energy = report.average_energy(OpKind::Write);
+    report.write_masked_energy = report.average_energy(OpKind::WriteMasked);
+    report.idle_energy = report.average_energy(OpKind::Idle);
+
+    Ok(report)
+}
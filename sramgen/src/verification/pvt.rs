@@ -0,0 +1,154 @@
+//! Simulation across process, voltage, and temperature (PVT) corners.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+use std::path::Path;
+
+use crate::config::tech::TechConfig;
+use crate::Result;
+
+/// Nominal supply voltage.
+pub const NOMINAL_VDD: f64 = 1.8;
+/// Nominal temperature, in degrees Celsius.
+pub const NOMINAL_TEMP: f64 = 25.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PvtCorner {
+    /// Process corner, as named in the tech configuration.
+    pub process: String,
+    /// Supply voltage.
+    pub vdd: f64,
+    /// Temperature, in degrees Celsius.
+    pub temp: f64,
+}
+
+impl PvtCorner {
+    /// The default process corner at nominal voltage and temperature.
+    pub fn nominal(tech: &TechConfig) -> Self {
+        Self {
+            process: tech.default_corner.clone(),
+            vdd: NOMINAL_VDD,
+            temp: NOMINAL_TEMP,
+        }
+    }
+
+    /// A name for this corner that can be used as a file name, eg. `ss_1p62v_100c`.
+    pub fn name(&self) -> String {
+        format!(
+            "{}_{}v_{}c",
+            self.process,
+            file_name_number(self.vdd),
+            file_name_number(self.temp)
+        )
+    }
+}
+
+impl Display for PvtCorner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} V {} C", self.process, self.vdd, self.temp)
+    }
+}
+
+fn file_name_number(x: f64) -> String {
+    x.to_string().replace('.', "p").replace('-', "m")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CornerResult {
+    pub corner: PvtCorner,
+    /// The reason the corner failed, or `None` if it passed.
+    pub error: Option<String>,
+}
+
+impl CornerResult {
+    #[inline]
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PvtReport {
+    pub results: Vec<CornerResult>,
+}
+
+/// Runs `f` at every corner, recording which corners pass.
+///
+/// A failure at one corner does not prevent the remaining corners from running.
+pub fn run_pvt_sweep(
+    corners: &[PvtCorner],
+    mut f: impl FnMut(&PvtCorner) -> Result<()>,
+) -> PvtReport {
+    let results = corners
+        .iter()
+        .map(|corner| CornerResult {
+            corner: corner.clone(),
+            error: f(corner).err().map(|e| format!("{e:#}")),
+        })
+        .collect();
+    PvtReport { results }
+}
+
+impl PvtReport {
+    pub fn failures(&self) -> impl Iterator<Item = &CornerResult> {
+        self.results.iter().filter(|r| !r.passed())
+    }
+
+    /// Formats the results as a table with one row per process corner
+    /// and one column per voltage and temperature.
+    pub fn matrix(&self) -> String {
+        let mut processes: Vec<&str> = Vec::new();
+        let mut conditions: Vec<(f64, f64)> = Vec::new();
+        for r in self.results.iter() {
+            if !processes.contains(&r.corner.process.as_str()) {
+                processes.push(&r.corner.process);
+            }
+            if !conditions.contains(&(r.corner.vdd, r.corner.temp)) {
+                conditions.push((r.corner.vdd, r.corner.temp));
+            }
+        }
+
+        let headers = conditions
+            .iter()
+            .map(|(vdd, temp)| format!("{vdd} V {temp} C"))
+            .collect::<Vec<_>>();
+        let first_width = processes.iter().map(|p| p.len()).max().unwrap_or(0);
+
+        let mut out = String::new();
+        write!(&mut out, "{:first_width$}", "").unwrap();
+        for header in headers.iter() {
+            write!(&mut out, " | {header}").unwrap();
+        }
+        writeln!(&mut out).unwrap();
+
+        for process in processes {
+            write!(&mut out, "{process:first_width$}").unwrap();
+            for ((vdd, temp), header) in conditions.iter().zip(headers.iter()) {
+                let status = self
+                    .results
+                    .iter()
+                    .find(|r| {
+                        r.corner.process == process
+                            && r.corner.vdd == *vdd
+                            && r.corner.temp == *temp
+                    })
+                    .map(|r| if r.passed() { "PASS" } else { "FAIL" })
+                    .unwrap_or("-");
+                write!(&mut out, " | {status:width$}", width = header.len()).unwrap();
+            }
+            writeln!(&mut out).unwrap();
+        }
+
+        out
+    }
+
+    /// Saves the report to `dir` as `pvt.json`, along with the
+    /// pass/fail matrix in `pvt.txt`.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("pvt.json"), serde_json::to_string_pretty(self)?)?;
+        std::fs::write(dir.join("pvt.txt"), self.matrix())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Randomized SRAM test cases.
+
+use anyhow::bail;
+use derive_builder::Builder;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::patterns::PatternParams;
+use crate::verification::Op;
+use crate::Result;
+
+/// Relative frequencies of each kind of operation.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpWeights {
+    pub read: f64,
+    pub write: f64,
+    pub write_masked: f64,
+    /// Idle cycles are not generated by default.
+    #[serde(default)]
+    pub idle: f64,
+}
+
+impl Default for OpWeights {
+    fn default() -> Self {
+        Self {
+            read: 1.0,
+            write: 1.0,
+            write_masked: 1.0,
+            idle: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Builder, Serialize, Deserialize)]
+pub struct RandomParams {
+    /// Seed of the random number generator.
+    ///
+    /// The same seed always generates the same operations.
+    pub seed: u64,
+    /// Number of operations to generate.
+    #[builder(default = "100")]
+    pub num_ops: usize,
+    #[builder(default)]
+    pub weights: OpWeights,
+}
+
+impl RandomParams {
+    #[inline]
+    pub fn builder() -> RandomParamsBuilder {
+        RandomParamsBuilder::default()
+    }
+}
+
+/// Generates random operations.
+///
+/// Reads and masked writes only target addresses that have already been written,
+/// since their results would otherwise be undefined.
+/// Until an address has been written, every operation is a write.
+/// Masked writes are only generated if the SRAM has more than one write mask lane.
+pub fn generate(params: &RandomParams, pattern: &PatternParams) -> Result<Vec<Op>> {
+    let OpWeights {
+        read,
+        write,
+        mut write_masked,
+        idle,
+    } = params.weights;
+    if pattern.wmask_width <= 1 {
+        write_masked = 0.0;
+    }
+    if write <= 0.0 {
+        bail!("The write weight must be positive");
+    }
+    let kinds = WeightedIndex::new([read, write, write_masked, idle])?;
+
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let mut written = Vec::new();
+    let mut ops = Vec::with_capacity(params.num_ops);
+
+    let random_bits = |rng: &mut StdRng, width: usize| {
+        BitSignal::from_vec((0..width).map(|_| rng.gen()).collect())
+    };
+
+    for _ in 0..params.num_ops {
+        let kind = if written.is_empty() {
+            1
+        } else {
+            kinds.sample(&mut rng)
+        };
+        let op = match kind {
+            0 => Op::Read {
+                addr: written[rng.gen_range(0..written.len())].clone(),
+            },
+            1 => {
+                let addr = random_bits(&mut rng, pattern.addr_width);
+                if !written.contains(&addr) {
+                    written.push(addr.clone());
+                }
+                Op::Write {
+                    addr,
+                    data: random_bits(&mut rng, pattern.data_width),
+                }
+            }
+            2 => Op::WriteMasked {
+                addr: written[rng.gen_range(0..written.len())].clone(),
+                data: random_bits(&mut rng, pattern.data_width),
+                mask: random_bits(&mut rng, pattern.wmask_width),
+            },
+            _ => Op::Idle,
+        };
+        ops.push(op);
+    }
+
+    Ok(ops)
+}
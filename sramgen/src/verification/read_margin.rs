@@ -0,0 +1,687 @@
+//! Monte Carlo analysis of the read margin of a single SRAM column.
+//!
+//! Each testbench contains the bitcells sharing one sense amp, along with their
+//! precharge devices and read muxes. The sense amp is enabled a fixed delay after
+//! the wordline rises, as it is by the replica timing control, and the testbench
+//! is simulated repeatedly with device mismatch enabled.
+//!
+//! Every run reads a 1 from the first row of the first column while every other
+//! bitcell stores a 0, so that leakage through the unselected bitcells opposes the read.
+
+use anyhow::{anyhow, bail, Context};
+use psf_ascii::parser::transient::TransientData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::path::{Path, PathBuf};
+use vlsir::circuit::{Instance, Module, Package, Signal};
+
+use crate::config::bitcell_array::{BitcellArrayDummyParams, BitcellArrayParams};
+use crate::config::mux::ReadMuxArrayParams;
+use crate::config::precharge::PrechargeArrayParams;
+use crate::config::sense_amp::SenseAmpArrayParams;
+use crate::config::sram::SramParams;
+use crate::config::tech::TechConfig;
+use crate::schematic::bitcell_array::bitcell_array;
+use crate::schematic::conns::{
+    bus, conn_map, port_inout, port_input, port_output, sig_conn, signal,
+};
+use crate::schematic::local_reference;
+use crate::schematic::mux::read::read_mux_array;
+use crate::schematic::netlist::netlist_package;
+use crate::schematic::precharge::precharge_array;
+use crate::schematic::sense_amp::sense_amp_array;
+use crate::schematic::sram::{bitcell_array_border, precharge_params, read_mux_params};
+use crate::schematic::NetlistFormat;
+use crate::tech::all_external_modules;
+use crate::verification::netlist::{
+    write_include, write_lib, write_netlist, write_pwl, DUT_INSTANCE,
+};
+use crate::verification::ngspice::{run_ngspice, NgspiceParams};
+use crate::verification::power::VDD_SOURCE;
+use crate::verification::pvt::PvtCorner;
+use crate::verification::utils::to_logic;
+use crate::verification::waveform::Waveform;
+use crate::{bus_bit, Result, LIB_PATH};
+
+/// The directory within the SRAM's work directory to which read margin results are saved.
+pub const READ_MARGIN_DIR: &str = "read_margin";
+
+/// How long the bitlines are precharged before the wordline rises.
+pub const PRECHARGE_TIME: f64 = 2e-9;
+
+/// How long the sense amp is given to resolve before its outputs are checked.
+pub const RESOLVE_TIME: f64 = 1e-9;
+
+/// Name of the column subcircuit.
+pub const COLUMN_NAME: &str = "read_column";
+
+/// Number of bins in the histogram of bitline splits.
+const HISTOGRAM_BINS: usize = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadMarginParams {
+    /// Number of Monte Carlo runs at each corner.
+    pub runs: usize,
+    /// Random seed of the first run.
+    ///
+    /// Run `i` uses seed `seed + i`.
+    pub seed: u64,
+    /// Time from the wordline rising to the sense amp enable rising.
+    pub sae_delay: f64,
+    /// Rise time of the precharge, wordline, and sense amp enable signals.
+    pub tr: f64,
+}
+
+impl Default for ReadMarginParams {
+    fn default() -> Self {
+        Self {
+            runs: 100,
+            seed: 1,
+            sae_delay: 1e-9,
+            tr: 50e-12,
+        }
+    }
+}
+
+impl ReadMarginParams {
+    /// Time at which the wordline starts to rise.
+    fn t_wl(&self) -> f64 {
+        PRECHARGE_TIME + self.tr
+    }
+
+    /// Time at which the sense amp enable starts to rise.
+    fn t_sae(&self) -> f64 {
+        self.t_wl() + self.sae_delay
+    }
+
+    /// Time at which the bitline split is measured,
+    /// when the sense amp enable crosses 50% of VDD.
+    pub fn t_sense(&self) -> f64 {
+        self.t_sae() + self.tr / 2.0
+    }
+
+    /// Time at which the simulation ends and the sense amp outputs are checked.
+    pub fn t_end(&self) -> f64 {
+        self.t_sae() + self.tr + RESOLVE_TIME
+    }
+}
+
+/// The ports of the column subcircuit, in order.
+fn column_ports(sram: &SramParams) -> Vec<Signal> {
+    vec![
+        signal("vdd"),
+        signal("vss"),
+        bus("wl", sram.rows as i64),
+        signal("pc_b"),
+        bus("sel_b", sram.mux_ratio as i64),
+        signal("sense_en"),
+        bus("bl", sram.mux_ratio as i64),
+        bus("br", sram.mux_ratio as i64),
+        signal("bl_read"),
+        signal("br_read"),
+        signal("dout"),
+        signal("dout_b"),
+    ]
+}
+
+/// The bitcells sharing a single sense amp, along with the precharge devices,
+/// read muxes, and sense amp serving them.
+///
+/// Uses the same devices and dummy rows as the SRAM described by `sram`.
+/// The top level module is named [`COLUMN_NAME`].
+pub fn read_column(sram: &SramParams) -> Vec<Module> {
+    let mux_ratio = sram.mux_ratio;
+    let (_, dummy_params) = bitcell_array_border(sram.control);
+
+    let mut modules = vec![bitcell_array(&BitcellArrayParams {
+        name: "bitcell_column".to_string(),
+        rows: sram.rows,
+        cols: mux_ratio,
+        replica_cols: 0,
+        dummy_params: BitcellArrayDummyParams {
+            left: 0,
+            right: 0,
+            ..dummy_params
+        },
+    })];
+    modules.extend(precharge_array(&PrechargeArrayParams {
+        name: "precharge_array".to_string(),
+        width: mux_ratio,
+        flip_toggle: false,
+        instance_params: precharge_params(),
+    }));
+    modules.extend(read_mux_array(&ReadMuxArrayParams {
+        name: "read_mux_array".to_string(),
+        mux_params: read_mux_params(),
+        cols: mux_ratio,
+        mux_ratio,
+    }));
+    modules.push(sense_amp_array(&SenseAmpArrayParams {
+        name: "sense_amp_array".to_string(),
+        width: 1,
+        spacing: None,
+    }));
+
+    let ports = column_ports(sram);
+    let sig = |name: &str| {
+        ports
+            .iter()
+            .find(|s| s.name == name)
+            .expect("column port should exist")
+    };
+
+    let mut m = Module {
+        name: COLUMN_NAME.to_string(),
+        ports: ports
+            .iter()
+            .map(|s| match s.name.as_str() {
+                "vdd" | "vss" | "bl" | "br" | "bl_read" | "br_read" => port_inout(s),
+                "dout" | "dout_b" => port_output(s),
+                _ => port_input(s),
+            })
+            .collect(),
+        signals: vec![],
+        instances: vec![],
+        parameters: vec![],
+    };
+
+    let mut conns = HashMap::new();
+    conns.insert("bl", sig_conn(sig("bl")));
+    conns.insert("br", sig_conn(sig("br")));
+    conns.insert("wl", sig_conn(sig("wl")));
+    conns.insert("vdd", sig_conn(sig("vdd")));
+    conns.insert("vss", sig_conn(sig("vss")));
+    conns.insert("vnb", sig_conn(sig("vss")));
+    conns.insert("vpb", sig_conn(sig("vdd")));
+    m.instances.push(Instance {
+        name: "bitcells".to_string(),
+        module: local_reference("bitcell_column"),
+        connections: conn_map(conns),
+        parameters: HashMap::new(),
+    });
+
+    let mut conns = HashMap::new();
+    conns.insert("vdd", sig_conn(sig("vdd")));
+    conns.insert("en_b", sig_conn(sig("pc_b")));
+    conns.insert("bl", sig_conn(sig("bl")));
+    conns.insert("br", sig_conn(sig("br")));
+    m.instances.push(Instance {
+        name: "precharge_array".to_string(),
+        module: local_reference("precharge_array"),
+        connections: conn_map(conns),
+        parameters: HashMap::new(),
+    });
+
+    let mut conns = HashMap::new();
+    conns.insert("vdd", sig_conn(sig("vdd")));
+    conns.insert("bl", sig_conn(sig("bl")));
+    conns.insert("br", sig_conn(sig("br")));
+    conns.insert("bl_out", sig_conn(sig("bl_read")));
+    conns.insert("br_out", sig_conn(sig("br_read")));
+    conns.insert("sel_b", sig_conn(sig("sel_b")));
+    m.instances.push(Instance {
+        name: "read_mux_array".to_string(),
+        module: local_reference("read_mux_array"),
+        connections: conn_map(conns),
+        parameters: HashMap::new(),
+    });
+
+    let mut conns = HashMap::new();
+    conns.insert("vdd", sig_conn(sig("vdd")));
+    conns.insert("vss", sig_conn(sig("vss")));
+    conns.insert("clk", sig_conn(sig("sense_en")));
+    conns.insert("bl", sig_conn(sig("bl_read")));
+    conns.insert("br", sig_conn(sig("br_read")));
+    conns.insert("data", sig_conn(sig("dout")));
+    conns.insert("data_b", sig_conn(sig("dout_b")));
+    m.instances.push(Instance {
+        name: "sense_amp_array".to_string(),
+        module: local_reference("sense_amp_array"),
+        connections: conn_map(conns),
+        parameters: HashMap::new(),
+    });
+
+    modules.push(m);
+    modules
+}
+
+/// A single Monte Carlo run of the read margin testbench.
+struct ReadMarginTb<'a> {
+    sram: &'a SramParams,
+    params: &'a ReadMarginParams,
+    corner: &'a PvtCorner,
+    includes: &'a [String],
+    libs: &'a [(String, String)],
+    column_netlist: &'a Path,
+    seed: u64,
+}
+
+impl<'a> ReadMarginTb<'a> {
+    fn netlist(&self) -> Result<String> {
+        let Self {
+            sram,
+            params,
+            corner,
+            ..
+        } = *self;
+        let vdd = corner.vdd;
+        let t_end = params.t_end();
+        let gnd_net = "vss";
+
+        let mut out = String::new();
+        writeln!(
+            &mut out,
+            "* SRAM22 generated read margin testbench for {}",
+            sram.name
+        )?;
+        writeln!(&mut out, "* Monte Carlo seed {}", self.seed)?;
+        writeln!(&mut out, ".option seed={}", self.seed)?;
+        writeln!(&mut out, ".param t_end={t_end}")?;
+        writeln!(&mut out, ".tran 1.00e-12 {{t_end}}\n")?;
+
+        for (path, section) in self.libs.iter() {
+            write_lib(&mut out, path, section)?;
+        }
+        for include in self.includes.iter() {
+            write_include(&mut out, include)?;
+        }
+        for include in [
+            self.column_netlist.to_path_buf(),
+            PathBuf::from(LIB_PATH).join("sram_sp_cell/sky130_fd_bd_sram__sram_sp_cell.spice"),
+            PathBuf::from(LIB_PATH).join("sramgen_sp_sense_amp/sramgen_sp_sense_amp.lvs.spice"),
+        ] {
+            write_include(&mut out, include)?;
+        }
+        writeln!(&mut out)?;
+
+        writeln!(&mut out, "{DUT_INSTANCE}")?;
+        for port in column_ports(sram) {
+            if port.width == 1 {
+                writeln!(&mut out, "+ {}", port.name)?;
+            } else {
+                for i in (0..port.width as usize).rev() {
+                    writeln!(&mut out, "+ {}", bus_bit(&port.name, i))?;
+                }
+            }
+        }
+        writeln!(&mut out, "+ {COLUMN_NAME}\n")?;
+
+        writeln!(&mut out, "{} vdd {} {}", VDD_SOURCE, gnd_net, vdd)?;
+        writeln!(&mut out, "Vvss {} 0 0\n", gnd_net)?;
+
+        // Each control signal rises once, after being held low.
+        let rise = |t: f64| {
+            let mut w = Waveform::with_initial_value(0f64);
+            w.push(t, 0f64);
+            w.push(t + params.tr, vdd);
+            w.push(t_end, vdd);
+            w
+        };
+        write_pwl(&mut out, "pc_b", gnd_net, &rise(PRECHARGE_TIME))?;
+        write_pwl(&mut out, "sense_en", gnd_net, &rise(params.t_sae()))?;
+        write_pwl(&mut out, &bus_bit("wl", 0), gnd_net, &rise(params.t_wl()))?;
+        for i in 1..sram.rows {
+            let net = bus_bit("wl", i);
+            writeln!(&mut out, "V{net} {net} {gnd_net} 0")?;
+        }
+        // Select the first column.
+        for i in 0..sram.mux_ratio {
+            let net = bus_bit("sel_b", i);
+            writeln!(
+                &mut out,
+                "V{net} {net} {gnd_net} {}",
+                if i == 0 { 0.0 } else { vdd }
+            )?;
+        }
+        writeln!(&mut out)?;
+
+        // Store a 1 in the accessed bitcell and a 0 in every other bitcell.
+        let (_, dummy_params) = bitcell_array_border(sram.control);
+        for row in 0..sram.rows {
+            for col in 0..sram.mux_ratio {
+                let cell = format!(
+                    "{DUT_INSTANCE}.xbitcells.xbitcell_{}_{}",
+                    row + dummy_params.bottom,
+                    col
+                );
+                let (q, qb) = if row == 0 && col == 0 {
+                    (vdd, 0.0)
+                } else {
+                    (0.0, vdd)
+                };
+                writeln!(&mut out, ".ic v({cell}.q)={q} v({cell}.qb)={qb}")?;
+            }
+        }
+        writeln!(&mut out)?;
+
+        for net in ["bl_read", "br_read", "sense_en", "dout", "dout_b"] {
+            writeln!(&mut out, ".save v({net})")?;
+        }
+        writeln!(&mut out, ".temp {}", corner.temp)?;
+        writeln!(&mut out, ".end")?;
+
+        Ok(out)
+    }
+
+    fn measure(&self, data: &TransientData) -> Result<ReadMarginRun> {
+        let vdd = self.corner.vdd;
+        let signal = |net: &str| {
+            let name = format!("v({net})");
+            data.signal(&name)
+                .ok_or_else(|| anyhow!("Unable to find signal {}", name))
+        };
+
+        let t = self.params.t_sense();
+        let idx = data
+            .idx_before_time(t)
+            .ok_or_else(|| anyhow!("Time {} was out of simulation range", t))?;
+        let split = signal("bl_read")?[idx] - signal("br_read")?[idx];
+
+        let last = data
+            .time
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("Simulation produced no data"))?;
+        let dout = to_logic(signal("dout")?[last], vdd);
+        let dout_b = to_logic(signal("dout_b")?[last], vdd);
+
+        Ok(ReadMarginRun {
+            seed: self.seed,
+            split,
+            passed: dout == Some(true) && dout_b == Some(false),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadMarginRun {
+    /// Random seed of the run.
+    pub seed: u64,
+    /// Differential `bl - br` at the sense amp input when the sense amp was enabled.
+    pub split: f64,
+    /// Whether the sense amp resolved to the stored value.
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadMarginReport {
+    pub corner: PvtCorner,
+    /// Time from the wordline rising to the sense amp enable rising.
+    pub sae_delay: f64,
+    pub runs: Vec<ReadMarginRun>,
+    /// Mean bitline split at sense amp enable.
+    pub mean_split: f64,
+    /// Sample standard deviation of the bitline split at sense amp enable.
+    pub std_split: f64,
+    /// Smallest bitline split at sense amp enable.
+    pub min_split: f64,
+    /// Number of runs in which the sense amp resolved incorrectly.
+    pub failures: usize,
+    /// Fraction of runs in which the sense amp resolved incorrectly.
+    pub failure_rate: f64,
+    /// Probability that the bitline split at sense amp enable is not positive,
+    /// assuming that it is normally distributed.
+    ///
+    /// Estimates failure probabilities too small to observe in a practical
+    /// number of runs, but does not account for sense amp offset.
+    pub estimated_failure_probability: f64,
+}
+
+impl ReadMarginReport {
+    pub fn new(corner: PvtCorner, sae_delay: f64, runs: Vec<ReadMarginRun>) -> Result<Self> {
+        if runs.is_empty() {
+            bail!("At least one Monte Carlo run is required");
+        }
+        let n = runs.len() as f64;
+        let mean_split = runs.iter().map(|r| r.split).sum::<f64>() / n;
+        let std_split = if runs.len() > 1 {
+            (runs
+                .iter()
+                .map(|r| (r.split - mean_split).powi(2))
+                .sum::<f64>()
+                / (n - 1.0))
+                .sqrt()
+        } else {
+            0.0
+        };
+        let min_split = runs.iter().map(|r| r.split).fold(f64::INFINITY, f64::min);
+        let failures = runs.iter().filter(|r| !r.passed).count();
+
+        let estimated_failure_probability = if std_split > 0.0 {
+            normal_cdf(-mean_split / std_split)
+        } else if mean_split > 0.0 {
+            0.0
+        } else {
+            1.0
+        };
+
+        Ok(Self {
+            corner,
+            sae_delay,
+            mean_split,
+            std_split,
+            min_split,
+            failures,
+            failure_rate: failures as f64 / n,
+            estimated_failure_probability,
+            runs,
+        })
+    }
+
+    #[inline]
+    pub fn passed(&self) -> bool {
+        self.failures == 0
+    }
+
+    /// Counts the runs whose bitline split falls in each of [`HISTOGRAM_BINS`]
+    /// equal bins between the smallest and largest split.
+    ///
+    /// Returns the lower edge of each bin along with its count.
+    pub fn histogram(&self) -> Vec<(f64, usize)> {
+        let max_split = self
+            .runs
+            .iter()
+            .map(|r| r.split)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let width = (max_split - self.min_split) / HISTOGRAM_BINS as f64;
+        if width <= 0.0 {
+            return vec![(self.min_split, self.runs.len())];
+        }
+
+        let mut counts = vec![0; HISTOGRAM_BINS];
+        for r in self.runs.iter() {
+            let bin = ((r.split - self.min_split) / width) as usize;
+            counts[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (self.min_split + i as f64 * width, count))
+            .collect()
+    }
+
+    /// Saves the report to `dir` as `read_margin.json` and `read_margin.txt`.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(
+            dir.join("read_margin.json"),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        std::fs::write(dir.join("read_margin.txt"), self.to_string())?;
+        Ok(())
+    }
+}
+
+impl Display for ReadMarginReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Read margin at {}, with the sense amp enabled {:.3} ns after the wordline:",
+            self.corner,
+            self.sae_delay * 1e9
+        )?;
+        writeln!(f, "  {:32}{}", "Runs:", self.runs.len())?;
+        writeln!(
+            f,
+            "  {:32}mean {:.1} mV, std. dev. {:.1} mV, min {:.1} mV",
+            "Bitline split at SAE:",
+            self.mean_split * 1e3,
+            self.std_split * 1e3,
+            self.min_split * 1e3
+        )?;
+        writeln!(
+            f,
+            "  {:32}{} ({:.1}%)",
+            "Read failures:",
+            self.failures,
+            self.failure_rate * 100.0
+        )?;
+        writeln!(
+            f,
+            "  {:32}{:.3e}",
+            "Estimated failure probability:", self.estimated_failure_probability
+        )?;
+
+        let histogram = self.histogram();
+        let max_count = histogram.iter().map(|(_, c)| *c).max().unwrap_or(0).max(1);
+        writeln!(f, "  Distribution of bitline split at SAE (mV):")?;
+        for (lower, count) in histogram {
+            let bar = "#".repeat((40 * count + max_count - 1) / max_count);
+            writeln!(f, "    {:>8.1} | {bar} {count}", lower * 1e3)?;
+        }
+        Ok(())
+    }
+}
+
+/// The cumulative distribution function of the standard normal distribution.
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// The complementary error function, with a relative error below 1.2e-7.
+///
+/// Uses the Chebyshev approximation from Numerical Recipes.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let ans = t
+        * (-z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+            .exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+/// Netlists the column testbench shared by every Monte Carlo run.
+fn write_column_netlist(sram: &SramParams, path: impl AsRef<Path>) -> Result<()> {
+    let format = NetlistFormat::NgSpice;
+    let pkg = Package {
+        domain: format!("sramgen_{COLUMN_NAME}"),
+        desc: "Sramgen generated cells".to_string(),
+        modules: read_column(sram),
+        ext_modules: all_external_modules(format),
+    };
+    write_netlist(path, &netlist_package(&pkg, format)?)
+}
+
+/// Runs a Monte Carlo analysis of the read margin of the SRAM described by `sram`
+/// at each of the given corners, using ngspice and the mismatch models
+/// in the tech configuration.
+///
+/// Each corner is simulated in its own directory within `work_dir/read_margin`.
+/// Returns the report for each corner, or an error, including the reports of the failing
+/// corners, if the sense amp resolved incorrectly in any run.
+pub fn run_read_margin(
+    sram: &SramParams,
+    tech: &TechConfig,
+    corners: &[PvtCorner],
+    work_dir: impl AsRef<Path>,
+    params: &ReadMarginParams,
+) -> Result<Vec<ReadMarginReport>> {
+    if params.runs == 0 {
+        bail!("At least one Monte Carlo run is required");
+    }
+    if params.sae_delay <= 0.0 {
+        bail!(
+            "Sense amp enable delay must be positive; got {}",
+            params.sae_delay
+        );
+    }
+    let models = &tech.ngspice;
+    if models.mismatch_libs.is_empty() {
+        bail!("No ngspice mismatch models specified in the tech configuration");
+    }
+
+    let dir = work_dir.as_ref().join(READ_MARGIN_DIR);
+    let column_netlist = dir.join(format!("{COLUMN_NAME}.spice"));
+    write_column_netlist(sram, &column_netlist)?;
+
+    let mut reports = Vec::with_capacity(corners.len());
+    for corner in corners {
+        tech.check_corner(&corner.process)?;
+        let includes = models.includes(&corner.process)?;
+        let libs = models.mismatch_libs(&corner.process)?;
+        let corner_dir = dir.join(corner.name());
+
+        let mut runs = Vec::with_capacity(params.runs);
+        for i in 0..params.runs {
+            let tb = ReadMarginTb {
+                sram,
+                params,
+                corner,
+                includes: &includes,
+                libs: &libs,
+                column_netlist: &column_netlist,
+                seed: params.seed + i as u64,
+            };
+            let run_dir = corner_dir.join(format!("run_{i}"));
+            let netlist_path = run_dir.join("read_margin_tb.sp");
+            write_netlist(&netlist_path, &tb.netlist()?)?;
+            let data = run_ngspice(&NgspiceParams {
+                work_dir: run_dir,
+                spice_path: netlist_path,
+            })
+            .with_context(|| format!("Error in Monte Carlo run {i} at corner {corner}"))?;
+            runs.push(tb.measure(&data)?);
+        }
+
+        let report = ReadMarginReport::new(corner.clone(), params.sae_delay, runs)?;
+        report.save(&corner_dir)?;
+        reports.push(report);
+    }
+
+    std::fs::write(
+        dir.join("read_margin.json"),
+        serde_json::to_string_pretty(&reports)?,
+    )?;
+
+    let failures = reports.iter().filter(|r| !r.passed()).collect::<Vec<_>>();
+    if !failures.is_empty() {
+        bail!(
+            "Read failures found by Monte Carlo analysis at {}\n\n{}",
+            failures
+                .iter()
+                .map(|r| format!("{} ({} of {} runs)", r.corner, r.failures, r.runs.len()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            failures
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    Ok(reports)
+}
@@ -0,0 +1,526 @@
+//! Static noise margin (SNM) analysis of SRAM bitcells.
+//!
+//! The latch of a bitcell is split into its two inverters, each simulated together with
+//! the access transistor on its output. Sweeping the input of each half traces the
+//! butterfly curve, and the noise margin is the side of the largest square that fits
+//! inside its smaller lobe. Margins are found while the bitcell holds its value,
+//! while it is read, and while a 0 is written over a stored 1.
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::tech::TechConfig;
+use crate::verification::netlist::{write_include, write_lib, write_netlist};
+use crate::verification::ngspice::{run_ngspice_dc, NgspiceParams};
+use crate::verification::power::VDD_SOURCE;
+use crate::verification::pvt::PvtCorner;
+use crate::{Result, LIB_PATH};
+
+/// The directory within the SRAM's work directory to which SNM results are saved.
+pub const SNM_DIR: &str = "snm";
+
+/// Number of steps in the DC sweep of each half of the latch.
+pub const SWEEP_STEPS: usize = 500;
+
+/// Number of points at which the lobes of the butterfly curve are measured.
+const SAMPLES: usize = 1_000;
+
+/// The bitcells that can be analyzed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Bitcell {
+    SpCell,
+    SpCellOpt1a,
+}
+
+impl Bitcell {
+    pub const ALL: [Bitcell; 2] = [Bitcell::SpCell, Bitcell::SpCellOpt1a];
+
+    /// The name of the bitcell in the SKY130 SRAM library.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Self::SpCell => "sky130_fd_bd_sram__sram_sp_cell",
+            Self::SpCellOpt1a => "sky130_fd_bd_sram__sram_sp_cell_opt1a",
+        }
+    }
+
+    /// The name of the subcircuit in the bitcell's SPICE netlist.
+    fn subckt(&self) -> &'static str {
+        match *self {
+            Self::SpCell => "sram_sp_cell",
+            Self::SpCellOpt1a => "sky130_fd_bd_sram__sram_sp_cell_opt1a",
+        }
+    }
+
+    fn spice_path(&self) -> PathBuf {
+        let dir = match *self {
+            Self::SpCell => "sram_sp_cell",
+            Self::SpCellOpt1a => "sram_sp_cell_opt1a",
+        };
+        PathBuf::from(LIB_PATH).join(format!("{dir}/{}.spice", self.name()))
+    }
+
+    /// The storage nodes of the bitcell, as `(q, q_b)`.
+    ///
+    /// `q` is connected to the bitline `BL` while the wordline is high.
+    fn storage_nodes(&self) -> (&'static str, &'static str) {
+        match *self {
+            Self::SpCell => ("Q", "QB"),
+            Self::SpCellOpt1a => ("Q", "Q_bar"),
+        }
+    }
+}
+
+impl Display for Bitcell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The operation during which a noise margin is measured.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SnmMode {
+    /// The wordline is low.
+    Hold,
+    /// The wordline is high and both bitlines are precharged to VDD.
+    Read,
+    /// The wordline is high, `BL` is low, and `BR` is high, writing a 0 over a stored 1.
+    Write,
+}
+
+impl SnmMode {
+    pub const ALL: [SnmMode; 3] = [SnmMode::Hold, SnmMode::Read, SnmMode::Write];
+
+    /// The voltages of the wordline, `BL`, and `BR`.
+    fn bias(&self, vdd: f64) -> (f64, f64, f64) {
+        match *self {
+            Self::Hold => (0.0, vdd, vdd),
+            Self::Read => (vdd, vdd, vdd),
+            Self::Write => (vdd, 0.0, vdd),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            Self::Hold => "hold",
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// Returns the ports of `subckt` if `line` begins its definition.
+fn subckt_header<'a>(line: &'a str, subckt: &str) -> Option<Vec<&'a str>> {
+    let mut tokens = line.split_whitespace();
+    let keyword = tokens.next()?;
+    if keyword.eq_ignore_ascii_case(".subckt") && tokens.next() == Some(subckt) {
+        Some(tokens.collect())
+    } else {
+        None
+    }
+}
+
+/// The ports of the subcircuit `subckt` in `netlist`, in order.
+fn subckt_ports<'a>(netlist: &'a str, subckt: &str) -> Result<Vec<&'a str>> {
+    netlist
+        .lines()
+        .find_map(|line| subckt_header(line, subckt))
+        .ok_or_else(|| anyhow!("Unable to find subcircuit `{}`", subckt))
+}
+
+/// Extracts one half of the latch of the bitcell subcircuit `subckt` in `netlist`.
+///
+/// The half consists of every device with a source or drain on the storage node `output`.
+/// The returned subcircuit is named `name`, and its ports are `IN` (the storage node `input`),
+/// `OUT` (the storage node `output`), and the ports of the bitcell, in order.
+pub fn half_cell(
+    netlist: &str,
+    subckt: &str,
+    input: &str,
+    output: &str,
+    name: &str,
+) -> Result<String> {
+    let ports = subckt_ports(netlist, subckt)?;
+    let lines = netlist
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| subckt_header(line, subckt).is_none())
+        .skip(1);
+
+    let rename = |node: &str| {
+        if node.eq_ignore_ascii_case(output) {
+            "OUT".to_string()
+        } else if node.eq_ignore_ascii_case(input) {
+            "IN".to_string()
+        } else {
+            node.to_string()
+        }
+    };
+
+    let mut out = String::new();
+    writeln!(&mut out, ".subckt {name} IN OUT {}", ports.join(" "))?;
+    let mut devices = 0;
+    for line in lines {
+        if line.to_lowercase().starts_with(".ends") {
+            writeln!(&mut out, ".ends")?;
+            if devices == 0 {
+                bail!(
+                    "Subcircuit `{}` has no devices driving `{}`",
+                    subckt,
+                    output
+                );
+            }
+            return Ok(out);
+        }
+        if line.is_empty() || line.starts_with('*') {
+            continue;
+        }
+        if line.starts_with('+') {
+            bail!("Continuation lines in bitcell netlists are not supported");
+        }
+
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        let is_device = tokens[0]
+            .to_lowercase()
+            .starts_with(|c| c == 'x' || c == 'm');
+        if tokens.len() < 6 || !is_device {
+            bail!("Unexpected device in bitcell netlist: {}", line);
+        }
+        let (drain, source) = (tokens[1], tokens[3]);
+        if drain.eq_ignore_ascii_case(output) || source.eq_ignore_ascii_case(output) {
+            let nodes = tokens[1..5].iter().map(|n| rename(n)).collect::<Vec<_>>();
+            writeln!(
+                &mut out,
+                "{} {} {}",
+                tokens[0],
+                nodes.join(" "),
+                tokens[5..].join(" ")
+            )?;
+            devices += 1;
+        }
+    }
+
+    bail!("Subcircuit `{}` is not terminated by `.ends`", subckt)
+}
+
+/// The net in the testbench to which the bitcell port `port` is connected.
+fn port_net(port: &str) -> Result<&'static str> {
+    Ok(match port.to_uppercase().as_str() {
+        "BL" => "bl",
+        "BR" => "br",
+        "WL" => "wl",
+        "VDD" | "VPWR" | "VPB" => "vdd",
+        "VSS" | "VGND" | "VNB" => "vss",
+        _ => bail!("Unexpected bitcell port `{}`", port),
+    })
+}
+
+struct SnmTb<'a> {
+    bitcell: Bitcell,
+    mode: SnmMode,
+    corner: &'a PvtCorner,
+    includes: &'a [String],
+    libs: &'a [(String, String)],
+}
+
+impl<'a> SnmTb<'a> {
+    /// A testbench that sweeps the inputs of both halves of the latch together.
+    ///
+    /// The output of the half driving `q` is saved as `v(q)`,
+    /// and the output of the half driving `q_b` as `v(qb)`.
+    fn netlist(&self) -> Result<String> {
+        let cell = std::fs::read_to_string(self.bitcell.spice_path())?;
+        let subckt = self.bitcell.subckt();
+        let (q, qb) = self.bitcell.storage_nodes();
+        let vdd = self.corner.vdd;
+
+        let mut out = String::new();
+        writeln!(
+            &mut out,
+            "* SRAM22 generated {} SNM testbench for {}",
+            self.mode.name(),
+            self.bitcell
+        )?;
+        writeln!(&mut out, ".dc vin 0 {vdd} {}\n", vdd / SWEEP_STEPS as f64)?;
+
+        for (path, section) in self.libs.iter() {
+            write_lib(&mut out, path, section)?;
+        }
+        for include in self.includes.iter() {
+            write_include(&mut out, include)?;
+        }
+        writeln!(&mut out)?;
+
+        let halves = [
+            (format!("{subckt}_half_q"), qb, q, "q"),
+            (format!("{subckt}_half_qb"), q, qb, "qb"),
+        ];
+        for (name, input, output, _) in halves.iter() {
+            writeln!(
+                &mut out,
+                "{}",
+                half_cell(&cell, subckt, input, output, name)?
+            )?;
+        }
+
+        let nets = subckt_ports(&cell, subckt)?
+            .into_iter()
+            .map(port_net)
+            .collect::<Result<Vec<_>>>()?;
+        for (name, _, _, net) in halves.iter() {
+            writeln!(&mut out, "x{net} in {net} {} {name}", nets.join(" "))?;
+        }
+        writeln!(&mut out)?;
+
+        let (wl, bl, br) = self.mode.bias(vdd);
+        writeln!(&mut out, "vin in vss 0")?;
+        writeln!(&mut out, "{} vdd vss {}", VDD_SOURCE, vdd)?;
+        writeln!(&mut out, "Vvss vss 0 0")?;
+        writeln!(&mut out, "vwl wl vss {wl}")?;
+        writeln!(&mut out, "vbl bl vss {bl}")?;
+        writeln!(&mut out, "vbr br vss {br}\n")?;
+
+        writeln!(&mut out, ".save v(q) v(qb)")?;
+        writeln!(&mut out, ".temp {}", self.corner.temp)?;
+        writeln!(&mut out, ".end")?;
+
+        Ok(out)
+    }
+}
+
+/// The transfer curves of the two halves of a bitcell's latch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Butterfly {
+    /// The swept input voltage.
+    pub input: Vec<f64>,
+    /// The voltage of `q` when `q_b` is driven to `input`.
+    pub q: Vec<f64>,
+    /// The voltage of `q_b` when `q` is driven to `input`.
+    pub qb: Vec<f64>,
+}
+
+impl Butterfly {
+    /// Measures the diagonal gap between the two curves.
+    ///
+    /// The curves are rotated by 45 degrees, so that each curve maps
+    /// `u = (q - q_b) / sqrt(2)` to `v = (q + q_b) / sqrt(2)`.
+    /// Returns `(u, g)` pairs over the range of `u` covered by both curves,
+    /// where `g` is the side of the square whose diagonal spans the curves at `u`.
+    /// `g` is negative where the curve of `q_b` lies below that of `q`.
+    pub fn gaps(&self) -> Result<Vec<(f64, f64)>> {
+        let rotate = |q: f64, qb: f64| {
+            (
+                (q - qb) / std::f64::consts::SQRT_2,
+                (q + qb) / std::f64::consts::SQRT_2,
+            )
+        };
+        let mut qb_curve = self
+            .input
+            .iter()
+            .zip(self.qb.iter())
+            .map(|(&q, &qb)| rotate(q, qb))
+            .collect::<Vec<_>>();
+        let mut q_curve = self
+            .input
+            .iter()
+            .zip(self.q.iter())
+            .map(|(&qb, &q)| rotate(q, qb))
+            .collect::<Vec<_>>();
+        for curve in [&mut qb_curve, &mut q_curve] {
+            if curve.len() < 2 {
+                bail!("Butterfly curves must have at least two points");
+            }
+            curve.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+
+        let start = qb_curve[0].0.max(q_curve[0].0);
+        let stop = qb_curve.last().unwrap().0.min(q_curve.last().unwrap().0);
+        if start >= stop {
+            bail!("Butterfly curves do not overlap");
+        }
+
+        Ok((0..=SAMPLES)
+            .map(|i| {
+                let u = start + (stop - start) * i as f64 / SAMPLES as f64;
+                let g = (interpolate(&qb_curve, u) - interpolate(&q_curve, u))
+                    / std::f64::consts::SQRT_2;
+                (u, g)
+            })
+            .collect())
+    }
+
+    /// The side of the largest square that fits inside the smaller lobe of the butterfly curve.
+    ///
+    /// Not positive if the latch is not bistable.
+    pub fn static_noise_margin(&self) -> Result<f64> {
+        let gaps = self.gaps()?;
+        let max = |sign: f64| {
+            gaps.iter()
+                .map(|(_, g)| sign * g)
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        Ok(max(1.0).min(max(-1.0)))
+    }
+
+    /// The side of the smallest square that fits between the curves where `q` is high.
+    ///
+    /// Applies to curves traced while writing a 0 over a stored 1.
+    /// Negative if the stored 1 survives the write, in which case its magnitude
+    /// is the side of the largest square in the remaining lobe.
+    pub fn write_margin(&self) -> Result<f64> {
+        self.gaps()?
+            .into_iter()
+            .filter(|(u, _)| *u > 0.0)
+            .map(|(_, g)| g)
+            .reduce(f64::min)
+            .ok_or_else(|| anyhow!("Butterfly curves do not reach the stored state"))
+    }
+}
+
+/// Linearly interpolates the polyline `curve`, whose points are sorted by `x`, at `x`.
+fn interpolate(curve: &[(f64, f64)], x: f64) -> f64 {
+    let i = curve.partition_point(|p| p.0 < x).clamp(1, curve.len() - 1);
+    let (x0, y0) = curve[i - 1];
+    let (x1, y1) = curve[i];
+    if x1 == x0 {
+        return y1;
+    }
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnmResult {
+    pub bitcell: Bitcell,
+    pub corner: PvtCorner,
+    /// Static noise margin while the wordline is low.
+    pub hold_snm: f64,
+    /// Static noise margin while the bitcell is read.
+    pub read_snm: f64,
+    /// Write margin while a 0 is written over a stored 1.
+    pub write_margin: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnmReport {
+    pub results: Vec<SnmResult>,
+}
+
+impl SnmReport {
+    /// Saves the report to `dir` as `snm.json` and `snm.txt`.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("snm.json"), serde_json::to_string_pretty(self)?)?;
+        std::fs::write(dir.join("snm.txt"), self.to_string())?;
+        Ok(())
+    }
+}
+
+impl Display for SnmReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .results
+            .iter()
+            .map(|r| r.bitcell.name().len())
+            .max()
+            .unwrap_or(0);
+        writeln!(
+            f,
+            "{:width$}  {:20}  {:>10}  {:>10}  {:>12}",
+            "Bitcell", "Corner", "Hold SNM", "Read SNM", "Write margin"
+        )?;
+        for r in self.results.iter() {
+            writeln!(
+                f,
+                "{:width$}  {:20}  {:>7.1} mV  {:>7.1} mV  {:>9.1} mV",
+                r.bitcell.name(),
+                r.corner.to_string(),
+                r.hold_snm * 1e3,
+                r.read_snm * 1e3,
+                r.write_margin * 1e3
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulates the butterfly curve of `bitcell` during `mode` at `corner` using ngspice.
+pub fn simulate_butterfly(
+    bitcell: Bitcell,
+    mode: SnmMode,
+    tech: &TechConfig,
+    corner: &PvtCorner,
+    work_dir: impl AsRef<Path>,
+) -> Result<Butterfly> {
+    tech.check_corner(&corner.process)?;
+    let includes = tech.ngspice.includes(&corner.process)?;
+    let libs = tech.ngspice.libs(&corner.process)?;
+    let tb = SnmTb {
+        bitcell,
+        mode,
+        corner,
+        includes: &includes,
+        libs: &libs,
+    };
+
+    let work_dir = work_dir.as_ref();
+    let netlist_path = work_dir.join("snm_tb.sp");
+    write_netlist(&netlist_path, &tb.netlist()?)?;
+    let data = run_ngspice_dc(&NgspiceParams {
+        work_dir: work_dir.to_path_buf(),
+        spice_path: netlist_path,
+    })?;
+
+    let signal = |name: &str| {
+        data.signal(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unable to find signal {}", name))
+    };
+    let butterfly = Butterfly {
+        input: data.sweep.clone(),
+        q: signal("v(q)")?,
+        qb: signal("v(qb)")?,
+    };
+    std::fs::write(
+        work_dir.join("butterfly.json"),
+        serde_json::to_string_pretty(&butterfly)?,
+    )?;
+    Ok(butterfly)
+}
+
+/// Finds the hold SNM, read SNM, and write margin of every [`Bitcell`] at each corner.
+///
+/// Each simulation is saved to `work_dir/snm/<bitcell>/<corner>/<mode>`.
+pub fn run_snm(
+    tech: &TechConfig,
+    corners: &[PvtCorner],
+    work_dir: impl AsRef<Path>,
+) -> Result<SnmReport> {
+    let dir = work_dir.as_ref().join(SNM_DIR);
+    let mut report = SnmReport::default();
+    for bitcell in Bitcell::ALL {
+        for corner in corners {
+            let corner_dir = dir.join(bitcell.name()).join(corner.name());
+            let mut margins = Vec::with_capacity(SnmMode::ALL.len());
+            for mode in SnmMode::ALL {
+                let butterfly =
+                    simulate_butterfly(bitcell, mode, tech, corner, corner_dir.join(mode.name()))?;
+                margins.push(match mode {
+                    SnmMode::Hold | SnmMode::Read => butterfly.static_noise_margin()?,
+                    SnmMode::Write => butterfly.write_margin()?,
+                });
+            }
+            report.results.push(SnmResult {
+                bitcell,
+                corner: corner.clone(),
+                hold_snm: margins[0],
+                read_snm: margins[1],
+                write_margin: margins[2],
+            });
+        }
+    }
+
+    report.save(&dir)?;
+    Ok(report)
+}
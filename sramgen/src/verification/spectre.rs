@@ -1,4 +1,4 @@
-use anyhow::{bail, Context};
+use anyhow::bail;
 use psf_ascii::parser::transient::TransientData;
 use serde::Serialize;
 use std::fs::File;
@@ -7,13 +7,25 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use tera::Context as TeraContext;
 
-use crate::config::sram::SramParams;
-use crate::verification::bit_signal::BitSignal;
-use crate::verification::{
-    self, source_files, PortClass, PortOrder, TbParams, TestCase, VerificationTask,
-};
+use crate::verification::{Simulator, SimulatorKind};
 use crate::{Result, TEMPLATES};
 
+/// The Cadence Spectre simulator.
+pub struct Spectre;
+
+impl Simulator for Spectre {
+    fn kind(&self) -> SimulatorKind {
+        SimulatorKind::Spectre
+    }
+
+    fn run(&self, work_dir: &Path, netlist_path: &Path) -> Result<TransientData> {
+        run_spectre(&SpectreParams {
+            work_dir: work_dir.to_path_buf(),
+            spice_path: netlist_path.to_path_buf(),
+        })
+    }
+}
+
 pub struct SpectreParams {
     pub work_dir: PathBuf,
     pub spice_path: PathBuf,
@@ -27,17 +39,6 @@ pub struct SpectreGeneratedPaths {
     pub run_script_path: PathBuf,
 }
 
-pub fn sky130_includes() -> Vec<String> {
-    vec![
-        "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/MODELS/SPECTRE/s8x/Models/models.all".into(),
-        "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/MODELS/SPECTRE/s8x/Models/tt.cor".into(),
-        "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/MODELS/SPECTRE/s8x/Models/ttcell.cor".into(),
-        "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/MODELS/SPECTRE/s8x/Models/npass.pm3".into(),
-        "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/MODELS/SPECTRE/s8x/Models/npd.pm3".into(),
-        "/tools/commercial/skywater/swtech130/skywater-src-nda/s8/V2.0.1/MODELS/SPECTRE/s8x/Models/ppu.pm3".into(),
-    ]
-}
-
 pub fn run_spectre(params: &SpectreParams) -> Result<TransientData> {
     let paths = generate_paths(params);
 
@@ -99,117 +100,3 @@ fn generate_paths(params: &SpectreParams) -> SpectreGeneratedPaths {
         run_script_path: params.work_dir.join("run_sim.sh"),
     }
 }
-
-pub fn run_sram_spectre(params: &SramParams, work_dir: impl AsRef<Path>, name: &str) -> Result<()> {
-    let &SramParams {
-        wmask_width,
-        data_width,
-        addr_width,
-        ..
-    } = params;
-
-    // An alternating 64-bit sequence 0b010101...01
-    let bit_pattern1 = 0x5555555555555555u64;
-
-    // An alternating 64-bit sequence 0b101010...10
-    let bit_pattern2 = 0xAAAAAAAAAAAAAAAAu64;
-
-    let addr1 = BitSignal::zeros(addr_width);
-    let addr2 = BitSignal::ones(addr_width);
-
-    let mut ops = vec![
-        verification::Op::Write {
-            addr: addr1.clone(),
-            data: BitSignal::from_u64(bit_pattern1, data_width),
-        },
-        verification::Op::Write {
-            addr: addr2.clone(),
-            data: BitSignal::from_u64(bit_pattern2, data_width),
-        },
-        verification::Op::Read {
-            addr: addr1.clone(),
-        },
-        verification::Op::Read { addr: addr2 },
-        verification::Op::Read { addr: addr1 },
-    ];
-
-    if true {
-        for i in 0..16 {
-            let bits = (i % 2) * bit_pattern2 + (1 - (i % 2)) * bit_pattern1 + i + 1;
-            ops.push(verification::Op::Write {
-                addr: BitSignal::from_u64(i, addr_width),
-                data: BitSignal::from_u64(bits, data_width),
-            });
-        }
-        for i in 0..16 {
-            ops.push(verification::Op::Read {
-                addr: BitSignal::from_u64(i, addr_width),
-            });
-        }
-
-        if wmask_width > 1 {
-            for i in 0..16 {
-                let bits = (1 - (i % 2)) * bit_pattern2 + (i % 2) * bit_pattern1 + i + 1;
-                ops.push(verification::Op::WriteMasked {
-                    addr: BitSignal::from_u64(i, addr_width),
-                    data: BitSignal::from_u64(bits, data_width),
-                    mask: BitSignal::from_u64(bit_pattern1, wmask_width),
-                });
-            }
-            for i in 0..16 {
-                ops.push(verification::Op::Read {
-                    addr: BitSignal::from_u64(i, addr_width),
-                });
-            }
-        }
-    }
-
-    let test_case = TestCase::builder().clk_period(20e-9).ops(ops).build()?;
-
-    let mut ports = vec![
-        (PortClass::Power, PortOrder::MsbFirst),
-        (PortClass::Ground, PortOrder::MsbFirst),
-        (PortClass::Clock, PortOrder::MsbFirst),
-        (PortClass::DataIn, PortOrder::MsbFirst),
-        (PortClass::DataOut, PortOrder::MsbFirst),
-        (PortClass::WriteEnable, PortOrder::MsbFirst),
-        (PortClass::Addr, PortOrder::MsbFirst),
-    ];
-    if wmask_width > 1 {
-        ports.push((PortClass::WriteMask, PortOrder::MsbFirst));
-    }
-    let mut tb = TbParams::builder();
-    tb.test_case(test_case)
-        .sram_name(name)
-        .tr(50e-12)
-        .tf(50e-12)
-        .vdd(1.8)
-        .c_load(5e-15)
-        .data_width(data_width)
-        .addr_width(addr_width)
-        .wmask_width(wmask_width)
-        .ports(ports)
-        .clk_port("clk")
-        .write_enable_port("we")
-        .addr_port("addr")
-        .data_in_port("din")
-        .data_out_port("dout")
-        .pwr_port("vdd")
-        .gnd_port("vss")
-        .wmask_port("wmask")
-        .work_dir(std::path::PathBuf::from(work_dir.as_ref()).join("sim"))
-        .source_paths(source_files(
-            &work_dir,
-            name,
-            VerificationTask::SpectreSim,
-            params.control,
-        ));
-
-    tb.includes(crate::verification::spectre::sky130_includes());
-
-    let tb = tb.build()?;
-
-    verification::run_testbench(&tb).with_context(|| "Error simulating testbench")?;
-
-    Ok(())
-}
@@ -0,0 +1,253 @@
+//! Timing measurements on simulated SRAM waveforms.
+
+use anyhow::anyhow;
+use psf_ascii::parser::transient::TransientData;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::bus_bit;
+use crate::verification::netlist::dut_node;
+use crate::verification::utils::{to_logic, DIGITAL_REL_TOL};
+use crate::verification::{Op, TbParams};
+use crate::Result;
+
+/// Fraction of VDD at which delays are measured.
+pub const DELAY_THRESHOLD: f64 = 0.5;
+
+/// Fraction of VDD below which a bitline is considered to have been written.
+pub const WRITE_THRESHOLD: f64 = 0.1;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Edge {
+    Rising,
+    Falling,
+    Either,
+}
+
+/// Finds the times in `[t_start, t_end]` at which `values` crosses `threshold`.
+///
+/// Crossing times are linearly interpolated between samples.
+pub fn crossings(
+    time: &[f64],
+    values: &[f64],
+    threshold: f64,
+    edge: Edge,
+    t_start: f64,
+    t_end: f64,
+) -> Vec<f64> {
+    let mut out = Vec::new();
+    for i in 1..time.len().min(values.len()) {
+        let (t0, t1) = (time[i - 1], time[i]);
+        if t1 < t_start {
+            continue;
+        }
+        if t0 > t_end {
+            break;
+        }
+
+        let (x0, x1) = (values[i - 1], values[i]);
+        let rising = x0 < threshold && x1 >= threshold;
+        let falling = x0 > threshold && x1 <= threshold;
+        let matches = match edge {
+            Edge::Rising => rising,
+            Edge::Falling => falling,
+            Edge::Either => rising || falling,
+        };
+
+        if matches {
+            let t = t0 + (threshold - x0) * (t1 - t0) / (x1 - x0);
+            if t >= t_start && t <= t_end {
+                out.push(t);
+            }
+        }
+    }
+    out
+}
+
+/// Finds the time in `[t_start, t_end]` after which `values` stays at the logic level
+/// it has at `t_end`.
+///
+/// The time at which `values` enters the valid range of that logic level is linearly
+/// interpolated between samples. Returns `t_start` if `values` is at that level throughout,
+/// and `None` if `values` is not at a valid logic level at `t_end`.
+pub fn settling_time(
+    time: &[f64],
+    values: &[f64],
+    vdd: f64,
+    t_start: f64,
+    t_end: f64,
+) -> Option<f64> {
+    let n = time.len().min(values.len());
+    let last = (0..n).rev().find(|&i| time[i] <= t_end)?;
+    let level = to_logic(values[last], vdd)?;
+
+    let settled = (0..=last)
+        .rev()
+        .take_while(|&i| time[i] >= t_start)
+        .find(|&i| to_logic(values[i], vdd) != Some(level));
+    let i = match settled {
+        Some(i) => i,
+        None => return Some(t_start),
+    };
+
+    let (t0, t1) = (time[i], time[i + 1]);
+    let (x0, x1) = (values[i], values[i + 1]);
+    let threshold = if level {
+        (1.0 - DIGITAL_REL_TOL) * vdd
+    } else {
+        DIGITAL_REL_TOL * vdd
+    };
+    let t = if x1 == x0 {
+        t1
+    } else {
+        t0 + (threshold - x0) * (t1 - t0) / (x1 - x0)
+    };
+    Some(t.clamp(t0, t1))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadTiming {
+    /// Index of the read in the test case.
+    pub op: usize,
+    /// Time at which the clock edge capturing the read crosses 50% of VDD.
+    pub t_clk: f64,
+    /// Delay from the clock edge until every data output has settled at a valid logic level.
+    ///
+    /// Data outputs that hold a valid level throughout the cycle, as when a read returns
+    /// the same value as the previous one, settle at the clock edge.
+    /// `None` if a data output is not at a valid logic level by the end of the cycle.
+    pub clk_to_dout: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteTiming {
+    /// Index of the write in the test case.
+    pub op: usize,
+    /// Time at which the clock edge capturing the write crosses 50% of VDD.
+    pub t_clk: f64,
+    /// Delay from the clock edge until the last written bitline falls
+    /// below [`WRITE_THRESHOLD`].
+    ///
+    /// `None` if bitlines were not probed or no bitline was written.
+    pub write_time: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingReport {
+    pub clk_period: f64,
+    pub vdd: f64,
+    pub reads: Vec<ReadTiming>,
+    pub writes: Vec<WriteTiming>,
+    /// Worst-case clock to data output delay over all reads.
+    pub access_time: Option<f64>,
+    /// Worst-case write completion time over all writes.
+    pub write_time: Option<f64>,
+}
+
+impl TimingReport {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Measures read and write timing for each operation in the testbench.
+///
+/// Uses the same cycle numbering as the waveforms generated for `tb`:
+/// the inputs of the `i`-th operation are captured by the clock edge
+/// at the start of cycle `i + 1`.
+pub fn measure_timing(data: &TransientData, tb: &TbParams) -> Result<TimingReport> {
+    let signal = |name: &str| {
+        data.signal(name)
+            .ok_or_else(|| anyhow!("Unable to find signal {}", name))
+    };
+
+    let time = &data.time;
+    let period = tb.test_case.clk_period;
+    let threshold = DELAY_THRESHOLD * tb.vdd;
+    let write_threshold = WRITE_THRESHOLD * tb.vdd;
+    let clk = signal(&format!("v({})", tb.clk_port))?;
+
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+
+    for (i, op) in tb.test_case.ops.iter().enumerate() {
+        let t_cycle = (i + 1) as f64 * period;
+        let t_next = t_cycle + period;
+        let t_clk = crossings(
+            time,
+            clk,
+            threshold,
+            Edge::Rising,
+            t_cycle,
+            t_cycle + tb.test_case.duty_cycle * period,
+        )
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow!("Unable to find a rising clock edge at time {}", t_cycle))?;
+
+        match op {
+            Op::Read { .. } => {
+                let mut t_settled = Some(t_clk);
+                for j in 0..tb.data_width {
+                    let dout = signal(&format!("v({})", bus_bit(&tb.data_out_port, j)))?;
+                    let t = settling_time(time, dout, tb.vdd, t_clk, t_next);
+                    t_settled = match (t_settled, t) {
+                        (Some(a), Some(b)) => Some(a.max(b)),
+                        _ => None,
+                    };
+                }
+                reads.push(ReadTiming {
+                    op: i,
+                    t_clk,
+                    clk_to_dout: t_settled.map(|t| t - t_clk),
+                });
+            }
+            Op::Write { .. } | Op::WriteMasked { .. } => {
+                let mut t_last = None;
+                for j in 0..tb.bitline_width.unwrap_or_default() {
+                    for bus in ["bl", "br"] {
+                        let name = format!("v({})", dut_node(tb.simulator, &bus_bit(bus, j)));
+                        let t = crossings(
+                            time,
+                            signal(&name)?,
+                            write_threshold,
+                            Edge::Falling,
+                            t_clk,
+                            t_next,
+                        )
+                        .last()
+                        .copied();
+                        t_last = max_time(t_last, t);
+                    }
+                }
+                writes.push(WriteTiming {
+                    op: i,
+                    t_clk,
+                    write_time: t_last.map(|t| t - t_clk),
+                });
+            }
+            Op::Idle => {}
+        }
+    }
+
+    let access_time = reads.iter().filter_map(|r| r.clk_to_dout).reduce(f64::max);
+    let write_time = writes.iter().filter_map(|w| w.write_time).reduce(f64::max);
+
+    Ok(TimingReport {
+        clk_period: period,
+        vdd: tb.vdd,
+        reads,
+        writes,
+        access_time,
+        write_time,
+    })
+}
+
+fn max_time(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
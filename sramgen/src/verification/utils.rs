@@ -20,12 +20,20 @@ pub fn logical_eq(x: f64, y: f64, vdd: f64) -> bool {
 }
 
 pub fn to_bit(x: f64, vdd: f64) -> Result<bool> {
+    match to_logic(x, vdd) {
+        Some(bit) => Ok(bit),
+        None => bail!("Value was not close enough to either VDD or ground: {}", x),
+    }
+}
+
+/// Converts `x` to a logic level, returning `None` if it is not close to either rail.
+pub fn to_logic(x: f64, vdd: f64) -> Option<bool> {
     if is_logical_low(x, vdd) {
-        Ok(false)
+        Some(false)
     } else if is_logical_high(x, vdd) {
-        Ok(true)
+        Some(true)
     } else {
-        bail!("Value was not close enough to either VDD or ground: {}", x);
+        None
     }
 }
 
@@ -0,0 +1,248 @@
+//! Value change dump (VCD) export of testbench waveforms.
+//!
+//! Analog values are converted to logic levels using the same thresholds as
+//! [`to_bit`](super::utils::to_bit). Values that are not close to either rail
+//! are dumped as `x`.
+
+use anyhow::anyhow;
+use psf_ascii::parser::transient::TransientData;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::verification::utils::to_logic;
+use crate::verification::waveform::Waveform;
+use crate::verification::{TbParams, TbWaveforms};
+use crate::{bus_bit, Result};
+
+/// The VCD time unit, in seconds.
+pub const VCD_TIMESCALE: f64 = 1e-12;
+
+/// The name of the file to which each testbench saves its stimulus.
+pub const STIMULUS_VCD_FILE: &str = "stimulus.vcd";
+
+/// The name of the file to which each testbench saves its simulated ports.
+pub const SIM_VCD_FILE: &str = "sim.vcd";
+
+/// An analog signal sampled at a series of time points.
+#[derive(Debug, Copy, Clone)]
+pub struct Trace<'a> {
+    pub time: &'a [f64],
+    pub values: &'a [f64],
+}
+
+/// A named scalar or bus signal.
+#[derive(Debug, Clone)]
+pub struct VcdSignal<'a> {
+    pub name: String,
+    /// One trace per bit, least significant bit first.
+    pub bits: Vec<Trace<'a>>,
+}
+
+/// The logic value of a bit, with `None` representing `x`.
+type Logic = Option<bool>;
+
+fn logic_char(value: Logic) -> char {
+    match value {
+        Some(true) => '1',
+        Some(false) => '0',
+        None => 'x',
+    }
+}
+
+/// Generates the `i`-th VCD identifier code.
+fn identifier(mut i: usize) -> String {
+    // Identifiers may use any printable ASCII character from `!` to `~`.
+    const FIRST: u8 = b'!';
+    const NUM_CHARS: usize = (b'~' - b'!' + 1) as usize;
+    let mut id = String::new();
+    loop {
+        id.push((FIRST + (i % NUM_CHARS) as u8) as char);
+        i /= NUM_CHARS;
+        if i == 0 {
+            break;
+        }
+        i -= 1;
+    }
+    id
+}
+
+/// Finds the times at which a bit changes logic value.
+///
+/// The first entry is the value at the first time point.
+fn bit_changes(trace: Trace, vdd: f64) -> Vec<(u64, Logic)> {
+    let mut changes: Vec<(u64, Logic)> = Vec::new();
+    for (&t, &x) in trace.time.iter().zip(trace.values) {
+        let t = (t / VCD_TIMESCALE).round() as u64;
+        let value = to_logic(x, vdd);
+        match changes.last_mut() {
+            Some(last) if last.1 == value => {}
+            Some(last) if last.0 == t => last.1 = value,
+            _ => changes.push((t, value)),
+        }
+    }
+    changes
+}
+
+/// Generates the contents of a VCD file containing the given signals.
+pub fn generate_vcd(signals: &[VcdSignal], vdd: f64, scope: &str) -> Result<String> {
+    let mut out = String::new();
+    writeln!(&mut out, "$version sramgen $end")?;
+    writeln!(&mut out, "$timescale 1ps $end")?;
+    writeln!(&mut out, "$scope module {scope} $end")?;
+    for (i, signal) in signals.iter().enumerate() {
+        let width = signal.bits.len();
+        if width == 1 {
+            writeln!(
+                &mut out,
+                "$var wire 1 {} {} $end",
+                identifier(i),
+                signal.name
+            )?;
+        } else {
+            writeln!(
+                &mut out,
+                "$var wire {} {} {} [{}:0] $end",
+                width,
+                identifier(i),
+                signal.name,
+                width - 1
+            )?;
+        }
+    }
+    writeln!(&mut out, "$upscope $end")?;
+    writeln!(&mut out, "$enddefinitions $end")?;
+
+    // Value changes of each signal, indexed by time.
+    let mut events: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+    for (i, signal) in signals.iter().enumerate() {
+        let mut bit_events: BTreeMap<u64, Vec<(usize, Logic)>> = BTreeMap::new();
+        for (j, trace) in signal.bits.iter().enumerate() {
+            for (t, value) in bit_changes(*trace, vdd) {
+                bit_events.entry(t).or_default().push((j, value));
+            }
+        }
+
+        let width = signal.bits.len();
+        let mut current = vec![None; width];
+        let mut last = None;
+        for (t, changes) in bit_events {
+            for (j, value) in changes {
+                current[j] = value;
+            }
+            let value = if width == 1 {
+                format!("{}{}", logic_char(current[0]), identifier(i))
+            } else {
+                let bits = current.iter().rev().map(|&b| logic_char(b));
+                format!("b{} {}", bits.collect::<String>(), identifier(i))
+            };
+            if last.as_ref() != Some(&value) {
+                events.entry(t).or_default().push(value.clone());
+                last = Some(value);
+            }
+        }
+    }
+
+    for (t, changes) in events {
+        writeln!(&mut out, "#{t}")?;
+        for value in changes {
+            writeln!(&mut out, "{value}")?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// The testbench ports dumped to VCD files, along with their widths.
+fn ports(tb: &TbParams) -> Vec<(&str, usize)> {
+    let mut ports = vec![
+        (tb.clk_port.as_str(), 1),
+        (tb.write_enable_port.as_str(), 1),
+        (tb.addr_port.as_str(), tb.addr_width),
+        (tb.data_in_port.as_str(), tb.data_width),
+        (tb.data_out_port.as_str(), tb.data_width),
+    ];
+    if let Some(wmask_port) = tb.wmask_port.as_ref().filter(|_| tb.wmask_width > 1) {
+        ports.push((wmask_port.as_str(), tb.wmask_width));
+    }
+    ports
+}
+
+/// Saves the simulated clock, write enable, address, data, and write mask signals
+/// to a VCD file.
+pub fn save_sim_vcd(data: &TransientData, tb: &TbParams, path: impl AsRef<Path>) -> Result<()> {
+    let mut signals = Vec::new();
+    for (port, width) in ports(tb) {
+        let bits = (0..width)
+            .map(|i| -> Result<Trace> {
+                let name = if width == 1 {
+                    format!("v({port})")
+                } else {
+                    format!("v({})", bus_bit(port, i))
+                };
+                let values = data
+                    .signal(&name)
+                    .ok_or_else(|| anyhow!("Unable to find signal {}", name))?;
+                Ok(Trace {
+                    time: &data.time,
+                    values,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        signals.push(VcdSignal {
+            name: port.to_string(),
+            bits,
+        });
+    }
+
+    std::fs::write(path, generate_vcd(&signals, tb.vdd, &tb.sram_name)?)?;
+    Ok(())
+}
+
+/// Saves the stimulus applied by a testbench to a VCD file.
+///
+/// Only inputs are included, so the file can be generated without running a simulation.
+pub(crate) fn save_stimulus_vcd(
+    waveforms: &TbWaveforms,
+    tb: &TbParams,
+    path: impl AsRef<Path>,
+) -> Result<()> {
+    let split = |w: &Waveform| w.values().unzip::<f64, f64, Vec<_>, Vec<_>>();
+
+    let mut inputs = vec![
+        (tb.clk_port.as_str(), vec![split(&waveforms.clk)]),
+        (tb.write_enable_port.as_str(), vec![split(&waveforms.we)]),
+        (
+            tb.addr_port.as_str(),
+            waveforms.addr.iter().map(split).collect(),
+        ),
+        (
+            tb.data_in_port.as_str(),
+            waveforms.din.iter().map(split).collect(),
+        ),
+    ];
+    if let Some(wmask_port) = tb
+        .wmask_port
+        .as_ref()
+        .filter(|_| !waveforms.wmask.is_empty())
+    {
+        inputs.push((
+            wmask_port.as_str(),
+            waveforms.wmask.iter().map(split).collect(),
+        ));
+    }
+
+    let signals = inputs
+        .iter()
+        .map(|(name, bits)| VcdSignal {
+            name: name.to_string(),
+            bits: bits
+                .iter()
+                .map(|(time, values)| Trace { time, values })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    std::fs::write(path, generate_vcd(&signals, tb.vdd, &tb.sram_name)?)?;
+    Ok(())
+}
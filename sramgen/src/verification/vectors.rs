@@ -0,0 +1,124 @@
+//! Test vector files.
+//!
+//! A test vector file holds a [`TestCase`] and, optionally, the electrical conditions
+//! under which to apply it. Files ending in `.toml` are parsed as TOML;
+//! all other files are parsed as JSON.
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::verification::bit_signal::BitSignal;
+use crate::verification::patterns::PatternParams;
+use crate::verification::{Op, TbParams, TbParamsBuilder, TestCase};
+use crate::Result;
+
+/// The name of the file to which each testbench saves its test vectors.
+pub const TEST_VECTORS_FILE: &str = "test_vectors.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestVectors {
+    #[serde(flatten)]
+    pub test_case: TestCase,
+    /// Supply voltage.
+    ///
+    /// Overrides the supply voltage of every simulated corner.
+    #[serde(default)]
+    pub vdd: Option<f64>,
+    /// Rise time of clock and inputs.
+    #[serde(default)]
+    pub tr: Option<f64>,
+    /// Fall time of clock and inputs.
+    #[serde(default)]
+    pub tf: Option<f64>,
+    /// Capacitance on output pins.
+    #[serde(default)]
+    pub c_load: Option<f64>,
+}
+
+impl TestVectors {
+    /// The test vectors applied by the given testbench.
+    pub fn from_tb(tb: &TbParams) -> Self {
+        Self {
+            test_case: tb.test_case.clone(),
+            vdd: Some(tb.vdd),
+            tr: Some(tb.tr),
+            tf: Some(tb.tf),
+            c_load: Some(tb.c_load),
+        }
+    }
+
+    /// Applies the test case and any specified conditions to a testbench.
+    pub fn apply(&self, tb: &mut TbParamsBuilder) {
+        tb.test_case(self.test_case.clone());
+        if let Some(vdd) = self.vdd {
+            tb.vdd(vdd);
+        }
+        if let Some(tr) = self.tr {
+            tb.tr(tr);
+        }
+        if let Some(tf) = self.tf {
+            tb.tf(tf);
+        }
+        if let Some(c_load) = self.c_load {
+            tb.c_load(c_load);
+        }
+    }
+
+    /// Checks that the width of every signal in the test case matches the SRAM.
+    pub fn validate(&self, params: &PatternParams) -> Result<()> {
+        let check = |i: usize, name: &str, signal: &BitSignal, width: usize| {
+            if signal.width() != width {
+                bail!(
+                    "Operation {} has a {}-bit {}; expected {} bits",
+                    i,
+                    signal.width(),
+                    name,
+                    width
+                );
+            }
+            Ok(())
+        };
+
+        for (i, op) in self.test_case.ops.iter().enumerate() {
+            match op {
+                Op::Read { addr } => {
+                    check(i, "address", addr, params.addr_width)?;
+                }
+                Op::Write { addr, data } => {
+                    check(i, "address", addr, params.addr_width)?;
+                    check(i, "data word", data, params.data_width)?;
+                }
+                Op::WriteMasked { addr, data, mask } => {
+                    check(i, "address", addr, params.addr_width)?;
+                    check(i, "data word", data, params.data_width)?;
+                    check(i, "write mask", mask, params.wmask_width)?;
+                }
+                Op::Idle => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the test vectors as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().map(|ext| ext == "toml").unwrap_or(false)
+}
+
+pub fn parse_test_vectors(path: impl AsRef<Path>) -> Result<TestVectors> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Error reading test vectors from {:?}", path))?;
+    let vectors = if is_toml(path) {
+        toml::from_str(&contents)?
+    } else {
+        serde_json::from_str(&contents)?
+    };
+    Ok(vectors)
+}
@@ -0,0 +1,74 @@
+use anyhow::{bail, Context};
+use psf_ascii::parser::transient::TransientData;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::verification::ngspice::parse_rawfile;
+use crate::verification::{Simulator, SimulatorKind};
+use crate::Result;
+
+/// The open source Xyce simulator.
+pub struct Xyce;
+
+impl Simulator for Xyce {
+    fn kind(&self) -> SimulatorKind {
+        SimulatorKind::Xyce
+    }
+
+    fn run(&self, work_dir: &Path, netlist_path: &Path) -> Result<TransientData> {
+        run_xyce(&XyceParams {
+            work_dir: work_dir.to_path_buf(),
+            spice_path: netlist_path.to_path_buf(),
+        })
+    }
+}
+
+pub struct XyceParams {
+    pub work_dir: PathBuf,
+    pub spice_path: PathBuf,
+}
+
+pub struct XyceGeneratedPaths {
+    pub raw_output_path: PathBuf,
+    pub log_path: PathBuf,
+    pub stdout_path: PathBuf,
+    pub stderr_path: PathBuf,
+}
+
+pub fn run_xyce(params: &XyceParams) -> Result<TransientData> {
+    let paths = generate_paths(params);
+
+    let out_file = std::fs::File::create(&paths.stdout_path)?;
+    let err_file = std::fs::File::create(&paths.stderr_path)?;
+
+    // Xyce writes every solution variable to the rawfile;
+    // `-a` selects the ASCII rawfile format.
+    let status = Command::new("Xyce")
+        .arg("-r")
+        .arg(&paths.raw_output_path)
+        .arg("-a")
+        .arg("-l")
+        .arg(&paths.log_path)
+        .arg(&params.spice_path)
+        .stdout(out_file)
+        .stderr(err_file)
+        .current_dir(&params.work_dir)
+        .status()
+        .with_context(|| "Failed to start Xyce")?;
+
+    if !status.success() {
+        bail!("Xyce exited unsuccessfully");
+    }
+
+    let raw = std::fs::read_to_string(&paths.raw_output_path)?;
+    parse_rawfile(&raw)
+}
+
+fn generate_paths(params: &XyceParams) -> XyceGeneratedPaths {
+    XyceGeneratedPaths {
+        raw_output_path: params.work_dir.join("xyce.raw"),
+        log_path: params.work_dir.join("xyce.log"),
+        stdout_path: params.work_dir.join("xyce.out"),
+        stderr_path: params.work_dir.join("xyce.err"),
+    }
+}
@@ -0,0 +1,25 @@
+# Runs DRC on a GDS layout, writing one violation per line to the report as
+# tab-separated `llx lly urx ury rule` values, in microns.
+
+gds read {{ layout_path }}
+load {{ cell_name }}
+select top cell
+drc euclidean on
+drc style drc(full)
+drc check
+drc catchup
+
+set oscale [cif scale out]
+set fout [open {{ report_path }} w]
+foreach {why boxes} [drc listall why] {
+    foreach box $boxes {
+        set llx [expr {$oscale * [lindex $box 0]}]
+        set lly [expr {$oscale * [lindex $box 1]}]
+        set urx [expr {$oscale * [lindex $box 2]}]
+        set ury [expr {$oscale * [lindex $box 3]}]
+        puts $fout "$llx\t$lly\t$urx\t$ury\t$why"
+    }
+}
+close $fout
+
+quit -noprompt
@@ -0,0 +1,11 @@
+# Extracts a SPICE netlist from a GDS layout for LVS.
+
+gds read {{ layout_path }}
+load {{ cell_name }}
+select top cell
+extract path {{ extract_dir }}
+extract all
+ext2spice lvs
+ext2spice -p {{ extract_dir }} -o {{ netlist_path }}
+
+quit -noprompt